@@ -1,4 +1,9 @@
-// extern crate bindgen;
+// The vendored build is unused when linking a system WFA2-lib, and nothing is
+// built without the native backend
+#![cfg_attr(
+    any(feature = "system-wfa2", not(feature = "native")),
+    allow(dead_code)
+)]
 
 use std::{env, path::PathBuf, process::Command};
 
@@ -18,30 +23,133 @@ impl BuildPaths {
     }
 }
 
-fn build_wfa() -> Result<(), Box<dyn std::error::Error>> {
+// Subdirectories of WFA2-lib whose C sources make up libwfa
+const WFA_SOURCE_DIRS: [&str; 4] = ["alignment", "system", "utils", "wavefront"];
+
+// Returns whether the library was built with OpenMP
+fn build_wfa() -> Result<bool, Box<dyn std::error::Error>> {
     let paths = BuildPaths::new();
 
-    // Check if WFA2-lib exists and has Makefile
-    if !paths.wfa_src.join("Makefile").exists() {
-        return Err("WFA2-lib/Makefile not found. Make sure the submodule is initialized.".into());
+    // Check if the WFA2-lib sources are present
+    if !paths.wfa_src.join("wavefront").is_dir() {
+        return Err("WFA2-lib sources not found. Make sure the submodule is initialized.".into());
     }
 
     let target = env::var("TARGET").unwrap_or_default();
+    // The build script runs on the host, so `cfg!(target_env)` would describe
+    // the host; cargo passes the target's configuration in the environment
+    let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default();
+    let msvc = target_env == "msvc";
+    let musl = target_env == "musl";
     let portable = env::var("PORTABLE").unwrap_or_default() == "1";
+    let native = !portable && target.contains("x86_64") && !target.contains("apple") && !msvc;
+    // OpenMP lets a single alignment use several threads. Apple clang rejects
+    // the bare -fopenmp flag and MSVC has no libgomp (its /openmp is OpenMP
+    // 2.0 only), so those builds stay sequential, as do targets without
+    // libgomp (feature `no-openmp`). MinGW builds use libgomp. musl toolchains
+    // ship no static libgomp, so fully static musl builds skip OpenMP too.
+    let openmp = !target.contains("apple")
+        && !msvc
+        && !musl
+        && env::var_os("CARGO_FEATURE_NO_OPENMP").is_none();
+
+    if cfg!(feature = "make-build") && msvc {
+        return Err(
+            "The make-build feature needs a GNU toolchain; MSVC targets use the cc build.".into(),
+        );
+    }
+    let (cc, cc_flags) = if cfg!(feature = "make-build") {
+        build_wfa_make(&paths, &target, native, openmp)?
+    } else {
+        build_wfa_cc(&paths, native, openmp)?
+    };
+
+    // Record how the native library was built for lib_wfa2::build_info()
+    println!("cargo:rustc-env=LIB_WFA2_CC={cc}");
+    println!("cargo:rustc-env=LIB_WFA2_CC_FLAGS={cc_flags}");
+    println!("cargo:rustc-env=LIB_WFA2_OPENMP={}", u8::from(openmp));
+    println!("cargo:rustc-env=LIB_WFA2_TARGET={target}");
+    // Optional components are compiled whenever their sources are present
+    let has_source = |file: &str| paths.wfa_src.join("wavefront").join(file).is_file();
+    println!(
+        "cargo:rustc-env=LIB_WFA2_BIWFA={}",
+        u8::from(has_source("wavefront_bialign.c"))
+    );
+    println!(
+        "cargo:rustc-env=LIB_WFA2_PLOT={}",
+        u8::from(has_source("wavefront_plot.c"))
+    );
+
+    Ok(openmp)
+}
+
+// Compile the WFA2-lib sources with the cc crate, which picks the compiler and
+// archiver for the target (so cross-compilation works) and emits the link
+// directives for the static library. Returns the compiler and extra flags.
+fn build_wfa_cc(
+    paths: &BuildPaths,
+    native: bool,
+    openmp: bool,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let mut build = cc::Build::new();
+    build.include(&paths.wfa_src).opt_level(3).warnings(false);
+    for dir in WFA_SOURCE_DIRS {
+        let dir = paths.wfa_src.join(dir);
+        let mut sources = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "c") {
+                sources.push(path);
+            }
+        }
+        // Sorted, so the archive does not depend on directory order
+        sources.sort();
+        build.files(sources);
+        println!("cargo:rerun-if-changed={}", dir.display());
+    }
+
+    let mut cc_flags = vec!["-O3"];
+    if native {
+        build.flag_if_supported("-march=native");
+        cc_flags.push("-march=native");
+    }
+    if openmp {
+        build.flag("-fopenmp").define("WFA_PARALLEL", None);
+        cc_flags.push("-fopenmp");
+    }
+    let compiler = build.get_compiler().path().display().to_string();
+    build.try_compile("wfa")?;
+    Ok((compiler, cc_flags.join(" ")))
+}
+
+// Build libwfa with WFA2-lib's own Makefile (feature `make-build`), as before
+// the cc-crate build. Needs make and a host gcc or clang.
+fn build_wfa_make(
+    paths: &BuildPaths,
+    target: &str,
+    native: bool,
+    openmp: bool,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    if !paths.wfa_src.join("Makefile").exists() {
+        return Err("WFA2-lib/Makefile not found. Make sure the submodule is initialized.".into());
+    }
+
     let mut cc_flags = String::from("-Wall -g -fPIE -O3");
-    if !portable && target.contains("x86_64") && !target.contains("apple") {
+    if native {
         cc_flags.push_str(" -march=native");
     }
 
     let (cc, cxx) = if target.contains("apple") {
         ("clang", "clang++")
+    } else if target.contains("musl") {
+        // musl-tools' wrapper; g++ is only named for the Makefile
+        ("musl-gcc", "g++")
     } else {
         ("gcc", "g++")
     };
 
-    // Build only the static library. WFA2-lib's tools/examples pull OpenMP into
-    // the build; the Rust bindings do not need them and Apple clang rejects the
-    // tools' bare -fopenmp flag.
+    // Build only the static library; the Rust bindings do not need WFA2-lib's
+    // tools and examples.
     let output = Command::new("make")
         .args([
             "clean",
@@ -49,7 +157,11 @@ fn build_wfa() -> Result<(), Box<dyn std::error::Error>> {
             "lib_wfa",
             "BUILD_TOOLS=0",
             "BUILD_EXAMPLES=0",
-            "BUILD_WFA_PARALLEL=0",
+            if openmp {
+                "BUILD_WFA_PARALLEL=1"
+            } else {
+                "BUILD_WFA_PARALLEL=0"
+            },
             &format!("CC={cc}"),
             &format!("CXX={cxx}"),
             &format!("CC_FLAGS={cc_flags}"),
@@ -63,56 +175,178 @@ fn build_wfa() -> Result<(), Box<dyn std::error::Error>> {
         return Err(format!("Make failed:\nSTDOUT:\n{stdout}\nSTDERR:\n{stderr}").into());
     }
 
-    Ok(())
+    // Link the WFA library
+    println!("cargo:rustc-link-lib=static=wfa");
+    println!(
+        "cargo:rustc-link-search=native={}",
+        paths.wfa_lib_dir().display()
+    );
+    println!(
+        "cargo:rerun-if-changed={}",
+        paths.wfa_lib_dir().join("libwfa.a").display()
+    );
+
+    Ok((cc.to_string(), cc_flags))
 }
 
-fn setup_linking() {
+fn emit_version_info() {
     let paths = BuildPaths::new();
 
-    // Link the WFA library
-    println!("cargo:rustc-link-lib=static=wfa");
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(&paths.wfa_src)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
 
-    // Set library search path
+    // Only trust git when WFA2-lib is a checkout of its own (not the parent repo)
+    let (version, commit) = if paths.wfa_src.join(".git").exists() {
+        (
+            git(&["describe", "--tags", "--always"]),
+            git(&["rev-parse", "HEAD"]),
+        )
+    } else {
+        (None, None)
+    };
     println!(
-        "cargo:rustc-link-search=native={}",
-        paths.wfa_lib_dir().display()
+        "cargo:rustc-env=LIB_WFA2_WFA2_VERSION={}",
+        version.unwrap_or_else(|| "unknown".to_string())
     );
+    println!(
+        "cargo:rustc-env=LIB_WFA2_WFA2_COMMIT={}",
+        commit.unwrap_or_else(|| "unknown".to_string())
+    );
+    emit_bindgen_version();
+}
+
+fn emit_bindgen_version() {
+    // The checked-in bindings record the bindgen release that generated them
+    let bindgen = std::fs::read_to_string("src/bindings_wfa.rs")
+        .ok()
+        .and_then(|s| {
+            s.lines()
+                .next()
+                .and_then(|line| line.split("rust-bindgen ").nth(1))
+                .map(|v| v.trim_end_matches("*/").trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=LIB_WFA2_BINDGEN_VERSION={bindgen}");
+    println!("cargo:rerun-if-changed=src/bindings_wfa.rs");
+}
+
+fn setup_linking(openmp: bool) {
+    // The WFA library itself is linked by build_wfa_cc or build_wfa_make
+    if openmp {
+        println!("cargo:rustc-link-lib=gomp");
+    }
 
     // Rerun if WFA library changes
     println!("cargo:rerun-if-changed=WFA2-lib");
+}
+
+// WFA2-lib release series the checked-in bindings were generated from. A
+// system library must be of the same major version and at least this release.
+#[cfg(feature = "system-wfa2")]
+const BINDINGS_WFA2_VERSION: &str = "2.3";
+
+// Link an installed WFA2-lib found via pkg-config instead of building the
+// vendored sources (feature `system-wfa2`)
+#[cfg(feature = "system-wfa2")]
+fn link_system_wfa() -> Result<(), Box<dyn std::error::Error>> {
+    let library = pkg_config::Config::new()
+        .atleast_version(BINDINGS_WFA2_VERSION)
+        .probe("wfa2lib")?;
+    let major = |version: &str| version.split('.').next().unwrap_or("").to_string();
+    if major(&library.version) != major(BINDINGS_WFA2_VERSION) {
+        return Err(format!(
+            "system WFA2-lib {} does not match the bindings (WFA2-lib {})",
+            library.version, BINDINGS_WFA2_VERSION
+        )
+        .into());
+    }
+
+    // Record what is known about the library for lib_wfa2::build_info()
+    let openmp = library.libs.iter().any(|lib| lib == "gomp");
+    println!("cargo:rustc-env=LIB_WFA2_WFA2_VERSION={}", library.version);
+    println!("cargo:rustc-env=LIB_WFA2_CC=system");
+    println!("cargo:rustc-env=LIB_WFA2_OPENMP={}", u8::from(openmp));
     println!(
-        "cargo:rerun-if-changed={}/libwfa.a",
-        paths.wfa_lib_dir().display()
+        "cargo:rustc-env=LIB_WFA2_TARGET={}",
+        env::var("TARGET").unwrap_or_default()
     );
+    emit_bindgen_version();
+    Ok(())
+}
 
-    // Generate bindings
-    // let bindings = bindgen::Builder::default()
-    //     // Generate bindings for this header file.
-    //     // .header("../wfa2/wavefront/wavefront_align.h")
-    //     .header("WFA2-lib/wavefront/wavefront_align.h")
-    //     // Add this directory to the include path to find included header files.
-    //     // .clang_arg("-I../wfa2")
-    //     .clang_arg(format!("-I{}", build_paths.wfa_src().display()))
-    //     // Generate bindings for all functions starting with `wavefront_`.
-    //     .allowlist_function("wavefront_.*")
-    //     // Generate bindings for all variables starting with `wavefront_`.
-    //     .allowlist_var("wavefront_.*")
-    //     // Invalidate the built crate whenever any of the included header files
-    //     // changed.
-    //     .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
-    //     // Finish the builder and generate the bindings.
-    //     .generate()
-    //     // Unwrap the Result and panic on failure.
-    //     .expect("Unable to generate bindings");
-    // // Write the bindings to the $OUT_DIR/bindings_wfa.rs file.
-    // bindings
-    //     .write_to_file(build_paths.out_dir().join("bindings_wfa.rs"))
-    //     .expect("Couldn't write bindings!");
+#[cfg(feature = "system-wfa2")]
+fn main() {
+    if let Err(e) = link_system_wfa() {
+        panic!("Failed to find a system WFA2-lib: {e}");
+    }
 }
 
+// Regenerate the bindings from the vendored headers (feature
+// `generate-bindings`) and check them against the checked-in ones, so that a
+// WFA2-lib update that changes the ABI fails the build instead of silently
+// mismatching.
+#[cfg(feature = "generate-bindings")]
+fn check_bindings() -> Result<(), Box<dyn std::error::Error>> {
+    let paths = BuildPaths::new();
+    let generated = PathBuf::from(env::var("OUT_DIR")?).join("bindings_wfa.rs");
+    bindgen::Builder::default()
+        .header(
+            paths
+                .wfa_src
+                .join("wavefront")
+                .join("wavefront_align.h")
+                .display()
+                .to_string(),
+        )
+        .clang_arg(format!("-I{}", paths.wfa_src.display()))
+        .allowlist_function("wavefront_.*")
+        .allowlist_var("wavefront_.*")
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .generate()?
+        .write_to_file(&generated)?;
+
+    // Ignore the banner, which names the bindgen release
+    let body = |text: String| {
+        text.lines()
+            .filter(|line| !line.starts_with("/* automatically generated"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let vendored = body(std::fs::read_to_string("src/bindings_wfa.rs")?);
+    if body(std::fs::read_to_string(&generated)?) != vendored {
+        return Err(format!(
+            "the WFA2-lib headers no longer match src/bindings_wfa.rs; \
+             review the regenerated bindings in {} and copy them over src/bindings_wfa.rs",
+            generated.display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "native", not(feature = "system-wfa2")))]
 fn main() {
-    if let Err(e) = build_wfa() {
-        panic!("Failed to build WFA2-lib: {e}");
+    let openmp = match build_wfa() {
+        Ok(openmp) => openmp,
+        Err(e) => panic!("Failed to build WFA2-lib: {e}"),
+    };
+    #[cfg(feature = "generate-bindings")]
+    if let Err(e) = check_bindings() {
+        panic!("Bindings out of date: {e}");
     }
-    setup_linking();
+    emit_version_info();
+    setup_linking(openmp);
 }
+
+// Only the pure-Rust modules are compiled (`--no-default-features`), so there
+// is no native library to build or link
+#[cfg(not(feature = "native"))]
+fn main() {}
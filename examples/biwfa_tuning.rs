@@ -0,0 +1,62 @@
+use lib_wfa2::affine_wavefront::{AffineWavefrontsBuilder, BiwfaFallback, MemoryMode};
+use std::time::Instant;
+
+// Pseudo-random sequence with a mutated copy (~`divergence` substitutions)
+fn pair(len: usize, divergence: f64, seed: u64) -> (Vec<u8>, Vec<u8>) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        state >> 33
+    };
+    let target: Vec<u8> = (0..len).map(|_| b"ACGT"[(next() % 4) as usize]).collect();
+    let query = target
+        .iter()
+        .map(|&base| {
+            if (next() % 10_000) as f64 / 10_000.0 < divergence {
+                b"ACGT"[(next() % 4) as usize]
+            } else {
+                base
+            }
+        })
+        .collect();
+    (query, target)
+}
+
+pub fn main() {
+    println!("bi-WFA fallback tuning\n");
+
+    // Mixed workload: many short pairs and a few long ones
+    let mut pairs: Vec<_> = (0..200).map(|i| pair(1_000, 0.02, i)).collect();
+    pairs.extend((0..3).map(|i| pair(100_000, 0.02, 1000 + i)));
+
+    let limits = [None, Some(1u64 << 20), Some(16 << 20), Some(256 << 20)];
+    for limit in limits {
+        let mut builder = AffineWavefrontsBuilder::new()
+            .penalties(0, 4, 6, 2)
+            .memory_mode(MemoryMode::Ultralow);
+        if let Some(max_memory) = limit {
+            builder = builder.biwfa_fallback(BiwfaFallback {
+                max_memory,
+                memory_mode: MemoryMode::High,
+                divergence: 0.02,
+            });
+        }
+        let aligner = builder.build().expect("valid configuration");
+
+        let start = Instant::now();
+        let mut fallbacks = 0;
+        for (query, target) in &pairs {
+            let _ = aligner.align(query, target);
+            fallbacks += usize::from(aligner.last_used_fallback());
+        }
+        let label = match limit {
+            None => "bi-WFA only".to_string(),
+            Some(bytes) => format!("fallback up to {} MiB", bytes >> 20),
+        };
+        println!(
+            "{label:>24}: {:>8.1} ms, {fallbacks}/{} pairs on the fallback",
+            start.elapsed().as_secs_f64() * 1000.0,
+            pairs.len()
+        );
+    }
+}
@@ -10,7 +10,7 @@ pub fn main() {
     // Text means reference
     let text = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
 
-    aligner.align(pattern, text);
+    aligner.align(pattern, text).ok().expect("alignment failed");
 
     println!("Pattern: {}", String::from_utf8_lossy(pattern));
     println!("Text:    {}\n", String::from_utf8_lossy(text));
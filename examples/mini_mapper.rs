@@ -0,0 +1,32 @@
+use lib_wfa2::mapper::{Mapper, MapperOptions, ReferenceIndex};
+
+pub fn main() {
+    println!("Mini mapper\n");
+
+    // A small pseudo-random reference
+    let mut state = 42u64;
+    let reference: Vec<u8> = (0..5000)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            b"ACGT"[(state >> 62) as usize]
+        })
+        .collect();
+
+    let options = MapperOptions::default();
+    let mut index = ReferenceIndex::new(options.k);
+    index.add("chr1", &reference);
+    let mut mapper = Mapper::new(index, options);
+
+    // A read from the reference with one substitution and one deletion
+    let mut read = reference[1200..1500].to_vec();
+    read[100] = if read[100] == b'A' { b'C' } else { b'A' };
+    read.remove(200);
+
+    match mapper.map("read1", &read) {
+        Some(mapping) => {
+            println!("PAF: {}", mapping.to_paf());
+            println!("SAM: {}", mapping.to_sam(&read));
+        }
+        None => println!("read1 is unmapped"),
+    }
+}
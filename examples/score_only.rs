@@ -0,0 +1,59 @@
+use lib_wfa2::affine_wavefront::AffineWavefrontsBuilder;
+use lib_wfa2::score_only::ScoreAligner;
+use std::time::Instant;
+
+// Pseudo-random sequences sharing a common ancestor (~`divergence` substitutions)
+fn family(count: usize, len: usize, divergence: f64) -> Vec<Vec<u8>> {
+    let mut state = 42u64;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        state >> 33
+    };
+    let ancestor: Vec<u8> = (0..len).map(|_| b"ACGT"[(next() % 4) as usize]).collect();
+    (0..count)
+        .map(|_| {
+            ancestor
+                .iter()
+                .map(|&base| {
+                    if (next() % 10_000) as f64 / 10_000.0 < divergence {
+                        b"ACGT"[(next() % 4) as usize]
+                    } else {
+                        base
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn main() {
+    let sequences = family(40, 5_000, 0.05);
+    let config = AffineWavefrontsBuilder::new().penalties(0, 4, 6, 2);
+
+    // All-vs-all with full alignments
+    let full = config.clone().build().expect("valid configuration");
+    let start = Instant::now();
+    let mut full_total = 0i64;
+    for (i, a) in sequences.iter().enumerate() {
+        for b in &sequences[i + 1..] {
+            full.align(a, b).ok().expect("alignment failed");
+            full_total += i64::from(full.score());
+        }
+    }
+    let full_time = start.elapsed();
+
+    // The same pairs, score only
+    let scorer = ScoreAligner::new(config).expect("valid configuration");
+    let start = Instant::now();
+    let mut score_total = 0i64;
+    for (i, a) in sequences.iter().enumerate() {
+        for b in &sequences[i + 1..] {
+            score_total += i64::from(scorer.align_score(a, b).expect("alignment failed"));
+        }
+    }
+    let score_time = start.elapsed();
+
+    assert_eq!(full_total, score_total);
+    println!("Full alignment: {full_time:?}");
+    println!("Score only:     {score_time:?}");
+}
@@ -35,7 +35,8 @@ fn main() {
         .memory_mode(MemoryMode::Ultralow)
         .alignment_scope(AlignmentScope::Alignment)
         .heuristic(HeuristicStrategy::None)
-        .build();
+        .build()
+        .expect("valid configuration");
     
     println!("Memory mode: {:?}", aligner3.get_memory_mode());
     println!("Distance metric: {:?}", aligner3.get_distance_metric());
@@ -52,7 +53,8 @@ fn main() {
         let aligner = AffineWavefrontsBuilder::new()
             .penalties(0, 4, 6, 2)
             .memory_mode(mode.clone())
-            .build();
+            .build()
+            .expect("valid configuration");
         
         println!("Created aligner with {:?} mode", mode);
         println!("  Verified mode: {:?}", aligner.get_memory_mode());
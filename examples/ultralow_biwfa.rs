@@ -43,7 +43,8 @@ pub fn main() {
         .memory_mode(MemoryMode::Ultralow)
         .alignment_scope(AlignmentScope::Alignment)
         .heuristic(HeuristicStrategy::None)
-        .build();
+        .build()
+        .expect("valid configuration");
     
     let _status3 = aligner3.align(pattern, text);
     println!("Score with builder: {}", aligner3.score());
@@ -0,0 +1,132 @@
+#ifndef LIB_WFA2_H
+#define LIB_WFA2_H
+
+/* Generated by cbindgen from src/capi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define WFA2_STATUS_COMPLETED 0
+
+#define WFA2_STATUS_PARTIAL 1
+
+#define WFA2_STATUS_MAX_STEPS_REACHED -100
+
+#define WFA2_STATUS_OOM -200
+
+#define WFA2_STATUS_UNATTAINABLE -300
+
+/**
+ * Any other outcome, including a null aligner.
+ */
+#define WFA2_STATUS_UNDEFINED -1
+
+#define WFA2_MEMORY_HIGH 0
+
+#define WFA2_MEMORY_MEDIUM 1
+
+#define WFA2_MEMORY_LOW 2
+
+#define WFA2_MEMORY_ULTRALOW 3
+
+/**
+ * Opaque aligner handle.
+ */
+typedef struct Wfa2Aligner Wfa2Aligner;
+
+/**
+ * Aligner configuration. Penalties follow the Rust API: `match_score` is 0
+ * or negative (a bonus), the others are positive. The second gap piece is
+ * used (dual-affine penalties) when both `gap_opening2` and
+ * `gap_extension2` are positive.
+ */
+typedef struct Wfa2Config {
+  int32_t match_score;
+  int32_t mismatch;
+  int32_t gap_opening1;
+  int32_t gap_extension1;
+  int32_t gap_opening2;
+  int32_t gap_extension2;
+  /**
+   * One of the `WFA2_MEMORY_*` constants.
+   */
+  int32_t memory_mode;
+  /**
+   * Maximum alignment steps, or 0 for WFA2's default.
+   */
+  int32_t max_alignment_steps;
+} Wfa2Config;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Default configuration: gap-affine penalties 0, 4, 6, 2 and high memory
+ * mode.
+ */
+struct Wfa2Config wfa2_config_default(void);
+
+/**
+ * Create an aligner, or return null if the configuration is invalid.
+ *
+ * # Safety
+ *
+ * `config` must be null or point to a valid `Wfa2Config`.
+ */
+struct Wfa2Aligner *wfa2_aligner_new(const struct Wfa2Config *config);
+
+/**
+ * Release an aligner. Null is ignored.
+ *
+ * # Safety
+ *
+ * `aligner` must be null or come from [`wfa2_aligner_new`], and must not be
+ * used afterwards.
+ */
+void wfa2_aligner_free(struct Wfa2Aligner *aligner);
+
+/**
+ * Align `pattern` against `text` and return a `WFA2_STATUS_*` code.
+ *
+ * # Safety
+ *
+ * `aligner` must be null or a live aligner, and each sequence pointer must
+ * be valid for reads of its length (it may be null if the length is 0).
+ */
+int32_t wfa2_align(struct Wfa2Aligner *aligner,
+                   const uint8_t *pattern,
+                   size_t pattern_len,
+                   const uint8_t *text,
+                   size_t text_len);
+
+/**
+ * Score of the last alignment (0 for a null aligner).
+ *
+ * # Safety
+ *
+ * `aligner` must be null or a live aligner.
+ */
+int32_t wfa2_score(const struct Wfa2Aligner *aligner);
+
+/**
+ * Compact CIGAR of the last alignment, one `M`, `X`, `I` or `D` per
+ * column and not NUL-terminated; its length is stored in `len`. The
+ * pointer stays valid until the next alignment or until the aligner is
+ * freed. Returns null (and length 0) for a null aligner.
+ *
+ * # Safety
+ *
+ * `aligner` must be null or a live aligner, and `len` must be null or
+ * valid for writes.
+ */
+const uint8_t *wfa2_cigar(const struct Wfa2Aligner *aligner, size_t *len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LIB_WFA2_H */
@@ -1,9 +1,23 @@
 use wfa::wavefront_aligner_set_max_alignment_steps;
 
+use crate::alphabet::EncodedSequence;
 use crate::bindings::*;
+use crate::cigar::{
+    btop, cigar_runs, cigar_string, format_alignment, format_alignment_with, sam_cigar,
+    score_cigar, strip_gap_ends, CigarRuns, CigarStats, FormatOptions,
+};
+use crate::scheduler::estimate_alignment_memory;
+use crate::trim::{trim_low_identity_ends, TrimmedCigar};
 use core::slice;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+pub use crate::types::{
+    Affine2pPenalties, AffinePenalties, AlignError, Alignment, AlignmentStatus, PenaltyError,
+};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DistanceMetric {
     Indel,
     Edit,
@@ -11,8 +25,21 @@ pub enum DistanceMetric {
     GapAffine2p,
 }
 
+// Names as accepted by the `wfa2` command line, e.g. `affine2p`
+impl std::fmt::Display for DistanceMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Indel => "indel",
+            Self::Edit => "edit",
+            Self::GapAffine => "affine",
+            Self::GapAffine2p => "affine2p",
+        })
+    }
+}
+
 /// Backwards-compatible distance configuration used by older callers.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Distance {
     Edit,
     GapAffine {
@@ -70,6 +97,132 @@ impl Distance {
         aligner
     }
 
+    /// Greatest common divisor of all penalties. Edit distance (and a scheme
+    /// with only zero penalties) reports 1.
+    pub fn penalty_gcd(&self) -> i32 {
+        fn gcd(a: i32, b: i32) -> i32 {
+            let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            a as i32
+        }
+
+        let g = match *self {
+            Self::Edit => 1,
+            Self::GapAffine {
+                mismatch,
+                gap_opening,
+                gap_extension,
+            } => [mismatch, gap_opening, gap_extension]
+                .into_iter()
+                .fold(0, gcd),
+            Self::GapAffine2p {
+                mismatch,
+                gap_opening1,
+                gap_extension1,
+                gap_opening2,
+                gap_extension2,
+            } => [
+                mismatch,
+                gap_opening1,
+                gap_extension1,
+                gap_opening2,
+                gap_extension2,
+            ]
+            .into_iter()
+            .fold(0, gcd),
+        };
+        g.max(1)
+    }
+
+    /// Divide every penalty by the GCD of the scheme. The optimal alignment is
+    /// unchanged and scores shrink by a factor of `penalty_gcd()`; multiply a
+    /// score computed with the reduced scheme by that factor to recover the
+    /// original one. Since WFA runtime grows with the alignment score, the
+    /// reduced scheme is typically much faster.
+    pub fn reduced(&self) -> Self {
+        let g = self.penalty_gcd();
+        match *self {
+            Self::Edit => Self::Edit,
+            Self::GapAffine {
+                mismatch,
+                gap_opening,
+                gap_extension,
+            } => Self::GapAffine {
+                mismatch: mismatch / g,
+                gap_opening: gap_opening / g,
+                gap_extension: gap_extension / g,
+            },
+            Self::GapAffine2p {
+                mismatch,
+                gap_opening1,
+                gap_extension1,
+                gap_opening2,
+                gap_extension2,
+            } => Self::GapAffine2p {
+                mismatch: mismatch / g,
+                gap_opening1: gap_opening1 / g,
+                gap_extension1: gap_extension1 / g,
+                gap_opening2: gap_opening2 / g,
+                gap_extension2: gap_extension2 / g,
+            },
+        }
+    }
+
+    /// Multiply every penalty by `factor`. Fails if the factor is not positive
+    /// or a penalty would overflow. Edit distance cannot be scaled.
+    pub fn scaled(&self, factor: i32) -> Result<Self, String> {
+        if factor <= 0 {
+            return Err(format!("Scaling factor must be positive, got {factor}"));
+        }
+        let scale = |p: i32| {
+            p.checked_mul(factor)
+                .ok_or_else(|| format!("Penalty {p} overflows when scaled by {factor}"))
+        };
+        match *self {
+            Self::Edit if factor == 1 => Ok(Self::Edit),
+            Self::Edit => Err("Edit distance penalties cannot be scaled".to_string()),
+            Self::GapAffine {
+                mismatch,
+                gap_opening,
+                gap_extension,
+            } => Ok(Self::GapAffine {
+                mismatch: scale(mismatch)?,
+                gap_opening: scale(gap_opening)?,
+                gap_extension: scale(gap_extension)?,
+            }),
+            Self::GapAffine2p {
+                mismatch,
+                gap_opening1,
+                gap_extension1,
+                gap_opening2,
+                gap_extension2,
+            } => Ok(Self::GapAffine2p {
+                mismatch: scale(mismatch)?,
+                gap_opening1: scale(gap_opening1)?,
+                gap_extension1: scale(gap_extension1)?,
+                gap_opening2: scale(gap_opening2)?,
+                gap_extension2: scale(gap_extension2)?,
+            }),
+        }
+    }
+
+    /// Dual-affine preset for cDNA-to-genome alignment. The second gap piece
+    /// has a high opening and a minimal extension, so intron-scale deletions
+    /// cost little more than their opening once longer than ~60 bases, while
+    /// short indels are still charged by the first piece. Annotate the
+    /// resulting long gaps with [`crate::introns::annotate_introns`].
+    pub fn long_gap_intron() -> Self {
+        Self::GapAffine2p {
+            mismatch: 8,
+            gap_opening1: 12,
+            gap_extension1: 4,
+            gap_opening2: 200,
+            gap_extension2: 1,
+        }
+    }
+
     pub fn to_u8(&self) -> u8 {
         match self {
             Self::Edit => 0,
@@ -98,7 +251,32 @@ impl Distance {
     }
 }
 
+// Penalties in WFA2's order, e.g. `affine(0,4,6,2)`
+impl std::fmt::Display for Distance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Edit => f.write_str("edit"),
+            Self::GapAffine {
+                mismatch,
+                gap_opening,
+                gap_extension,
+            } => write!(f, "affine(0,{mismatch},{gap_opening},{gap_extension})"),
+            Self::GapAffine2p {
+                mismatch,
+                gap_opening1,
+                gap_extension1,
+                gap_opening2,
+                gap_extension2,
+            } => write!(
+                f,
+                "affine2p(0,{mismatch},{gap_opening1},{gap_extension1},{gap_opening2},{gap_extension2})"
+            ),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeuristicStrategy {
     None,
     BandedStatic {
@@ -130,7 +308,190 @@ pub enum HeuristicStrategy {
     },
 }
 
+impl HeuristicStrategy {
+    /// A reasonable heuristic for aligning sequences of about `seq_len` bases
+    /// that differ at roughly `expected_divergence` (0.0 - 1.0) of positions.
+    /// Exact alignment is cheap for short or near-identical pairs, so those
+    /// get `None`. Otherwise WF-adaptive pruning is used, with thresholds
+    /// that grow with the expected number of differences `d`: diagonals are
+    /// pruned once the wavefront is `d / 10` long (10 - 256) and they lag
+    /// `d / 2` (50 - 4096) behind the best one.
+    pub fn auto_for(expected_divergence: f64, seq_len: usize) -> Self {
+        let differences = (seq_len as f64 * expected_divergence.clamp(0.0, 1.0)).ceil();
+        if seq_len <= 1000 || differences < 1.0 {
+            return Self::None;
+        }
+        Self::WFAdaptive {
+            min_wavefront_length: (differences / 10.0).clamp(10.0, 256.0) as i32,
+            max_distance_threshold: (differences / 2.0).clamp(50.0, 4096.0) as i32,
+            score_steps: 1,
+        }
+    }
+
+    /// Static band for aligning a `pattern_len` pattern against a `text_len`
+    /// text: it spans the diagonals from the start (0) to the end
+    /// (`text_len - pattern_len`) of the alignment, widened on both sides by
+    /// `divergence` (0.0 - 1.0) of the longer sequence.
+    pub fn banded_for(pattern_len: usize, text_len: usize, divergence: f64) -> Self {
+        let longest = pattern_len.max(text_len) as f64;
+        let slack = (longest * divergence.clamp(0.0, 1.0)).ceil() as i64;
+        let end = text_len as i64 - pattern_len as i64;
+        let limit = |k: i64| k.clamp(i32::MIN.into(), i32::MAX.into()) as i32;
+        Self::BandedStatic {
+            band_min_k: limit(end.min(0) - slack),
+            band_max_k: limit(end.max(0) + slack),
+        }
+    }
+}
+
+// The `wfa2` command line's `NAME[:ARG,ARG,...]` syntax, e.g. `xdrop:100,1`
+impl std::fmt::Display for HeuristicStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::BandedStatic {
+                band_min_k,
+                band_max_k,
+            } => write!(f, "banded-static:{band_min_k},{band_max_k}"),
+            Self::BandedAdaptive {
+                band_min_k,
+                band_max_k,
+                score_steps,
+            } => write!(f, "banded-adaptive:{band_min_k},{band_max_k},{score_steps}"),
+            Self::WFAdaptive {
+                min_wavefront_length,
+                max_distance_threshold,
+                score_steps,
+            } => write!(
+                f,
+                "wf-adaptive:{min_wavefront_length},{max_distance_threshold},{score_steps}"
+            ),
+            Self::XDrop { xdrop, score_steps } => write!(f, "xdrop:{xdrop},{score_steps}"),
+            Self::ZDrop { zdrop, score_steps } => write!(f, "zdrop:{zdrop},{score_steps}"),
+            Self::WFMash {
+                min_wavefront_length,
+                max_distance_threshold,
+                score_steps,
+            } => write!(
+                f,
+                "wf-mash:{min_wavefront_length},{max_distance_threshold},{score_steps}"
+            ),
+        }
+    }
+}
+
+/// One of WFA2's heuristic strategies, without its parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HeuristicKind {
+    ZDrop,
+    XDrop,
+    BandedAdaptive,
+    BandedStatic,
+    WFAdaptive,
+    WFMash,
+}
+
+impl HeuristicKind {
+    pub const ALL: [Self; 6] = [
+        Self::ZDrop,
+        Self::XDrop,
+        Self::BandedAdaptive,
+        Self::BandedStatic,
+        Self::WFAdaptive,
+        Self::WFMash,
+    ];
+
+    fn flag(self) -> wfa::wf_heuristic_strategy {
+        match self {
+            Self::ZDrop => wfa::wf_heuristic_strategy_wf_heuristic_zdrop,
+            Self::XDrop => wfa::wf_heuristic_strategy_wf_heuristic_xdrop,
+            Self::BandedAdaptive => wfa::wf_heuristic_strategy_wf_heuristic_banded_adaptive,
+            Self::BandedStatic => wfa::wf_heuristic_strategy_wf_heuristic_banded_static,
+            Self::WFAdaptive => wfa::wf_heuristic_strategy_wf_heuristic_wfadaptive,
+            Self::WFMash => wfa::wf_heuristic_strategy_wf_heuristic_wfmash,
+        }
+    }
+}
+
+/// The aligner's heuristic settings exactly as WFA2 holds them: the enabled
+/// strategies and every parameter. Parameters are shared between
+/// strategies (e.g. `score_steps`, or the band of both banded variants) and
+/// keep their last value even when no strategy uses them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeuristicConfig {
+    pub enabled: Vec<HeuristicKind>,
+    pub score_steps: i32,
+    pub band_min_k: i32,
+    pub band_max_k: i32,
+    pub min_wavefront_length: i32,
+    pub max_distance_threshold: i32,
+    pub xdrop: i32,
+    pub zdrop: i32,
+}
+
+impl HeuristicConfig {
+    fn from_wfa(heuristic: &wfa::wavefront_heuristic_t) -> Self {
+        Self {
+            enabled: HeuristicKind::ALL
+                .into_iter()
+                .filter(|kind| heuristic.strategy & kind.flag() != 0)
+                .collect(),
+            score_steps: heuristic.steps_between_cutoffs,
+            band_min_k: heuristic.min_k,
+            band_max_k: heuristic.max_k,
+            min_wavefront_length: heuristic.min_wavefront_length,
+            max_distance_threshold: heuristic.max_distance_threshold,
+            xdrop: heuristic.xdrop,
+            zdrop: heuristic.zdrop,
+        }
+    }
+
+    pub fn is_enabled(&self, kind: HeuristicKind) -> bool {
+        self.enabled.contains(&kind)
+    }
+
+    /// The enabled strategies with their parameters, as accepted by
+    /// [`AffineWavefronts::set_heuristics`].
+    pub fn strategies(&self) -> Vec<HeuristicStrategy> {
+        self.enabled
+            .iter()
+            .map(|kind| match kind {
+                HeuristicKind::ZDrop => HeuristicStrategy::ZDrop {
+                    zdrop: self.zdrop,
+                    score_steps: self.score_steps,
+                },
+                HeuristicKind::XDrop => HeuristicStrategy::XDrop {
+                    xdrop: self.xdrop,
+                    score_steps: self.score_steps,
+                },
+                HeuristicKind::BandedAdaptive => HeuristicStrategy::BandedAdaptive {
+                    band_min_k: self.band_min_k,
+                    band_max_k: self.band_max_k,
+                    score_steps: self.score_steps,
+                },
+                HeuristicKind::BandedStatic => HeuristicStrategy::BandedStatic {
+                    band_min_k: self.band_min_k,
+                    band_max_k: self.band_max_k,
+                },
+                HeuristicKind::WFAdaptive => HeuristicStrategy::WFAdaptive {
+                    min_wavefront_length: self.min_wavefront_length,
+                    max_distance_threshold: self.max_distance_threshold,
+                    score_steps: self.score_steps,
+                },
+                HeuristicKind::WFMash => HeuristicStrategy::WFMash {
+                    min_wavefront_length: self.min_wavefront_length,
+                    max_distance_threshold: self.max_distance_threshold,
+                    score_steps: self.score_steps,
+                },
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AlignmentScope {
     ComputeScore,
     Alignment,
@@ -147,7 +508,18 @@ impl AlignmentScope {
     }
 }
 
+impl std::fmt::Display for AlignmentScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ComputeScore => "score",
+            Self::Alignment => "alignment",
+            Self::Undefined => "undefined",
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AlignmentSpan {
     End2End,
     EndsFree {
@@ -174,7 +546,27 @@ impl AlignmentSpan {
     }
 }
 
+// Free ends in the order pattern begin, pattern end, text begin, text end
+impl std::fmt::Display for AlignmentSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::End2End => f.write_str("end-to-end"),
+            Self::EndsFree {
+                pattern_begin_free,
+                pattern_end_free,
+                text_begin_free,
+                text_end_free,
+            } => write!(
+                f,
+                "ends-free({pattern_begin_free},{pattern_end_free},{text_begin_free},{text_end_free})"
+            ),
+            Self::Undefined => f.write_str("undefined"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MemoryMode {
     High,
     Medium,
@@ -205,6 +597,18 @@ impl MemoryMode {
     }
 }
 
+impl std::fmt::Display for MemoryMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::High => "high",
+            Self::Medium => "med",
+            Self::Low => "low",
+            Self::Ultralow => "ultralow",
+            Self::Undefined => "undefined",
+        })
+    }
+}
+
 pub trait HeuristicArg<'a> {
     fn into_heuristic(self) -> Option<&'a HeuristicStrategy>;
 }
@@ -221,47 +625,507 @@ impl<'a> HeuristicArg<'a> for Option<&'a HeuristicStrategy> {
     }
 }
 
+/// Why [`AffineWavefronts::score_checked`] could not return a score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreError {
+    /// WFA2 left the score unset and there is no CIGAR to recompute it from,
+    /// e.g. in score-only scope.
+    Unavailable,
+    /// The alignment score does not fit in an `i32`.
+    Overflow(i64),
+    /// The score reported by WFA2 wrapped around: its sign differs from the
+    /// score recomputed from the CIGAR.
+    WrappedAround { reported: i32, recomputed: i64 },
+}
+
+impl std::fmt::Display for ScoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScoreError::Unavailable => f.write_str(
+                "WFA2 did not report a score and there is no CIGAR to recompute it from",
+            ),
+            ScoreError::Overflow(score) => write!(f, "alignment score {score} overflows i32"),
+            ScoreError::WrappedAround {
+                reported,
+                recomputed,
+            } => write!(
+                f,
+                "WFA2 score {reported} wrapped around (recomputed score is {recomputed})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScoreError {}
+
+/// Where an alignment starts and ends on both sequences (0-based, ends
+/// exclusive). Gaps at either end of the CIGAR, as left by ends-free
+/// alignment, are not part of the aligned region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AlignmentCoordinates {
+    pub pattern_start: usize,
+    pub pattern_end: usize,
+    pub text_start: usize,
+    pub text_end: usize,
+}
+
+impl AlignmentCoordinates {
+    // The CIGAR path is anchored at WFA2's reported end point, so CIGARs with
+    // and without the free end gaps give the same region
+    fn new(cigar: &[u8], pattern_end: usize, text_end: usize) -> Self {
+        let count = |ops: &[u8], op: u8| ops.iter().filter(|&&c| c == op).count();
+        let (pattern_len, text_len) = (
+            cigar.len() - count(cigar, b'D'),
+            cigar.len() - count(cigar, b'I'),
+        );
+        let (leading_pattern, leading_text, body) = strip_gap_ends(cigar);
+        let trailing = &cigar[leading_pattern + leading_text + body.len()..];
+        let pattern_start = pattern_end.saturating_sub(pattern_len) + leading_pattern;
+        let text_start = text_end.saturating_sub(text_len) + leading_text;
+        Self {
+            pattern_start,
+            pattern_end: pattern_end
+                .saturating_sub(count(trailing, b'I'))
+                .max(pattern_start),
+            text_start,
+            text_end: text_end
+                .saturating_sub(count(trailing, b'D'))
+                .max(text_start),
+        }
+    }
+}
+
+/// Scores in the similarity convention of Smith-Waterman style aligners: a
+/// positive match score and negative mismatch and gap scores, with a gap of
+/// length `l` scoring `gap_opening + gap_extension * l`. They are converted
+/// to WFA2 penalties by negation, and [`AffineWavefronts::score`] already
+/// reports `matches * match_score` plus the (negative) mismatch and gap
+/// scores, so results need no conversion back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimilarityScores {
+    pub match_score: i32,
+    pub mismatch: i32,
+    pub gap_opening: i32,
+    pub gap_extension: i32,
+    /// Opening and extension of a second gap piece (dual-affine), if any.
+    pub long_gap: Option<(i32, i32)>,
+}
+
+impl SimilarityScores {
+    /// Equivalent WFA2 penalties. Errors refer to the converted (negated)
+    /// values, e.g. a negative match score is reported as a positive match
+    /// penalty.
+    pub fn to_penalties(&self) -> Result<Penalties, PenaltyError> {
+        let negate = |score: i32| score.checked_neg().ok_or(PenaltyError::Overflow);
+        let (match_score, mismatch) = (negate(self.match_score)?, negate(self.mismatch)?);
+        let (gap_opening, gap_extension) = (negate(self.gap_opening)?, negate(self.gap_extension)?);
+        let penalties = match self.long_gap {
+            None => {
+                let p = AffinePenalties {
+                    match_score,
+                    mismatch,
+                    gap_opening,
+                    gap_extension,
+                };
+                p.validate()?;
+                Penalties::GapAffine(p)
+            }
+            Some((opening, extension)) => {
+                let p = Affine2pPenalties {
+                    match_score,
+                    mismatch,
+                    gap_opening1: gap_opening,
+                    gap_extension1: gap_extension,
+                    gap_opening2: negate(opening)?,
+                    gap_extension2: negate(extension)?,
+                };
+                p.validate()?;
+                Penalties::GapAffine2p(p)
+            }
+        };
+        Ok(penalties)
+    }
+}
+
+/// Why [`AffineWavefrontsBuilder::build`] rejected a configuration.
 #[derive(Debug, Clone, PartialEq)]
-pub enum AlignmentStatus {
-    Completed,
-    Partial,
-    MaxStepsReached,
-    OOM,
-    Unattainable,
-    Undefined,
+pub enum BuildError {
+    /// Invalid penalties for the distance metric.
+    Penalties(PenaltyError),
+    /// The builder cannot create aligners for this distance metric.
+    UnsupportedMetric(DistanceMetric),
+    /// Second gap penalties were given, but the distance metric is not
+    /// `GapAffine2p`, so they would be ignored.
+    DualAffineWithoutAffine2p(DistanceMetric),
+    /// Only one of the second gap opening and extension penalties was given.
+    IncompleteDualAffine,
+    /// `max_alignment_steps` must be positive.
+    NonPositiveMaxSteps(i32),
+    /// The band divergence must be a fraction between 0 and 1.
+    InvalidBandDivergence(f64),
+    /// A bi-WFA fallback was given, but the memory mode is not
+    /// `MemoryMode::Ultralow`, so it would be ignored.
+    FallbackWithoutUltralow(MemoryMode),
+    /// `MemoryMode::Undefined` was given for the aligner or its bi-WFA
+    /// fallback; aligners need a concrete memory mode.
+    UndefinedMemoryMode,
+    /// `AlignmentScope::Undefined` was given; aligners compute either the
+    /// score or the full alignment.
+    UndefinedScope,
+    /// WFA2 cannot use this many threads per alignment, see
+    /// [`AffineWavefronts::set_num_threads`].
+    Threads { threads: usize, reason: String },
+    /// An ends-free span was given together with a non-zero match score,
+    /// which the zero-match rewrite cannot score correctly, see
+    /// [`MatchNormalization`].
+    EndsFreeWithMatchBonus(i32),
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::Penalties(e) => e.fmt(f),
+            BuildError::UnsupportedMetric(metric) => {
+                write!(
+                    f,
+                    "distance metric {metric:?} is not supported by the builder"
+                )
+            }
+            BuildError::DualAffineWithoutAffine2p(metric) => write!(
+                f,
+                "second gap penalties need the GapAffine2p distance metric, got {metric:?}"
+            ),
+            BuildError::IncompleteDualAffine => {
+                f.write_str("second gap opening and extension penalties must be given together")
+            }
+            BuildError::NonPositiveMaxSteps(steps) => {
+                write!(f, "max_alignment_steps must be positive, got {steps}")
+            }
+            BuildError::InvalidBandDivergence(divergence) => {
+                write!(
+                    f,
+                    "band divergence must be between 0 and 1, got {divergence}"
+                )
+            }
+            BuildError::FallbackWithoutUltralow(mode) => write!(
+                f,
+                "a bi-WFA fallback needs the Ultralow memory mode, got {mode:?}"
+            ),
+            BuildError::UndefinedMemoryMode => f.write_str("memory mode must not be Undefined"),
+            BuildError::UndefinedScope => f.write_str("alignment scope must not be Undefined"),
+            BuildError::EndsFreeWithMatchBonus(match_) => write!(
+                f,
+                "ends-free alignment needs a zero match score, got {match_}"
+            ),
+            BuildError::Threads { threads, reason } => {
+                write!(f, "cannot use {threads} threads per alignment: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Penalties(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<PenaltyError> for BuildError {
+    fn from(e: PenaltyError) -> Self {
+        BuildError::Penalties(e)
+    }
+}
+
+/// Penalties of an aligner by distance metric. Indel and edit distance have
+/// fixed unit costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Penalties {
+    Indel,
+    Edit,
+    GapAffine(AffinePenalties),
+    GapAffine2p(Affine2pPenalties),
+}
+
+// Penalties in WFA2's order (match, mismatch, then gap opening and extension
+// of each piece), e.g. `affine(0,4,6,2)`
+impl std::fmt::Display for AffinePenalties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "affine({},{},{},{})",
+            self.match_score, self.mismatch, self.gap_opening, self.gap_extension
+        )
+    }
+}
+
+impl std::fmt::Display for Affine2pPenalties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "affine2p({},{},{},{},{},{})",
+            self.match_score,
+            self.mismatch,
+            self.gap_opening1,
+            self.gap_extension1,
+            self.gap_opening2,
+            self.gap_extension2
+        )
+    }
+}
+
+impl std::fmt::Display for Penalties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Indel => f.write_str("indel"),
+            Self::Edit => f.write_str("edit"),
+            Self::GapAffine(penalties) => penalties.fmt(f),
+            Self::GapAffine2p(penalties) => penalties.fmt(f),
+        }
+    }
 }
 
-impl From<std::os::raw::c_int> for AlignmentStatus {
-    fn from(value: std::os::raw::c_int) -> Self {
-        match value {
-            0 => AlignmentStatus::Completed,
-            1 => AlignmentStatus::Partial,
-            -100 => AlignmentStatus::MaxStepsReached,
-            -200 => AlignmentStatus::OOM,
-            -300 => AlignmentStatus::Unattainable,
-            _ => AlignmentStatus::Undefined,
+/// Scoring presets named after, and matching the dual-affine scoring of,
+/// minimap2's `-x` presets. Gap costs follow minimap2's `O + E*l`, which is
+/// WFA2's `o + e*l`; the match bonus becomes a negative match score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PenaltyPreset {
+    /// Oxford Nanopore reads (`map-ont`).
+    MapOnt,
+    /// PacBio CLR reads (`map-pb`).
+    MapPb,
+    /// PacBio HiFi reads (`map-hifi`).
+    MapHifi,
+    /// Assemblies within ~0.1% divergence (`asm5`).
+    Asm5,
+    /// Assemblies within ~1% divergence (`asm10`).
+    Asm10,
+    /// Assemblies within several percent divergence (`asm20`).
+    Asm20,
+}
+
+impl PenaltyPreset {
+    pub const ALL: [Self; 6] = [
+        Self::MapOnt,
+        Self::MapPb,
+        Self::MapHifi,
+        Self::Asm5,
+        Self::Asm10,
+        Self::Asm20,
+    ];
+
+    /// minimap2 name of the preset, e.g. `map-ont`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MapOnt => "map-ont",
+            Self::MapPb => "map-pb",
+            Self::MapHifi => "map-hifi",
+            Self::Asm5 => "asm5",
+            Self::Asm10 => "asm10",
+            Self::Asm20 => "asm20",
+        }
+    }
+
+    pub fn penalties(&self) -> Affine2pPenalties {
+        // minimap2's -A, -B, -O and -E
+        let (a, b, o1, o2, e1, e2) = match self {
+            Self::MapOnt | Self::MapPb => (2, 4, 4, 24, 2, 1),
+            Self::MapHifi | Self::Asm20 => (1, 4, 6, 26, 2, 1),
+            Self::Asm5 => (1, 19, 39, 81, 3, 1),
+            Self::Asm10 => (1, 9, 16, 41, 2, 1),
+        };
+        Affine2pPenalties {
+            match_score: -a,
+            mismatch: b,
+            gap_opening1: o1,
+            gap_extension1: e1,
+            gap_opening2: o2,
+            gap_extension2: e2,
+        }
+    }
+}
+
+impl std::fmt::Display for PenaltyPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for PenaltyPreset {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|preset| preset.name()).collect();
+                format!(
+                    "Unknown penalty preset '{name}', expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl From<Distance> for Penalties {
+    fn from(distance: Distance) -> Self {
+        match distance {
+            Distance::Edit => Penalties::Edit,
+            Distance::GapAffine {
+                mismatch,
+                gap_opening,
+                gap_extension,
+            } => Penalties::GapAffine(AffinePenalties {
+                match_score: 0,
+                mismatch,
+                gap_opening,
+                gap_extension,
+            }),
+            Distance::GapAffine2p {
+                mismatch,
+                gap_opening1,
+                gap_extension1,
+                gap_opening2,
+                gap_extension2,
+            } => Penalties::GapAffine2p(Affine2pPenalties {
+                match_score: 0,
+                mismatch,
+                gap_opening1,
+                gap_extension1,
+                gap_opening2,
+                gap_extension2,
+            }),
         }
     }
 }
 
+impl Alignment {
+    /// Aligned region on both sequences, see [`AlignmentCoordinates`].
+    pub fn coordinates(&self) -> AlignmentCoordinates {
+        AlignmentCoordinates::new(&self.cigar, self.pattern_end, self.text_end)
+    }
+}
+
+/// Records how a scoring scheme with a non-zero match score was rewritten into
+/// the equivalent zero-match scheme that WFA2 works with, so that reported
+/// scores can be converted back to the original scheme.
+///
+/// With a match bonus `a` (WFA2 `match = -a`), mismatch `x` and gap cost
+/// `o + e*l`, the zero-match scheme uses mismatch `2(a + x)`, gap opening `2o`
+/// and gap extension `2e + a`. An alignment spanning `n` pattern and `m` text
+/// bases with normalized score `s'` scores `(a*(n + m) + s') / 2` originally.
+///
+/// The rewrite only preserves the optimal alignment when `n + m` is the same
+/// for every candidate, i.e. end to end. With an ends-free span the
+/// normalized scheme favours shorter aligned regions, so aligners with a
+/// match bonus reject ends-free spans (see [`BuildError::EndsFreeWithMatchBonus`]
+/// and [`AffineWavefronts::set_alignment_span`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchNormalization {
+    /// Match score as originally supplied (WFA2 convention, `<= 0` is a bonus).
+    pub match_: i32,
+}
+
+impl MatchNormalization {
+    pub fn match_bonus(&self) -> i32 {
+        -self.match_
+    }
+
+    pub fn mismatch(&self, mismatch: i32) -> i32 {
+        2 * (self.match_bonus() + mismatch)
+    }
+
+    pub fn gap_opening(&self, gap_opening: i32) -> i32 {
+        2 * gap_opening
+    }
+
+    pub fn gap_extension(&self, gap_extension: i32) -> i32 {
+        2 * gap_extension + self.match_bonus()
+    }
+
+    /// Convert a score computed with the zero-match scheme back to the original
+    /// scheme. `aligned_length` is the number of pattern plus text bases covered
+    /// by the alignment.
+    pub fn restore_score(&self, normalized_score: i64, aligned_length: usize) -> i64 {
+        (i64::from(self.match_bonus()) * aligned_length as i64 + normalized_score) / 2
+    }
+}
+
+/// Standard-WFA fallback for an ultralow (bi-WFA) aligner. Pairs small enough
+/// that aligning them in `memory_mode` is estimated to need at most
+/// `max_memory` bytes (see [`crate::scheduler::estimate_alignment_memory`])
+/// skip the bidirectional recursion and use a second aligner in that mode,
+/// trading a bounded amount of memory for speed. WFA2's own cutoff for
+/// sub-problems inside the recursion is fixed at compile time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BiwfaFallback {
+    pub max_memory: u64,
+    pub memory_mode: MemoryMode,
+    /// Expected divergence (0.0 - 1.0) used for the memory estimate.
+    pub divergence: f64,
+}
+
+// e.g. `med(max_memory=1048576,divergence=0.1)`
+impl std::fmt::Display for BiwfaFallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}(max_memory={},divergence={})",
+            self.memory_mode, self.max_memory, self.divergence
+        )
+    }
+}
+
+struct FallbackAligner {
+    config: BiwfaFallback,
+    aligner: AffineWavefronts,
+}
+
 pub struct AffineWavefronts {
     wf_aligner: *mut wfa::wavefront_aligner_t,
+    match_normalization: Option<MatchNormalization>,
+    // Combined length of the last aligned pair, used to restore normalized
+    // scores when no CIGAR is available.
+    last_lengths: Cell<usize>,
+    record_timing: bool,
+    last_duration: Cell<Option<Duration>>,
+    fallback: Option<Box<FallbackAligner>>,
+    // Whether the last pair was aligned by the fallback aligner
+    used_fallback: Cell<bool>,
+    band_divergence: Option<f64>,
 }
 
+/// Deep copy: a new WFA2 aligner (and bi-WFA fallback, if any) with the same
+/// penalties, memory mode, heuristic, span, scope, step limit and timing
+/// setting. Results of earlier alignments are not copied.
 impl Clone for AffineWavefronts {
     fn clone(&self) -> Self {
-        Self {
-            wf_aligner: self.wf_aligner,
-        }
+        let mut aligner = self.to_builder().build_unchecked();
+        aligner.set_max_alignment_steps(self.get_max_alignment_steps());
+        aligner.set_record_timing(self.record_timing);
+        aligner
+    }
+}
+
+impl std::fmt::Debug for AffineWavefronts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AffineWavefronts")
+            .field("attributes", &self.attributes())
+            .finish_non_exhaustive()
     }
 }
 
 impl Default for AffineWavefronts {
     fn default() -> Self {
-        Self {
-            // null pointer means wavefront_aligner_new will use default attributes.
-            wf_aligner: unsafe { wfa::wavefront_aligner_new(core::ptr::null_mut()) },
-        }
+        // null pointer means wavefront_aligner_new will use default attributes.
+        Self::from_raw(unsafe { wfa::wavefront_aligner_new(core::ptr::null_mut()) })
     }
 }
 
@@ -273,7 +1137,71 @@ impl Drop for AffineWavefronts {
     }
 }
 
+// SAFETY: the WFA2 aligner (and its fallback) is owned exclusively by this
+// value and only touched through it, so moving it to another thread is sound.
+// It is not `Sync`: aligning mutates the C state behind `&self`.
+unsafe impl Send for AffineWavefronts {}
+
 impl AffineWavefronts {
+    fn from_raw(wf_aligner: *mut wfa::wavefront_aligner_t) -> Self {
+        Self {
+            wf_aligner,
+            match_normalization: None,
+            last_lengths: Cell::new(0),
+            record_timing: false,
+            last_duration: Cell::new(None),
+            fallback: None,
+            used_fallback: Cell::new(false),
+            band_divergence: None,
+        }
+    }
+
+    // Aligner holding the results of the last alignment
+    fn result_aligner(&self) -> *mut wfa::wavefront_aligner_t {
+        match &self.fallback {
+            Some(fallback) if self.used_fallback.get() => fallback.aligner.wf_aligner,
+            _ => self.wf_aligner,
+        }
+    }
+
+    /// Add a standard-WFA fallback for small pairs. Only meaningful for
+    /// ultralow aligners; the fallback copies this aligner's configuration.
+    pub fn set_biwfa_fallback(&mut self, fallback: Option<BiwfaFallback>) {
+        self.used_fallback.set(false);
+        self.fallback = None;
+        self.fallback = fallback.map(|config| {
+            let aligner = self
+                .to_builder()
+                .memory_mode(config.memory_mode.clone())
+                .build_unchecked();
+            Box::new(FallbackAligner { config, aligner })
+        });
+    }
+
+    pub fn biwfa_fallback(&self) -> Option<&BiwfaFallback> {
+        self.fallback.as_ref().map(|fallback| &fallback.config)
+    }
+
+    /// Whether the last alignment ran on the fallback aligner.
+    pub fn last_used_fallback(&self) -> bool {
+        self.used_fallback.get()
+    }
+
+    /// Size a static band for each pair from its lengths and `divergence`
+    /// (see [`HeuristicStrategy::banded_for`]), on top of any other
+    /// heuristics. The band applies to one alignment at a time and is not
+    /// reported by [`Self::get_heuristics`]. `None` turns it off.
+    pub fn set_banded_for(&mut self, divergence: Option<f64>) {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.set_banded_for(divergence);
+        }
+        self.band_divergence = divergence;
+    }
+
+    pub fn get_banded_for(&self) -> Option<f64> {
+        self.band_divergence
+    }
+
     pub fn aligner_mut(&mut self) -> *mut wfa::wavefront_aligner_t {
         self.wf_aligner
     }
@@ -313,6 +1241,9 @@ impl AffineWavefronts {
         )
     }
 
+    /// # Panics
+    ///
+    /// If the penalties are invalid, see [`AffinePenalties::validate`].
     pub fn with_penalties_and_memory_mode(
         match_: i32,
         mismatch: i32,
@@ -320,6 +1251,27 @@ impl AffineWavefronts {
         gap_extension: i32,
         memory_mode: MemoryMode,
     ) -> Self {
+        let penalties = AffinePenalties {
+            match_score: match_,
+            mismatch,
+            gap_opening,
+            gap_extension,
+        };
+        if let Err(e) = penalties.validate() {
+            panic!("Invalid gap-affine penalties: {e}");
+        }
+        // WFA2 needs match = 0; rewrite other schemes into the equivalent zero-match form
+        let normalization = (match_ != 0).then_some(MatchNormalization { match_ });
+        let (match_, mismatch, gap_opening, gap_extension) = match normalization {
+            Some(n) => (
+                0,
+                n.mismatch(mismatch),
+                n.gap_opening(gap_opening),
+                n.gap_extension(gap_extension),
+            ),
+            None => (match_, mismatch, gap_opening, gap_extension),
+        };
+
         unsafe {
             // Create attributes and set defaults
             let mut attributes = wfa::wavefront_aligner_attr_default;
@@ -348,10 +1300,43 @@ impl AffineWavefronts {
             // Create aligner with attributes
             let wf_aligner = wfa::wavefront_aligner_new(&mut attributes);
 
-            Self { wf_aligner }
+            let mut aligner = Self::from_raw(wf_aligner);
+            aligner.match_normalization = normalization;
+            aligner
         }
     }
 
+    /// Semi-global (glocal) gap-affine aligner, the usual setup for mapping
+    /// reads to a reference: the pattern (query) is aligned end to end and
+    /// may start and end anywhere in the text (reference) at no cost. Uses
+    /// high memory mode, like [`Self::with_penalties`].
+    ///
+    /// # Panics
+    ///
+    /// If `match_` is not zero, see [`Self::set_alignment_span`].
+    pub fn new_semiglobal(
+        match_: i32,
+        mismatch: i32,
+        gap_opening: i32,
+        gap_extension: i32,
+    ) -> Self {
+        let mut aligner = Self::with_penalties(match_, mismatch, gap_opening, gap_extension);
+        // WFA2 caps the free ends at the sequence lengths
+        aligner.set_alignment_span(AlignmentSpan::EndsFree {
+            pattern_begin_free: 0,
+            pattern_end_free: 0,
+            text_begin_free: i32::MAX,
+            text_end_free: i32::MAX,
+        });
+        aligner
+    }
+
+    /// Edit (Levenshtein) distance aligner: every mismatch, insertion and
+    /// deletion costs 1. Uses high memory mode, like [`Self::with_penalties`].
+    pub fn new_edit() -> Self {
+        Self::with_edit_and_memory_mode(MemoryMode::High)
+    }
+
     pub fn with_edit_and_memory_mode(memory_mode: MemoryMode) -> Self {
         unsafe {
             let mut attributes = wfa::wavefront_aligner_attr_default;
@@ -362,7 +1347,7 @@ impl AffineWavefronts {
 
             let wf_aligner = wfa::wavefront_aligner_new(&mut attributes);
 
-            Self { wf_aligner }
+            Self::from_raw(wf_aligner)
         }
     }
 
@@ -405,6 +1390,9 @@ impl AffineWavefronts {
         )
     }
 
+    /// # Panics
+    ///
+    /// If the penalties are invalid, see [`Affine2pPenalties::validate`].
     pub fn with_penalties_affine2p_and_memory_mode(
         match_: i32,
         mismatch: i32,
@@ -414,6 +1402,39 @@ impl AffineWavefronts {
         gap_extension2: i32,
         memory_mode: MemoryMode,
     ) -> Self {
+        let penalties = Affine2pPenalties {
+            match_score: match_,
+            mismatch,
+            gap_opening1,
+            gap_extension1,
+            gap_opening2,
+            gap_extension2,
+        };
+        if let Err(e) = penalties.validate() {
+            panic!("Invalid dual-affine penalties: {e}");
+        }
+        // WFA2 needs match = 0; rewrite other schemes into the equivalent zero-match form
+        let normalization = (match_ != 0).then_some(MatchNormalization { match_ });
+        let (match_, mismatch, gap_opening1, gap_extension1, gap_opening2, gap_extension2) =
+            match normalization {
+                Some(n) => (
+                    0,
+                    n.mismatch(mismatch),
+                    n.gap_opening(gap_opening1),
+                    n.gap_extension(gap_extension1),
+                    n.gap_opening(gap_opening2),
+                    n.gap_extension(gap_extension2),
+                ),
+                None => (
+                    match_,
+                    mismatch,
+                    gap_opening1,
+                    gap_extension1,
+                    gap_opening2,
+                    gap_extension2,
+                ),
+            };
+
         unsafe {
             // Create attributes and set defaults (see https://github.com/smarco/WFA2-lib/blob/2ec2891/wavefront/wavefront_attributes.c#L38)
             let mut attributes = wfa::wavefront_aligner_attr_default;
@@ -444,7 +1465,9 @@ impl AffineWavefronts {
             // Create aligner with attributes
             let wf_aligner = wfa::wavefront_aligner_new(&mut attributes);
 
-            Self { wf_aligner }
+            let mut aligner = Self::from_raw(wf_aligner);
+            aligner.match_normalization = normalization;
+            aligner
         }
     }
 
@@ -482,29 +1505,105 @@ impl AffineWavefronts {
         }
     }
 
+    /// Penalties as originally supplied, before any match normalization (see
+    /// [`MatchNormalization`]); [`Self::get_distance`] reports the normalized
+    /// ones WFA2 works with.
+    pub fn get_penalties(&self) -> Penalties {
+        let penalties = unsafe { (*self.wf_aligner).penalties };
+        // Undo the normalization applied at construction
+        let (match_score, mismatch, o1, e1, o2, e2) = match self.match_normalization {
+            Some(n) => {
+                let a = n.match_bonus();
+                (
+                    n.match_,
+                    penalties.mismatch / 2 - a,
+                    penalties.gap_opening1 / 2,
+                    (penalties.gap_extension1 - a) / 2,
+                    penalties.gap_opening2 / 2,
+                    (penalties.gap_extension2 - a) / 2,
+                )
+            }
+            None => (
+                penalties.match_,
+                penalties.mismatch,
+                penalties.gap_opening1,
+                penalties.gap_extension1,
+                penalties.gap_opening2,
+                penalties.gap_extension2,
+            ),
+        };
+        match self.get_distance_metric() {
+            DistanceMetric::Indel => Penalties::Indel,
+            DistanceMetric::Edit => Penalties::Edit,
+            DistanceMetric::GapAffine => Penalties::GapAffine(AffinePenalties {
+                match_score,
+                mismatch,
+                gap_opening: o1,
+                gap_extension: e1,
+            }),
+            DistanceMetric::GapAffine2p => Penalties::GapAffine2p(Affine2pPenalties {
+                match_score,
+                mismatch,
+                gap_opening1: o1,
+                gap_extension1: e1,
+                gap_opening2: o2,
+                gap_extension2: e2,
+            }),
+        }
+    }
+
+    /// Replace the aligner's heuristics with `heuristic` (or none).
     pub fn set_heuristic<'a, H>(&mut self, heuristic: H)
     where
         H: HeuristicArg<'a>,
     {
         match heuristic.into_heuristic() {
-            None | Some(HeuristicStrategy::None) => unsafe {
-                wfa::wavefront_aligner_set_heuristic_none(self.wf_aligner)
-            },
-            Some(HeuristicStrategy::BandedStatic {
+            Some(heuristic) => self.set_heuristics(std::slice::from_ref(heuristic)),
+            None => self.set_heuristics(&[]),
+        }
+    }
+
+    /// Replace the aligner's heuristics with all of `heuristics`, which WFA2
+    /// applies together. Strategies share one `score_steps` setting, so the
+    /// last one given wins.
+    pub fn set_heuristics(&mut self, heuristics: &[HeuristicStrategy]) {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.set_heuristics(heuristics);
+        }
+        unsafe { wfa::wavefront_aligner_set_heuristic_none(self.wf_aligner) };
+        for heuristic in heuristics {
+            self.add_heuristic(heuristic);
+        }
+    }
+
+    /// Disable one strategy, keeping any others enabled along with all
+    /// parameters.
+    pub fn clear_heuristic(&mut self, kind: HeuristicKind) {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.clear_heuristic(kind);
+        }
+        unsafe { (*self.wf_aligner).heuristic.strategy &= !kind.flag() };
+    }
+
+    // WFA2's heuristic setters add to the strategy bitmask
+    fn add_heuristic(&mut self, heuristic: &HeuristicStrategy) {
+        match heuristic {
+            HeuristicStrategy::None => {}
+            HeuristicStrategy::BandedStatic {
                 band_min_k,
                 band_max_k,
-            }) => unsafe {
+            } => unsafe {
                 wfa::wavefront_aligner_set_heuristic_banded_static(
                     self.wf_aligner,
                     *band_min_k,
                     *band_max_k,
                 )
             },
-            Some(HeuristicStrategy::BandedAdaptive {
+            HeuristicStrategy::BandedAdaptive {
                 band_min_k,
                 band_max_k,
                 score_steps,
-            }) => unsafe {
+            } => unsafe {
                 wfa::wavefront_aligner_set_heuristic_banded_adaptive(
                     self.wf_aligner,
                     *band_min_k,
@@ -512,11 +1611,11 @@ impl AffineWavefronts {
                     *score_steps,
                 )
             },
-            Some(HeuristicStrategy::WFAdaptive {
+            HeuristicStrategy::WFAdaptive {
                 min_wavefront_length,
                 max_distance_threshold,
                 score_steps,
-            }) => unsafe {
+            } => unsafe {
                 wfa::wavefront_aligner_set_heuristic_wfadaptive(
                     self.wf_aligner,
                     *min_wavefront_length,
@@ -524,17 +1623,17 @@ impl AffineWavefronts {
                     *score_steps,
                 )
             },
-            Some(HeuristicStrategy::XDrop { xdrop, score_steps }) => unsafe {
+            HeuristicStrategy::XDrop { xdrop, score_steps } => unsafe {
                 wfa::wavefront_aligner_set_heuristic_xdrop(self.wf_aligner, *xdrop, *score_steps)
             },
-            Some(HeuristicStrategy::ZDrop { zdrop, score_steps }) => unsafe {
+            HeuristicStrategy::ZDrop { zdrop, score_steps } => unsafe {
                 wfa::wavefront_aligner_set_heuristic_zdrop(self.wf_aligner, *zdrop, *score_steps)
             },
-            Some(HeuristicStrategy::WFMash {
+            HeuristicStrategy::WFMash {
                 min_wavefront_length,
                 max_distance_threshold,
                 score_steps,
-            }) => unsafe {
+            } => unsafe {
                 wfa::wavefront_aligner_set_heuristic_wfmash(
                     self.wf_aligner,
                     *min_wavefront_length,
@@ -545,59 +1644,27 @@ impl AffineWavefronts {
         }
     }
 
+    /// Every heuristic setting of the aligner, see [`HeuristicConfig`].
+    pub fn get_heuristic_config(&self) -> HeuristicConfig {
+        HeuristicConfig::from_wfa(&unsafe { *self.wf_aligner }.heuristic)
+    }
+
+    /// The enabled heuristics with their parameters, see
+    /// [`HeuristicConfig::strategies`].
     pub fn get_heuristics(&self) -> Vec<HeuristicStrategy> {
-        let mut hs = Vec::new();
-        let heuristic = unsafe { *self.wf_aligner }.heuristic;
-        let strategy = heuristic.strategy;
-
-        if strategy & wfa::wf_heuristic_strategy_wf_heuristic_zdrop > 0 {
-            hs.push(HeuristicStrategy::ZDrop {
-                zdrop: heuristic.zdrop,
-                score_steps: heuristic.steps_between_cutoffs,
-            });
-        }
-        if strategy & wfa::wf_heuristic_strategy_wf_heuristic_xdrop > 0 {
-            hs.push(HeuristicStrategy::XDrop {
-                xdrop: heuristic.zdrop,
-                score_steps: heuristic.steps_between_cutoffs,
-            });
-        }
-        if strategy & wfa::wf_heuristic_strategy_wf_heuristic_banded_adaptive > 0 {
-            hs.push(HeuristicStrategy::BandedAdaptive {
-                band_min_k: heuristic.min_k,
-                band_max_k: heuristic.max_k,
-                score_steps: heuristic.steps_between_cutoffs,
-            });
-        }
-        if strategy & wfa::wf_heuristic_strategy_wf_heuristic_banded_static > 0 {
-            hs.push(HeuristicStrategy::BandedStatic {
-                band_min_k: heuristic.min_k,
-                band_max_k: heuristic.max_k,
-            });
-        }
-        if strategy & wfa::wf_heuristic_strategy_wf_heuristic_wfadaptive > 0 {
-            hs.push(HeuristicStrategy::WFAdaptive {
-                min_wavefront_length: heuristic.min_wavefront_length,
-                max_distance_threshold: heuristic.max_distance_threshold,
-                score_steps: heuristic.steps_between_cutoffs,
-            });
-        }
-        if strategy & wfa::wf_heuristic_strategy_wf_heuristic_wfmash > 0 {
-            hs.push(HeuristicStrategy::WFMash {
-                min_wavefront_length: heuristic.min_wavefront_length,
-                max_distance_threshold: heuristic.max_distance_threshold,
-                score_steps: heuristic.steps_between_cutoffs,
-            });
-        }
-        hs
+        self.get_heuristic_config().strategies()
     }
 
     pub fn set_alignment_scope(&mut self, scope: AlignmentScope) {
-        (unsafe { *self.wf_aligner }).alignment_scope = match scope {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.set_alignment_scope(scope.clone());
+        }
+        let scope = match scope {
             AlignmentScope::ComputeScore => wfa::alignment_scope_t_compute_score,
             AlignmentScope::Alignment => wfa::alignment_scope_t_compute_alignment,
             AlignmentScope::Undefined => panic!("Cannot set an undefined scope"),
-        }
+        };
+        unsafe { (*self.wf_aligner).alignment_scope = scope };
     }
 
     pub fn get_alignment_scope(&self) -> AlignmentScope {
@@ -605,7 +1672,23 @@ impl AffineWavefronts {
         AlignmentScope::from_scope(a.alignment_scope)
     }
 
+    /// Set the span of later alignments.
+    ///
+    /// # Panics
+    ///
+    /// If `span` is ends-free and the aligner has a non-zero match score:
+    /// the zero-match rewrite only scores end-to-end alignments correctly,
+    /// see [`MatchNormalization`].
     pub fn set_alignment_span(&mut self, span: AlignmentSpan) {
+        if let (Some(n), AlignmentSpan::EndsFree { .. }) = (self.match_normalization, &span) {
+            panic!(
+                "ends-free alignment needs a zero match score, got {}",
+                n.match_
+            );
+        }
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.set_alignment_span(span.clone());
+        }
         let _form: &mut wfa::alignment_form_t = &mut (unsafe { *self.wf_aligner }).alignment_form;
         match span {
             AlignmentSpan::End2End => {
@@ -649,6 +1732,9 @@ impl AffineWavefronts {
     }
 
     pub fn set_max_alignment_steps(&mut self, steps: i32) {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.set_max_alignment_steps(steps);
+        }
         unsafe {
             wavefront_aligner_set_max_alignment_steps(self.wf_aligner, steps);
         }
@@ -663,9 +1749,76 @@ impl AffineWavefronts {
         a.system.max_alignment_steps
     }
 
+    /// Memory budget in bytes: alignments that would need more stop with
+    /// `AlignmentStatus::OOM` instead of allocating without bound. WFA2 also
+    /// starts compacting its wavefronts at this size if it was set to compact
+    /// later.
+    pub fn set_max_memory(&mut self, bytes: u64) {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.set_max_memory(bytes);
+        }
+        let resident = unsafe { (*self.wf_aligner).system.max_memory_resident };
+        unsafe {
+            wfa::wavefront_aligner_set_max_memory(self.wf_aligner, resident.min(bytes), bytes)
+        };
+    }
+
+    /// Memory budget in bytes, `u64::MAX` if unlimited (WFA2's default).
+    pub fn get_max_memory(&self) -> u64 {
+        unsafe { (*self.wf_aligner).system.max_memory_abort }
+    }
+
+    /// Bytes currently held by WFA2 for this aligner: wavefronts, backtrace
+    /// buffers and bi-WFA state kept from earlier alignments for reuse.
+    pub fn memory_usage(&self) -> u64 {
+        let fallback = self
+            .fallback
+            .as_ref()
+            .map_or(0, |f| f.aligner.memory_usage());
+        unsafe { wfa::wavefront_aligner_get_size(self.wf_aligner) + fallback }
+    }
+
+    /// Release the buffers WFA2 keeps between alignments, which grow to fit
+    /// the largest pair aligned so far, without rebuilding the aligner: the
+    /// configuration is kept. Lets long-running services bound their memory,
+    /// e.g. after an unusually long pair (see [`Self::memory_usage`]). The
+    /// result of the last alignment must be read before.
+    pub fn reset(&mut self) {
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.reset();
+        }
+        unsafe { wfa::wavefront_aligner_reap(self.wf_aligner) };
+        self.last_lengths.set(0);
+        self.last_duration.set(None);
+        self.used_fallback.set(false);
+    }
+
+    /// Threads WFA2 may use within a single alignment (OpenMP). Fails if
+    /// `threads` is zero or the library was built without OpenMP (see
+    /// [`crate::capabilities`]).
+    pub fn set_num_threads(&mut self, threads: usize) -> Result<(), String> {
+        if !crate::capabilities().openmp {
+            return Err("WFA2 was built without OpenMP".to_string());
+        }
+        if threads == 0 {
+            return Err("Number of threads must be positive".to_string());
+        }
+        if let Some(fallback) = &mut self.fallback {
+            fallback.aligner.set_num_threads(threads)?;
+        }
+        let threads = threads.min(i32::MAX as usize) as i32;
+        unsafe { wfa::wavefront_aligner_set_max_num_threads(self.wf_aligner, threads) };
+        Ok(())
+    }
+
+    pub fn get_num_threads(&self) -> usize {
+        let threads = unsafe { (*self.wf_aligner).system.max_num_threads };
+        threads.max(1) as usize
+    }
+
     pub fn cigar(&self) -> &[u8] {
         unsafe {
-            let cigar = (*self.wf_aligner).cigar;
+            let cigar = (*self.result_aligner()).cigar;
             let ops = (*cigar).operations;
             let begin_offset = (*cigar).begin_offset;
             let end_offset = (*cigar).end_offset;
@@ -679,31 +1832,516 @@ impl AffineWavefronts {
         }
     }
 
+    /// Last alignment's CIGAR in run-length form, e.g. `"12=1X3I8="`. See
+    /// [`crate::cigar::cigar_string`].
+    pub fn cigar_string(&self) -> String {
+        cigar_string(self.cigar())
+    }
+
+    /// Last alignment's CIGAR for a SAM record; `collapse_matches` merges
+    /// `=`/`X` into `M`. See [`crate::cigar::sam_cigar`].
+    pub fn cigar_sam(&self, collapse_matches: bool) -> String {
+        sam_cigar(self.cigar(), collapse_matches)
+    }
+
+    /// Last alignment in BLAST BTOP form; `query` and `target` must be the
+    /// sequences it was computed for. See [`crate::cigar::btop`].
+    pub fn btop(&self, query: &[u8], target: &[u8]) -> Result<String, String> {
+        btop(query, target, self.cigar())
+    }
+
+    /// Last alignment as a three-line view wrapped at `width` columns; `pattern`
+    /// and `text` must be the sequences it was computed for. See
+    /// [`crate::cigar::format_alignment`].
+    pub fn format_alignment(
+        &self,
+        pattern: &[u8],
+        text: &[u8],
+        width: usize,
+    ) -> Result<String, String> {
+        format_alignment(pattern, text, self.cigar(), width)
+    }
+
+    /// [`Self::format_alignment`] with optional coloring, see
+    /// [`crate::cigar::FormatOptions`].
+    pub fn format_alignment_with(
+        &self,
+        pattern: &[u8],
+        text: &[u8],
+        options: &FormatOptions,
+    ) -> Result<String, String> {
+        format_alignment_with(pattern, text, self.cigar(), options)
+    }
+
+    /// Operation counts and identity of the last alignment.
+    pub fn stats(&self) -> CigarStats {
+        CigarStats::from_cigar(self.cigar())
+    }
+
+    /// Runs of the last alignment's CIGAR as `(operation, length)` pairs.
+    pub fn cigar_ops(&self) -> CigarRuns<'_> {
+        cigar_runs(self.cigar())
+    }
+
+    /// Score of the last alignment. Scores restored from a zero-match rewrite
+    /// (see [`MatchNormalization`]) that do not fit an `i32` saturate.
     pub fn score(&self) -> i32 {
-        unsafe {
-            let cigar = (*self.wf_aligner).cigar;
+        let score = unsafe {
+            let cigar = (*self.result_aligner()).cigar;
             (*cigar).score
+        };
+        match self.match_normalization {
+            Some(n) => {
+                let restored = n.restore_score(i64::from(score), self.aligned_length());
+                i32::try_from(restored).unwrap_or(if restored < 0 { i32::MIN } else { i32::MAX })
+            }
+            None => score,
+        }
+    }
+
+    /// Normalization applied to a non-zero match score at construction, if any.
+    pub fn match_normalization(&self) -> Option<MatchNormalization> {
+        self.match_normalization
+    }
+
+    // Pattern plus text bases covered by the last alignment
+    fn aligned_length(&self) -> usize {
+        let cigar = self.alignment_cigar();
+        if cigar.is_empty() {
+            return self.last_lengths.get();
+        }
+        Self::cigar_aligned_length(cigar)
+    }
+
+    // CIGAR of the last alignment, empty in score-only scope, where WFA2 leaves
+    // the CIGAR of an earlier alignment in place
+    fn alignment_cigar(&self) -> &[u8] {
+        if self.get_alignment_scope() == AlignmentScope::ComputeScore {
+            return &[];
+        }
+        self.cigar()
+    }
+
+    fn cigar_aligned_length(cigar: &[u8]) -> usize {
+        cigar
+            .iter()
+            .map(|op| match op {
+                b'M' | b'=' | b'X' => 2,
+                b'I' | b'D' => 1,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Recompute the score of the last alignment from its CIGAR using checked
+    /// `i64` accumulation. Returns `None` when there is no CIGAR (e.g. score-only
+    /// scope) or the accumulation overflows.
+    pub fn cigar_score_i64(&self) -> Option<i64> {
+        let cigar = self.alignment_cigar();
+        if cigar.is_empty() {
+            return None;
+        }
+        self.score_cigar_i64(cigar)
+    }
+
+    /// Score an arbitrary compact CIGAR with this aligner's penalties, using
+    /// checked `i64` accumulation. Returns `None` on overflow. See
+    /// [`crate::cigar::score_cigar`].
+    pub fn score_cigar_i64(&self, cigar: &[u8]) -> Option<i64> {
+        score_cigar(cigar, &self.get_penalties())
+    }
+
+    /// Alignment score widened to `i64`. Prefers the score recomputed from the
+    /// CIGAR, which cannot wrap around, and falls back to the WFA2 score.
+    pub fn score_i64(&self) -> i64 {
+        self.cigar_score_i64()
+            .unwrap_or_else(|| i64::from(self.score()))
+    }
+
+    /// Like [`Self::score_checked`], with the error as a message.
+    #[deprecated(note = "use `score_checked`, which returns a typed error")]
+    pub fn checked_score(&self) -> Result<i32, String> {
+        self.score_checked().map_err(|e| e.to_string())
+    }
+
+    /// Score of the last alignment, consistent across memory modes and checked
+    /// for overflow. Some WFA2 versions leave the score of bi-WFA (ultralow)
+    /// alignments unset as `i32::MIN`; it is then recomputed from the CIGAR
+    /// with the configured penalties (see [`Self::cigar_score_i64`]). Fails if
+    /// the score is unset and there is no CIGAR to recompute it from, or if the
+    /// true score does not fit in an `i32`.
+    pub fn score_checked(&self) -> Result<i32, ScoreError> {
+        let raw = unsafe { (*(*self.result_aligner()).cigar).score };
+        let recomputed = self.cigar_score_i64();
+        if raw == i32::MIN {
+            let recomputed = recomputed.ok_or(ScoreError::Unavailable)?;
+            return i32::try_from(recomputed).map_err(|_| ScoreError::Overflow(recomputed));
+        }
+        let reported = match self.match_normalization {
+            Some(n) => n.restore_score(i64::from(raw), self.aligned_length()),
+            None => i64::from(raw),
+        };
+        let reported = i32::try_from(reported).map_err(|_| ScoreError::Overflow(reported))?;
+        match recomputed {
+            Some(wide) if i32::try_from(wide).is_err() => Err(ScoreError::Overflow(wide)),
+            Some(wide) if (wide < 0) != (reported < 0) && wide != i64::from(reported) => {
+                Err(ScoreError::WrappedAround {
+                    reported,
+                    recomputed: wide,
+                })
+            }
+            _ => Ok(reported),
+        }
+    }
+
+    /// Trim the ends of the last alignment whose windowed identity falls below
+    /// `min_identity`. See [`crate::trim::trim_low_identity_ends`].
+    pub fn trim_low_identity_ends(&self, min_identity: f64, window: usize) -> TrimmedCigar {
+        trim_low_identity_ends(self.cigar(), min_identity, window)
+    }
+
+    /// Record the wall-clock duration of every alignment. Off by default to
+    /// avoid the clock overhead.
+    pub fn set_record_timing(&mut self, enabled: bool) {
+        self.record_timing = enabled;
+        if !enabled {
+            self.last_duration.set(None);
         }
     }
 
+    pub fn get_record_timing(&self) -> bool {
+        self.record_timing
+    }
+
+    /// Wall-clock duration of the last alignment, if timing is enabled.
+    pub fn last_alignment_duration(&self) -> Option<Duration> {
+        self.last_duration.get()
+    }
+
     pub fn align(&self, a: &[u8], b: &[u8]) -> AlignmentStatus {
-        unsafe {
-            let a = slice::from_raw_parts(a.as_ptr() as *const i8, a.len());
-            let b = slice::from_raw_parts(b.as_ptr() as *const i8, b.len());
+        self.timed(|| self.align_untimed(a, b))
+    }
+
+    /// Like [`align`](Self::align), but returns the alignment on success and
+    /// the failure status as an error, so it cannot be silently dropped.
+    pub fn align_checked(&self, a: &[u8], b: &[u8]) -> Result<Alignment, AlignError> {
+        let status = self.align(a, b);
+        status.clone().ok()?;
+        Ok(self.alignment(status))
+    }
+
+    /// Align and return the result by value, whatever the status.
+    pub fn align_owned(&self, a: &[u8], b: &[u8]) -> Alignment {
+        let status = self.align(a, b);
+        self.alignment(status)
+    }
+
+    /// Align with an ends-free span for this call only (see
+    /// [`AffineWavefrontsBuilder::ends_free`]); the aligner's previous span is
+    /// restored before returning, so one aligner can serve both global and
+    /// semi-global calls.
+    ///
+    /// # Panics
+    ///
+    /// If the aligner has a non-zero match score, see
+    /// [`Self::set_alignment_span`].
+    pub fn align_ends_free(
+        &mut self,
+        a: &[u8],
+        b: &[u8],
+        pattern_begin_free: i32,
+        pattern_end_free: i32,
+        text_begin_free: i32,
+        text_end_free: i32,
+    ) -> AlignmentStatus {
+        let span = self.get_alignment_span();
+        self.set_alignment_span(AlignmentSpan::EndsFree {
+            pattern_begin_free,
+            pattern_end_free,
+            text_begin_free,
+            text_end_free,
+        });
+        let status = self.align(a, b);
+        self.set_alignment_span(span);
+        status
+    }
+
+    /// Extend an alignment from the start of both sequences, as from the end
+    /// of a seed: both ends are free and an X-drop heuristic (checked every
+    /// step) stops the extension once the score falls `xdrop` below the best
+    /// seen. `pattern_end` and `text_end` of the result tell how far the
+    /// extension reached; a `Partial` status means X-drop cut it short. The
+    /// aligner's span and heuristic are restored before returning.
+    ///
+    /// # Panics
+    ///
+    /// If the aligner has a non-zero match score, see
+    /// [`Self::set_alignment_span`].
+    pub fn extend(&mut self, pattern: &[u8], text: &[u8], xdrop: i32) -> Alignment {
+        let span = self.get_alignment_span();
+        let heuristics = self.get_heuristics();
+        self.set_alignment_span(AlignmentSpan::EndsFree {
+            pattern_begin_free: 0,
+            pattern_end_free: pattern.len() as i32,
+            text_begin_free: 0,
+            text_end_free: text.len() as i32,
+        });
+        self.set_heuristic(&HeuristicStrategy::XDrop {
+            xdrop,
+            score_steps: 1,
+        });
+        let extension = self.align_owned(pattern, text);
+        self.set_alignment_span(span);
+        self.set_heuristics(&heuristics);
+        extension
+    }
+
+    /// Copy of the last alignment's result, tagged with the `status` its
+    /// `align()` call returned.
+    pub fn alignment(&self, status: AlignmentStatus) -> Alignment {
+        let (pattern_end, text_end) = self.alignment_end();
+        Alignment {
+            status,
+            score: self.score(),
+            cigar: self.cigar().to_vec(),
+            pattern_end,
+            text_end,
+            elapsed: self.last_alignment_duration(),
+        }
+    }
+
+    /// Where the last alignment starts and ends on the pattern and text, see
+    /// [`AlignmentCoordinates`].
+    pub fn alignment_coordinates(&self) -> AlignmentCoordinates {
+        let (pattern_end, text_end) = self.alignment_end();
+        AlignmentCoordinates::new(self.cigar(), pattern_end, text_end)
+    }
+
+    // End point of the last alignment as reported by WFA2
+    fn alignment_end(&self) -> (usize, usize) {
+        let (end_v, end_h) = unsafe {
+            let cigar = (*self.result_aligner()).cigar;
+            ((*cigar).end_v, (*cigar).end_h)
+        };
+        (end_v.max(0) as usize, end_h.max(0) as usize)
+    }
+
+    /// Align sequences in any [`EncodedSequence`] representation (e.g. 2-bit
+    /// packed containers) without converting them to bytes first. Symbols are
+    /// compared through WFA2's custom match function, position by position.
+    /// The bi-WFA fallback and per-pair band apply as in [`Self::align`].
+    pub fn align_encoded<P, T>(&self, pattern: &P, text: &T) -> AlignmentStatus
+    where
+        P: EncodedSequence + ?Sized,
+        T: EncodedSequence + ?Sized,
+    {
+        unsafe extern "C" fn symbols_match<P, T>(
+            v: std::os::raw::c_int,
+            h: std::os::raw::c_int,
+            arguments: *mut std::os::raw::c_void,
+        ) -> std::os::raw::c_int
+        where
+            P: EncodedSequence + ?Sized,
+            T: EncodedSequence + ?Sized,
+        {
+            let (pattern, text) = unsafe { *(arguments as *const (&P, &T)) };
+            let (v, h) = (v as usize, h as usize);
+            let matches = v < pattern.len() && h < text.len() && pattern.code(v) == text.code(h);
+            matches.into()
+        }
+
+        self.timed(|| {
+            let mut sequences = (pattern, text);
+            let arguments = &mut sequences as *mut (&P, &T) as *mut std::os::raw::c_void;
+            self.align_on(pattern.len(), text.len(), &|wf_aligner| unsafe {
+                wfa::wavefront_align_lambda(
+                    wf_aligner,
+                    Some(symbols_match::<P, T>),
+                    arguments,
+                    pattern.len() as i32,
+                    text.len() as i32,
+                )
+            })
+        })
+    }
+
+    fn timed(&self, align: impl FnOnce() -> AlignmentStatus) -> AlignmentStatus {
+        if !self.record_timing {
+            return align();
+        }
+        let start = Instant::now();
+        let status = align();
+        self.last_duration.set(Some(start.elapsed()));
+        status
+    }
 
-            let alignment_status: AlignmentStatus = wfa::wavefront_align(
-                self.wf_aligner,
+    fn align_untimed(&self, a: &[u8], b: &[u8]) -> AlignmentStatus {
+        let (a, b) = unsafe {
+            (
+                slice::from_raw_parts(a.as_ptr() as *const i8, a.len()),
+                slice::from_raw_parts(b.as_ptr() as *const i8, b.len()),
+            )
+        };
+        self.align_on(a.len(), b.len(), &|wf_aligner| unsafe {
+            wfa::wavefront_align(
+                wf_aligner,
                 a.as_ptr(),
                 a.len() as i32,
                 b.as_ptr(),
                 b.len() as i32,
             )
-            .into();
+        })
+    }
+
+    // Run `align` on the aligner that handles a pair of these lengths (this
+    // one or its bi-WFA fallback), with the per-pair band applied
+    fn align_on(
+        &self,
+        pattern_len: usize,
+        text_len: usize,
+        align: &dyn Fn(*mut wfa::wavefront_aligner_t) -> std::os::raw::c_int,
+    ) -> AlignmentStatus {
+        self.last_lengths.set(pattern_len + text_len);
+        let use_fallback = self.fallback.as_ref().is_some_and(|fallback| {
+            let config = &fallback.config;
+            estimate_alignment_memory(
+                pattern_len,
+                text_len,
+                &config.memory_mode,
+                config.divergence,
+            ) <= config.max_memory
+        });
+        self.used_fallback.set(use_fallback);
+        if use_fallback {
+            if let Some(fallback) = &self.fallback {
+                return fallback.aligner.align_on(pattern_len, text_len, align);
+            }
+        }
+        unsafe {
+            let heuristic = (*self.wf_aligner).heuristic;
+            if let Some(divergence) = self.band_divergence {
+                if let HeuristicStrategy::BandedStatic {
+                    band_min_k,
+                    band_max_k,
+                } = HeuristicStrategy::banded_for(pattern_len, text_len, divergence)
+                {
+                    wfa::wavefront_aligner_set_heuristic_banded_static(
+                        self.wf_aligner,
+                        band_min_k,
+                        band_max_k,
+                    );
+                }
+            }
+            let alignment_status: AlignmentStatus = align(self.wf_aligner).into();
+            (*self.wf_aligner).heuristic = heuristic;
 
             alignment_status
         }
     }
 
+    /// Builder preconfigured with this aligner's current settings: distance
+    /// metric and penalties (as originally supplied, before any match
+    /// normalization), memory mode, heuristic, scope and span. Use it to derive
+    /// variants, e.g. `aligner.to_builder().memory_mode(MemoryMode::Ultralow)`.
+    pub fn to_builder(&self) -> AffineWavefrontsBuilder {
+        let metric = self.get_distance_metric();
+        let mut builder = AffineWavefrontsBuilder::new()
+            .memory_mode(self.get_memory_mode())
+            .heuristics(&self.get_heuristics())
+            .alignment_span(self.get_alignment_span());
+        match self.get_penalties() {
+            Penalties::GapAffine(p) => {
+                builder =
+                    builder.penalties(p.match_score, p.mismatch, p.gap_opening, p.gap_extension);
+            }
+            Penalties::GapAffine2p(p) => {
+                builder = builder
+                    .penalties(p.match_score, p.mismatch, p.gap_opening1, p.gap_extension1)
+                    .dual_affine_penalties(p.gap_opening2, p.gap_extension2);
+            }
+            Penalties::Indel | Penalties::Edit => {}
+        }
+        if let Some(fallback) = self.biwfa_fallback() {
+            builder = builder.biwfa_fallback(fallback.clone());
+        }
+        if self.get_max_memory() != u64::MAX {
+            builder = builder.max_memory(self.get_max_memory());
+        }
+        builder = builder.max_alignment_steps(self.get_max_alignment_steps());
+        if let Some(divergence) = self.band_divergence {
+            builder = builder.banded_for(divergence);
+        }
+        if self.get_num_threads() > 1 {
+            builder = builder.num_threads(self.get_num_threads());
+        }
+        let scope = self.get_alignment_scope();
+        if scope != AlignmentScope::Undefined {
+            builder = builder.alignment_scope(scope);
+        }
+        builder.distance_metric(metric)
+    }
+
+    /// This aligner's configuration, see [`AlignerConfig`].
+    pub fn config(&self) -> AlignerConfig {
+        self.to_builder().into()
+    }
+
+    /// Snapshot of the settings WFA2 will align with, see
+    /// [`WavefrontAttributes`].
+    pub fn attributes(&self) -> WavefrontAttributes {
+        let system = unsafe { (*self.wf_aligner).system };
+        WavefrontAttributes {
+            distance_metric: self.get_distance_metric(),
+            penalties: self.get_penalties(),
+            distance: self.get_distance(),
+            match_normalization: self.match_normalization,
+            memory_mode: self.get_memory_mode(),
+            heuristics: self.get_heuristics(),
+            alignment_scope: self.get_alignment_scope(),
+            alignment_span: self.get_alignment_span(),
+            system: SystemLimits {
+                max_alignment_steps: system.max_alignment_steps,
+                probe_interval_global: system.probe_interval_global,
+                probe_interval_compact: system.probe_interval_compact,
+                max_partial_compacts: system.max_partial_compacts,
+                max_memory_compact: system.max_memory_compact,
+                max_memory_resident: system.max_memory_resident,
+                max_memory_abort: system.max_memory_abort,
+                max_num_threads: self.get_num_threads(),
+                min_offsets_per_thread: system.min_offsets_per_thread,
+                verbose: system.verbose,
+            },
+            band_divergence: self.band_divergence,
+            biwfa_fallback: self.biwfa_fallback().cloned(),
+        }
+    }
+
+    /// Aligner for similarity-style scores, see [`SimilarityScores`].
+    pub fn from_similarity(
+        scores: &SimilarityScores,
+        memory_mode: MemoryMode,
+    ) -> Result<Self, BuildError> {
+        AffineWavefrontsBuilder::new()
+            .similarity_scores(scores)?
+            .memory_mode(memory_mode)
+            .build()
+    }
+
+    /// Dual-affine aligner with the penalties of a minimap2-style preset, see
+    /// [`PenaltyPreset`].
+    pub fn from_preset(preset: PenaltyPreset, memory_mode: MemoryMode) -> Self {
+        let p = preset.penalties();
+        Self::with_penalties_affine2p_and_memory_mode(
+            p.match_score,
+            p.mismatch,
+            p.gap_opening1,
+            p.gap_extension1,
+            p.gap_opening2,
+            p.gap_extension2,
+            memory_mode,
+        )
+    }
+
     // Convenient constructor for bi-WFA with ultralow memory
     pub fn new_ultralow() -> Self {
         Self::with_penalties_affine2p_and_memory_mode(
@@ -718,7 +2356,215 @@ impl AffineWavefronts {
     }
 }
 
+/// Complete aligner configuration as plain data, so that pipeline settings can
+/// be stored (e.g. as JSON or TOML with the `serde` feature) and reproduced
+/// exactly. Fields mirror [`AffineWavefrontsBuilder`]; `max_alignment_steps`
+/// keeps WFA2's default when `None`. Fields missing from serialized
+/// configurations take their default values; unknown fields are rejected.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct AlignerConfig {
+    pub distance_metric: DistanceMetric,
+    pub match_score: i32,
+    pub mismatch: i32,
+    pub gap_opening1: i32,
+    pub gap_extension1: i32,
+    /// Second gap piece, used with `DistanceMetric::GapAffine2p`.
+    pub gap_opening2: Option<i32>,
+    pub gap_extension2: Option<i32>,
+    pub memory_mode: MemoryMode,
+    /// Heuristics applied together, see [`AffineWavefronts::set_heuristics`].
+    pub heuristics: Vec<HeuristicStrategy>,
+    pub alignment_scope: AlignmentScope,
+    pub alignment_span: AlignmentSpan,
+    pub max_alignment_steps: Option<i32>,
+    pub biwfa_fallback: Option<BiwfaFallback>,
+    /// Memory budget in bytes, see [`AffineWavefronts::set_max_memory`].
+    pub max_memory: Option<u64>,
+    /// Per-pair band, see [`AffineWavefronts::set_banded_for`].
+    pub band_divergence: Option<f64>,
+    /// Threads per alignment, see [`AffineWavefronts::set_num_threads`].
+    pub num_threads: Option<usize>,
+}
+
+impl Default for AlignerConfig {
+    fn default() -> Self {
+        AffineWavefrontsBuilder::default().into()
+    }
+}
+
+impl AlignerConfig {
+    /// # Panics
+    ///
+    /// If the configuration is invalid, see [`AffineWavefrontsBuilder::build_unchecked`].
+    pub fn build(&self) -> AffineWavefronts {
+        AffineWavefrontsBuilder::from(self.clone()).build_unchecked()
+    }
+
+    /// Like [`Self::build`], but an invalid configuration (e.g. from a
+    /// hand-edited file) is returned as an error.
+    pub fn try_build(&self) -> Result<AffineWavefronts, BuildError> {
+        AffineWavefrontsBuilder::from(self.clone()).build()
+    }
+
+    /// Builder with this configuration, to adjust some settings before
+    /// building.
+    pub fn to_builder(&self) -> AffineWavefrontsBuilder {
+        self.clone().into()
+    }
+}
+
+impl From<&AffineWavefronts> for AlignerConfig {
+    fn from(aligner: &AffineWavefronts) -> Self {
+        aligner.config()
+    }
+}
+
+// One line for logs, e.g. `affine(0,4,6,2) memory=high scope=alignment
+// span=end-to-end heuristic=xdrop:100,1`; unset optional settings are left out
+impl std::fmt::Display for AlignerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let piece = |value: Option<i32>| value.map_or("?".to_string(), |v| v.to_string());
+        match self.distance_metric {
+            DistanceMetric::Indel | DistanceMetric::Edit => write!(f, "{}", self.distance_metric)?,
+            DistanceMetric::GapAffine => write!(
+                f,
+                "affine({},{},{},{})",
+                self.match_score, self.mismatch, self.gap_opening1, self.gap_extension1
+            )?,
+            DistanceMetric::GapAffine2p => write!(
+                f,
+                "affine2p({},{},{},{},{},{})",
+                self.match_score,
+                self.mismatch,
+                self.gap_opening1,
+                self.gap_extension1,
+                piece(self.gap_opening2),
+                piece(self.gap_extension2)
+            )?,
+        }
+        write!(
+            f,
+            " memory={} scope={} span={}",
+            self.memory_mode, self.alignment_scope, self.alignment_span
+        )?;
+        for heuristic in &self.heuristics {
+            write!(f, " heuristic={heuristic}")?;
+        }
+        if let Some(steps) = self.max_alignment_steps {
+            write!(f, " max-steps={steps}")?;
+        }
+        if let Some(bytes) = self.max_memory {
+            write!(f, " max-memory={bytes}")?;
+        }
+        if let Some(divergence) = self.band_divergence {
+            write!(f, " band-divergence={divergence}")?;
+        }
+        if let Some(threads) = self.num_threads {
+            write!(f, " threads={threads}")?;
+        }
+        if let Some(fallback) = &self.biwfa_fallback {
+            write!(f, " fallback={fallback}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<AffineWavefrontsBuilder> for AlignerConfig {
+    fn from(builder: AffineWavefrontsBuilder) -> Self {
+        Self {
+            distance_metric: builder.distance_metric,
+            match_score: builder.match_score,
+            mismatch: builder.mismatch_penalty,
+            gap_opening1: builder.gap_opening1,
+            gap_extension1: builder.gap_extension1,
+            gap_opening2: builder.gap_opening2,
+            gap_extension2: builder.gap_extension2,
+            memory_mode: builder.memory_mode,
+            heuristics: builder.heuristics,
+            alignment_scope: builder.alignment_scope,
+            alignment_span: builder.alignment_span,
+            max_alignment_steps: builder.max_alignment_steps,
+            biwfa_fallback: builder.biwfa_fallback,
+            max_memory: builder.max_memory,
+            band_divergence: builder.band_divergence,
+            num_threads: builder.num_threads,
+        }
+    }
+}
+
+impl From<AlignerConfig> for AffineWavefrontsBuilder {
+    fn from(config: AlignerConfig) -> Self {
+        Self {
+            distance_metric: config.distance_metric,
+            match_score: config.match_score,
+            mismatch_penalty: config.mismatch,
+            gap_opening1: config.gap_opening1,
+            gap_extension1: config.gap_extension1,
+            gap_opening2: config.gap_opening2,
+            gap_extension2: config.gap_extension2,
+            memory_mode: config.memory_mode,
+            heuristics: config.heuristics,
+            alignment_scope: config.alignment_scope,
+            alignment_span: config.alignment_span,
+            max_alignment_steps: config.max_alignment_steps,
+            biwfa_fallback: config.biwfa_fallback,
+            max_memory: config.max_memory,
+            band_divergence: config.band_divergence,
+            num_threads: config.num_threads,
+        }
+    }
+}
+
+/// Effective settings of an aligner, mirroring WFA2's
+/// `wavefront_aligner_attr_t` plus what this crate applies on top (match
+/// normalization, per-pair band, bi-WFA fallback). Unlike [`AlignerConfig`]
+/// it is read back from the C aligner, so it shows exactly what the next
+/// alignment will use. See [`AffineWavefronts::attributes`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavefrontAttributes {
+    pub distance_metric: DistanceMetric,
+    /// Penalties as supplied, see [`AffineWavefronts::get_penalties`].
+    pub penalties: Penalties,
+    /// Penalties WFA2 works with, after any match normalization.
+    pub distance: Distance,
+    pub match_normalization: Option<MatchNormalization>,
+    pub memory_mode: MemoryMode,
+    pub heuristics: Vec<HeuristicStrategy>,
+    pub alignment_scope: AlignmentScope,
+    pub alignment_span: AlignmentSpan,
+    pub system: SystemLimits,
+    pub band_divergence: Option<f64>,
+    pub biwfa_fallback: Option<BiwfaFallback>,
+}
+
+/// Limits and tuning of WFA2's `alignment_system_t`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemLimits {
+    /// Score at which alignments stop with `MaxStepsReached`.
+    pub max_alignment_steps: i32,
+    /// Score steps between checks of the limits.
+    pub probe_interval_global: i32,
+    /// Score steps between checks whether to compact the backtrace buffer.
+    pub probe_interval_compact: i32,
+    /// Partial compactions of the backtrace buffer before a full one.
+    pub max_partial_compacts: u64,
+    /// Backtrace buffer size in bytes at which it is compacted.
+    pub max_memory_compact: u64,
+    /// Memory in bytes kept buffered between alignments before it is
+    /// released.
+    pub max_memory_resident: u64,
+    /// Memory in bytes at which alignments stop with `OOM`, `u64::MAX` if
+    /// unlimited.
+    pub max_memory_abort: u64,
+    pub max_num_threads: usize,
+    pub min_offsets_per_thread: i32,
+    pub verbose: i32,
+}
+
 // Builder pattern for more complex configurations
+#[derive(Debug, Clone)]
 pub struct AffineWavefrontsBuilder {
     distance_metric: DistanceMetric,
     match_score: i32,
@@ -728,8 +2574,14 @@ pub struct AffineWavefrontsBuilder {
     gap_opening2: Option<i32>,
     gap_extension2: Option<i32>,
     memory_mode: MemoryMode,
-    heuristic: HeuristicStrategy,
+    heuristics: Vec<HeuristicStrategy>,
     alignment_scope: AlignmentScope,
+    alignment_span: AlignmentSpan,
+    max_alignment_steps: Option<i32>,
+    biwfa_fallback: Option<BiwfaFallback>,
+    max_memory: Option<u64>,
+    band_divergence: Option<f64>,
+    num_threads: Option<usize>,
 }
 
 impl Default for AffineWavefrontsBuilder {
@@ -743,8 +2595,14 @@ impl Default for AffineWavefrontsBuilder {
             gap_opening2: None,
             gap_extension2: None,
             memory_mode: MemoryMode::High,
-            heuristic: HeuristicStrategy::None,
+            heuristics: Vec::new(),
             alignment_scope: AlignmentScope::Alignment,
+            alignment_span: AlignmentSpan::End2End,
+            max_alignment_steps: None,
+            biwfa_fallback: None,
+            max_memory: None,
+            band_divergence: None,
+            num_threads: None,
         }
     }
 }
@@ -774,13 +2632,40 @@ impl AffineWavefrontsBuilder {
         self
     }
 
+    /// Penalties (and distance metric) from similarity-style scores, see
+    /// [`SimilarityScores`].
+    pub fn similarity_scores(self, scores: &SimilarityScores) -> Result<Self, PenaltyError> {
+        Ok(match scores.to_penalties()? {
+            Penalties::GapAffine(p) => self.distance_metric(DistanceMetric::GapAffine).penalties(
+                p.match_score,
+                p.mismatch,
+                p.gap_opening,
+                p.gap_extension,
+            ),
+            Penalties::GapAffine2p(p) => self
+                .penalties(p.match_score, p.mismatch, p.gap_opening1, p.gap_extension1)
+                .dual_affine_penalties(p.gap_opening2, p.gap_extension2),
+            Penalties::Indel | Penalties::Edit => self,
+        })
+    }
+
     pub fn memory_mode(mut self, mode: MemoryMode) -> Self {
         self.memory_mode = mode;
         self
     }
 
-    pub fn heuristic(mut self, strategy: HeuristicStrategy) -> Self {
-        self.heuristic = strategy;
+    pub fn heuristic(self, strategy: HeuristicStrategy) -> Self {
+        self.heuristics(&[strategy])
+    }
+
+    /// Apply all of `strategies` together, see
+    /// [`AffineWavefronts::set_heuristics`].
+    pub fn heuristics(mut self, strategies: &[HeuristicStrategy]) -> Self {
+        self.heuristics = strategies
+            .iter()
+            .filter(|&strategy| *strategy != HeuristicStrategy::None)
+            .cloned()
+            .collect();
         self
     }
 
@@ -789,7 +2674,193 @@ impl AffineWavefrontsBuilder {
         self
     }
 
-    pub fn build(self) -> AffineWavefronts {
+    pub fn alignment_span(mut self, span: AlignmentSpan) -> Self {
+        self.alignment_span = span;
+        self
+    }
+
+    /// Ends-free (semi-global) span: up to `pattern_begin_free` leading and
+    /// `pattern_end_free` trailing pattern bases, and likewise for the text,
+    /// may be left unaligned at no cost.
+    pub fn ends_free(
+        self,
+        pattern_begin_free: i32,
+        pattern_end_free: i32,
+        text_begin_free: i32,
+        text_end_free: i32,
+    ) -> Self {
+        self.alignment_span(AlignmentSpan::EndsFree {
+            pattern_begin_free,
+            pattern_end_free,
+            text_begin_free,
+            text_end_free,
+        })
+    }
+
+    /// Give up (with `AlignmentStatus::MaxStepsReached`) once the alignment
+    /// score exceeds `steps`, see [`AffineWavefronts::set_max_alignment_steps`].
+    /// Must be positive.
+    pub fn max_alignment_steps(mut self, steps: i32) -> Self {
+        self.max_alignment_steps = Some(steps);
+        self
+    }
+
+    /// Band each pair by its lengths and `divergence`, see
+    /// [`AffineWavefronts::set_banded_for`].
+    pub fn banded_for(mut self, divergence: f64) -> Self {
+        self.band_divergence = Some(divergence);
+        self
+    }
+
+    /// Threads per alignment, see [`AffineWavefronts::set_num_threads`].
+    pub fn num_threads(mut self, threads: usize) -> Self {
+        self.num_threads = Some(threads);
+        self
+    }
+
+    /// Memory budget in bytes, see [`AffineWavefronts::set_max_memory`].
+    pub fn max_memory(mut self, bytes: u64) -> Self {
+        self.max_memory = Some(bytes);
+        self
+    }
+
+    /// Standard-WFA fallback for small pairs, see [`BiwfaFallback`]. Ignored
+    /// unless the memory mode is `MemoryMode::Ultralow`.
+    pub fn biwfa_fallback(mut self, fallback: BiwfaFallback) -> Self {
+        self.biwfa_fallback = Some(fallback);
+        self
+    }
+
+    /// Check the penalties of the configured distance metric without building
+    /// an aligner.
+    pub fn validate(&self) -> Result<(), PenaltyError> {
+        match self.distance_metric {
+            DistanceMetric::GapAffine => AffinePenalties {
+                match_score: self.match_score,
+                mismatch: self.mismatch_penalty,
+                gap_opening: self.gap_opening1,
+                gap_extension: self.gap_extension1,
+            }
+            .validate(),
+            DistanceMetric::GapAffine2p => Affine2pPenalties {
+                match_score: self.match_score,
+                mismatch: self.mismatch_penalty,
+                gap_opening1: self.gap_opening1,
+                gap_extension1: self.gap_extension1,
+                gap_opening2: self.gap_opening2.unwrap_or(12),
+                gap_extension2: self.gap_extension2.unwrap_or(1),
+            }
+            .validate(),
+            DistanceMetric::Indel | DistanceMetric::Edit => Ok(()),
+        }
+    }
+
+    /// Check every setting [`Self::build`] checks, without building an
+    /// aligner. Configurations that pass are safe for
+    /// [`Self::build_unchecked`].
+    pub fn check(&self) -> Result<(), BuildError> {
+        if self.distance_metric == DistanceMetric::Indel {
+            return Err(BuildError::UnsupportedMetric(self.distance_metric.clone()));
+        }
+        self.validate()?;
+        match (self.gap_opening2, self.gap_extension2) {
+            (Some(_), None) | (None, Some(_)) => return Err(BuildError::IncompleteDualAffine),
+            (Some(_), Some(_)) if self.distance_metric != DistanceMetric::GapAffine2p => {
+                return Err(BuildError::DualAffineWithoutAffine2p(
+                    self.distance_metric.clone(),
+                ));
+            }
+            _ => {}
+        }
+        if let Some(steps) = self.max_alignment_steps.filter(|&steps| steps <= 0) {
+            return Err(BuildError::NonPositiveMaxSteps(steps));
+        }
+        if let Some(divergence) = self
+            .band_divergence
+            .filter(|divergence| !(0.0..=1.0).contains(divergence))
+        {
+            return Err(BuildError::InvalidBandDivergence(divergence));
+        }
+        let fallback_mode = self.biwfa_fallback.as_ref().map(|f| &f.memory_mode);
+        if self.memory_mode == MemoryMode::Undefined
+            || fallback_mode == Some(&MemoryMode::Undefined)
+        {
+            return Err(BuildError::UndefinedMemoryMode);
+        }
+        if self.alignment_scope == AlignmentScope::Undefined {
+            return Err(BuildError::UndefinedScope);
+        }
+        let normalized = matches!(
+            self.distance_metric,
+            DistanceMetric::GapAffine | DistanceMetric::GapAffine2p
+        ) && self.match_score != 0;
+        if normalized && matches!(self.alignment_span, AlignmentSpan::EndsFree { .. }) {
+            return Err(BuildError::EndsFreeWithMatchBonus(self.match_score));
+        }
+        if self.biwfa_fallback.is_some() && self.memory_mode != MemoryMode::Ultralow {
+            return Err(BuildError::FallbackWithoutUltralow(
+                self.memory_mode.clone(),
+            ));
+        }
+        if let Some(threads) = self.num_threads {
+            // The conditions under which set_num_threads fails
+            let reason = if !crate::capabilities().openmp {
+                Some("WFA2 was built without OpenMP")
+            } else if threads == 0 {
+                Some("Number of threads must be positive")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                return Err(BuildError::Threads {
+                    threads,
+                    reason: reason.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [`Self::build`], but only the penalties are checked, so other
+    /// invalid settings still panic.
+    #[deprecated(note = "use `build`, which checks every setting")]
+    pub fn try_build(self) -> Result<AffineWavefronts, PenaltyError> {
+        self.validate()?;
+        Ok(self.build_unchecked())
+    }
+
+    /// Build the aligner, or report the first setting that is invalid or
+    /// inconsistent with the others (see [`BuildError`]).
+    pub fn build(mut self) -> Result<AffineWavefronts, BuildError> {
+        self.check()?;
+        // Applied here, as build_unchecked panics when they cannot be
+        let threads = self.num_threads.take();
+        let mut aligner = self.build_unchecked();
+        if let Some(threads) = threads {
+            aligner
+                .set_num_threads(threads)
+                .map_err(|reason| BuildError::Threads { threads, reason })?;
+        }
+        Ok(aligner)
+    }
+
+    /// Build the aligner without the checks of [`Self::build`]: settings
+    /// that would be ignored are ignored.
+    ///
+    /// # Panics
+    ///
+    /// If the penalties are invalid (see [`Self::validate`]), the distance
+    /// metric is not supported, `max_alignment_steps` is not positive, the
+    /// memory mode or scope is `Undefined` or `num_threads` cannot be
+    /// applied.
+    pub fn build_unchecked(self) -> AffineWavefronts {
+        if let Some(steps) = self.max_alignment_steps {
+            assert!(
+                steps > 0,
+                "max_alignment_steps must be positive, got {steps}"
+            );
+        }
+        let ultralow = self.memory_mode == MemoryMode::Ultralow;
         let mut aligner = match self.distance_metric {
             DistanceMetric::GapAffine => {
                 AffineWavefronts::with_penalties_and_memory_mode(
@@ -811,11 +2882,28 @@ impl AffineWavefrontsBuilder {
                     self.memory_mode,
                 )
             }
+            DistanceMetric::Edit => AffineWavefronts::with_edit_and_memory_mode(self.memory_mode),
             _ => panic!("Distance metric {:?} not yet supported in builder", self.distance_metric),
         };
 
-        aligner.set_heuristic(&self.heuristic);
+        aligner.set_heuristics(&self.heuristics);
         aligner.set_alignment_scope(self.alignment_scope);
+        aligner.set_alignment_span(self.alignment_span);
+        aligner.set_banded_for(self.band_divergence);
+        if ultralow {
+            aligner.set_biwfa_fallback(self.biwfa_fallback);
+        }
+        if let Some(steps) = self.max_alignment_steps {
+            aligner.set_max_alignment_steps(steps);
+        }
+        if let Some(bytes) = self.max_memory {
+            aligner.set_max_memory(bytes);
+        }
+        if let Some(threads) = self.num_threads {
+            if let Err(e) = aligner.set_num_threads(threads) {
+                panic!("Cannot use {threads} threads per alignment: {e}");
+            }
+        }
 
         aligner
     }
@@ -0,0 +1,101 @@
+//! Pinning of worker threads to CPU cores or NUMA nodes.
+//!
+//! Wavefront alignment is memory-bandwidth-bound; on multi-socket machines
+//! keeping a worker and its aligner's allocations on one NUMA node avoids
+//! cross-node traffic. Linux allocates pages on the node of the thread that
+//! first touches them, so pinning a worker before creating its aligner is
+//! enough to keep the wavefront buffers local. The pool and batch APIs do
+//! this for their workers through [`BatchOptions`](crate::batch::BatchOptions).
+
+/// How worker threads are placed on CPU cores.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CorePinning {
+    /// Let the OS scheduler place threads.
+    #[default]
+    None,
+    /// Pin worker `i` to `cores[i % cores.len()]`.
+    Cores(Vec<usize>),
+    /// Pin workers round-robin to the cores of one NUMA node.
+    NumaNode(usize),
+}
+
+impl CorePinning {
+    /// Pin the calling thread as worker `worker_index`. Call this at worker
+    /// start-up, before creating the worker's aligner.
+    pub fn apply(&self, worker_index: usize) -> Result<(), String> {
+        let cores = match self {
+            Self::None => return Ok(()),
+            Self::Cores(cores) => cores.clone(),
+            Self::NumaNode(node) => numa_node_cores(*node)?,
+        };
+        if cores.is_empty() {
+            return Err("No cores to pin to".to_string());
+        }
+        pin_current_thread(&[cores[worker_index % cores.len()]])
+    }
+}
+
+/// CPU cores belonging to a NUMA node, as listed in sysfs.
+pub fn numa_node_cores(node: usize) -> Result<Vec<usize>, String> {
+    let path = format!("/sys/devices/system/node/node{node}/cpulist");
+    let list = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot read cores of NUMA node {node} ({path}): {e}"))?;
+    parse_cpu_list(list.trim())
+}
+
+// Parse the kernel's cpulist format, e.g. "0-3,8,10-11"
+fn parse_cpu_list(list: &str) -> Result<Vec<usize>, String> {
+    let parse = |s: &str| {
+        s.parse::<usize>()
+            .map_err(|_| format!("Invalid CPU list entry: {s}"))
+    };
+    let mut cores = Vec::new();
+    for range in list.split(',').filter(|r| !r.is_empty()) {
+        match range.split_once('-') {
+            Some((first, last)) => cores.extend(parse(first)?..=parse(last)?),
+            None => cores.push(parse(range)?),
+        }
+    }
+    Ok(cores)
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    // Matches glibc's cpu_set_t (1024 CPUs)
+    pub const CPU_SET_WORDS: usize = 16;
+
+    extern "C" {
+        pub fn sched_setaffinity(
+            pid: std::os::raw::c_int,
+            cpusetsize: usize,
+            mask: *const u64,
+        ) -> std::os::raw::c_int;
+    }
+}
+
+/// Restrict the calling thread to the given CPU cores.
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(cores: &[usize]) -> Result<(), String> {
+    let mut mask = [0u64; sys::CPU_SET_WORDS];
+    for &core in cores {
+        if core >= sys::CPU_SET_WORDS * 64 {
+            return Err(format!("Core {core} is out of range"));
+        }
+        mask[core / 64] |= 1 << (core % 64);
+    }
+    // pid 0 is the calling thread
+    let ret = unsafe { sys::sched_setaffinity(0, std::mem::size_of_val(&mask), mask.as_ptr()) };
+    if ret != 0 {
+        return Err(format!(
+            "sched_setaffinity failed: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+/// Restrict the calling thread to the given CPU cores.
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(_cores: &[usize]) -> Result<(), String> {
+    Err("Core pinning is only supported on Linux".to_string())
+}
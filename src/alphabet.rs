@@ -0,0 +1,108 @@
+//! Sequence representations other than plain bytes, for use with
+//! [`AffineWavefronts::align_encoded`](crate::affine_wavefront::AffineWavefronts::align_encoded).
+
+/// Random access to the symbols of an encoded sequence. Two positions match
+/// when their codes are equal, so both sequences of an alignment must use the
+/// same encoding.
+pub trait EncodedSequence {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Code of the symbol at `index` (`index < len()`).
+    fn code(&self, index: usize) -> u8;
+}
+
+impl EncodedSequence for [u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn code(&self, index: usize) -> u8 {
+        self[index]
+    }
+}
+
+impl EncodedSequence for Vec<u8> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn code(&self, index: usize) -> u8 {
+        self[index]
+    }
+}
+
+/// DNA packed at 2 bits per base (A=0, C=1, G=2, T=3), four bases per byte
+/// starting from the low bits.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PackedDna {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl PackedDna {
+    /// Pack an ASCII sequence. Fails on symbols other than `ACGT` (either
+    /// case), which 2 bits cannot represent.
+    pub fn from_ascii(sequence: &[u8]) -> Result<Self, String> {
+        let mut bytes = vec![0u8; (sequence.len() + 3) / 4];
+        for (i, &base) in sequence.iter().enumerate() {
+            let code = match base.to_ascii_uppercase() {
+                b'A' => 0,
+                b'C' => 1,
+                b'G' => 2,
+                b'T' => 3,
+                other => {
+                    return Err(format!(
+                        "Cannot pack '{}' at position {i} into 2 bits",
+                        other as char
+                    ))
+                }
+            };
+            bytes[i / 4] |= code << (2 * (i % 4));
+        }
+        Ok(Self {
+            bytes,
+            len: sequence.len(),
+        })
+    }
+}
+
+impl EncodedSequence for PackedDna {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn code(&self, index: usize) -> u8 {
+        (self.bytes[index / 4] >> (2 * (index % 4))) & 0b11
+    }
+}
+
+#[cfg(feature = "bio-seq")]
+mod bio_seq_support {
+    use super::EncodedSequence;
+    use bio_seq::codec::Codec;
+    use bio_seq::seq::{Seq, SeqSlice};
+
+    impl<A: Codec> EncodedSequence for SeqSlice<A> {
+        fn len(&self) -> usize {
+            SeqSlice::len(self)
+        }
+
+        fn code(&self, index: usize) -> u8 {
+            self.get(index).map_or(u8::MAX, Codec::to_bits)
+        }
+    }
+
+    impl<A: Codec> EncodedSequence for Seq<A> {
+        fn len(&self) -> usize {
+            SeqSlice::len(self)
+        }
+
+        fn code(&self, index: usize) -> u8 {
+            self.get(index).map_or(u8::MAX, Codec::to_bits)
+        }
+    }
+}
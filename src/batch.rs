@@ -0,0 +1,450 @@
+use crate::affine_wavefront::AffineWavefronts;
+#[cfg(feature = "rayon")]
+use crate::affine_wavefront::{AffineWavefrontsBuilder, AlignerConfig, Alignment, BuildError};
+use crate::affinity::CorePinning;
+use crate::options::AlignmentResult;
+use crate::orient::Strand;
+use crate::scheduler::{MemoryBudget, MemoryReservation};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// A pair of sequences to align, identified so finished work can be skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchPair {
+    pub id: String,
+    pub query: Vec<u8>,
+    pub target: Vec<u8>,
+}
+
+/// Progress of a resumable batch: the pairs whose results are safely on disk
+/// and the length of the results file that covers them.
+///
+/// The ids of completed pairs are appended to a journal next to the
+/// checkpoint file (its path with `.journal` appended), so each commit only
+/// writes the new ids. The checkpoint file itself holds the results and
+/// journal lengths and is replaced atomically; journal entries past the
+/// recorded length, left by a crash mid-commit, are ignored and overwritten.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Checkpoint {
+    pub completed: HashSet<String>,
+    pub results_offset: u64,
+    /// Length of the journal covered by this checkpoint.
+    pub journal_offset: u64,
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Pair ids are stored one per line, so they cannot contain line breaks.
+fn check_pair_id(id: &str) -> io::Result<()> {
+    if id.contains(['\n', '\r']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Pair id {id:?} contains a line break"),
+        ));
+    }
+    Ok(())
+}
+
+impl Checkpoint {
+    fn journal_path(path: &Path) -> PathBuf {
+        let mut journal = path.as_os_str().to_owned();
+        journal.push(".journal");
+        PathBuf::from(journal)
+    }
+
+    /// Load a checkpoint and replay its journal, or return an empty one if
+    /// the checkpoint file does not exist.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        let mut lines = BufReader::new(file).lines();
+        let mut offset = |name: &str| -> io::Result<u64> {
+            let line = lines.next().transpose()?.unwrap_or_default();
+            line.strip_prefix(name)
+                .and_then(|value| value.strip_prefix('\t'))
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| invalid_data(format!("Invalid checkpoint line: {line}")))
+        };
+        let results_offset = offset("offset")?;
+        let journal_offset = offset("journal")?;
+
+        let mut journal = Vec::new();
+        if journal_offset > 0 {
+            File::open(Self::journal_path(path))?
+                .take(journal_offset)
+                .read_to_end(&mut journal)?;
+        }
+        if journal.len() as u64 != journal_offset {
+            return Err(invalid_data(format!(
+                "Checkpoint journal is shorter than the {journal_offset} bytes recorded"
+            )));
+        }
+        let completed = String::from_utf8(journal)
+            .map_err(|e| invalid_data(format!("Invalid checkpoint journal: {e}")))?
+            .lines()
+            .map(str::to_string)
+            .collect();
+        Ok(Self {
+            completed,
+            results_offset,
+            journal_offset,
+        })
+    }
+
+    /// Record `ids` as completed, with the results file now `results_offset`
+    /// bytes long: the ids are appended to the journal and synced, then the
+    /// new lengths are written atomically (temporary file + rename), so a
+    /// crash at any point leaves the previous checkpoint intact.
+    pub fn commit<I>(&mut self, path: &Path, ids: I, results_offset: u64) -> io::Result<()>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let ids: Vec<String> = ids.into_iter().collect();
+        for id in &ids {
+            check_pair_id(id)?;
+        }
+        let journal = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(Self::journal_path(path))?;
+        journal.set_len(self.journal_offset)?;
+        let mut out = BufWriter::new(journal);
+        out.seek(io::SeekFrom::Start(self.journal_offset))?;
+        for id in &ids {
+            writeln!(out, "{id}")?;
+        }
+        out.flush()?;
+        out.get_ref().sync_data()?;
+        let journal_offset = out.stream_position()?;
+
+        let tmp = path.with_extension("tmp");
+        {
+            let mut out = File::create(&tmp)?;
+            write!(out, "offset\t{results_offset}\njournal\t{journal_offset}\n")?;
+            out.sync_all()?;
+        }
+        fs::rename(tmp, path)?;
+        self.completed.extend(ids);
+        self.results_offset = results_offset;
+        self.journal_offset = journal_offset;
+        Ok(())
+    }
+}
+
+/// Outcome of a (possibly resumed) batch run.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BatchSummary {
+    pub aligned: usize,
+    pub skipped: usize,
+}
+
+/// Batch runner that writes one TSV line per pair
+/// (`id  status  score  cigar`, plus the alignment's duration in seconds if
+/// the aligner records timing) and periodically checkpoints its progress.
+///
+/// After a crash or preemption, running the same batch again truncates the
+/// results file to the last checkpoint and only aligns the pairs that were not
+/// recorded as completed.
+pub struct ResumableBatch {
+    results_path: PathBuf,
+    checkpoint_path: PathBuf,
+    checkpoint_every: usize,
+}
+
+impl ResumableBatch {
+    pub fn new(results_path: impl Into<PathBuf>, checkpoint_path: impl Into<PathBuf>) -> Self {
+        Self {
+            results_path: results_path.into(),
+            checkpoint_path: checkpoint_path.into(),
+            checkpoint_every: 1000,
+        }
+    }
+
+    /// Number of aligned pairs between checkpoints (default 1000).
+    pub fn checkpoint_every(mut self, pairs: usize) -> Self {
+        self.checkpoint_every = pairs.max(1);
+        self
+    }
+
+    pub fn run<I>(&self, aligner: &AffineWavefronts, pairs: I) -> io::Result<BatchSummary>
+    where
+        I: IntoIterator<Item = BatchPair>,
+    {
+        let mut checkpoint = Checkpoint::load(&self.checkpoint_path)?;
+
+        // Drop results written after the last checkpoint; those pairs are redone
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&self.results_path)?;
+        file.set_len(checkpoint.results_offset)?;
+        let mut out = BufWriter::new(file);
+        out.seek(io::SeekFrom::Start(checkpoint.results_offset))?;
+
+        let mut summary = BatchSummary::default();
+        let mut pending = Vec::new();
+        for pair in pairs {
+            if checkpoint.completed.contains(&pair.id) {
+                summary.skipped += 1;
+                continue;
+            }
+            check_pair_id(&pair.id)?;
+            let status = aligner.align(&pair.query, &pair.target);
+            write!(
+                out,
+                "{}\t{:?}\t{}\t{}",
+                pair.id,
+                status,
+                aligner.score(),
+                String::from_utf8_lossy(aligner.cigar())
+            )?;
+            if let Some(elapsed) = aligner.last_alignment_duration() {
+                write!(out, "\t{}", elapsed.as_secs_f64())?;
+            }
+            writeln!(out)?;
+            pending.push(pair.id);
+            summary.aligned += 1;
+
+            if pending.len() >= self.checkpoint_every {
+                self.commit(&mut out, &mut checkpoint, &mut pending)?;
+            }
+        }
+        self.commit(&mut out, &mut checkpoint, &mut pending)?;
+        Ok(summary)
+    }
+
+    fn commit(
+        &self,
+        out: &mut BufWriter<File>,
+        checkpoint: &mut Checkpoint,
+        pending: &mut Vec<String>,
+    ) -> io::Result<()> {
+        out.flush()?;
+        out.get_ref().sync_data()?;
+        let results_offset = out.stream_position()?;
+        checkpoint.commit(&self.checkpoint_path, pending.drain(..), results_offset)
+    }
+}
+
+/// Scheduling options of the parallel batch APIs
+/// ([`stream_batch_with_options`], `align_pairs_parallel_with_options` and
+/// [`AlignerPool::with_options`](crate::pool::AlignerPool::with_options)).
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Budget shared by the workers: each alignment reserves its estimated
+    /// memory (see [`estimate_alignment_memory`](crate::scheduler::estimate_alignment_memory))
+    /// while it runs, and waits while that would exceed the budget.
+    pub memory_budget: Option<Arc<MemoryBudget>>,
+    /// Expected divergence (0.0 - 1.0) of the pairs, for the memory estimates.
+    pub divergence: f64,
+    /// Placement of the worker threads, applied by each worker before it
+    /// builds its aligner. Workers that cannot be pinned (e.g. outside
+    /// Linux) run unpinned.
+    pub pinning: CorePinning,
+    /// Record the wall-clock duration of every alignment in its result
+    /// (see [`AffineWavefronts::set_record_timing`]).
+    pub record_timing: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            memory_budget: None,
+            divergence: 0.1,
+            pinning: CorePinning::None,
+            record_timing: false,
+        }
+    }
+}
+
+impl BatchOptions {
+    // Reservation for aligning `pattern` against `text` with `aligner`,
+    // waiting for the budget if needed
+    pub(crate) fn reserve(
+        &self,
+        aligner: &AffineWavefronts,
+        pattern: &[u8],
+        text: &[u8],
+    ) -> Option<MemoryReservation<'_>> {
+        self.memory_budget.as_ref().map(|budget| {
+            budget.acquire_for(
+                pattern.len(),
+                text.len(),
+                &aligner.get_memory_mode(),
+                self.divergence,
+            )
+        })
+    }
+}
+
+/// Results of [`stream_batch`] in completion order, as `(index, result)` with
+/// `index` the position of the pair in the input. Dropping the stream stops
+/// the workers after their current pair.
+pub struct BatchStream {
+    results: Option<Receiver<(usize, AlignmentResult)>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl Iterator for BatchStream {
+    type Item = (usize, AlignmentResult);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.results.as_ref()?.recv().ok()
+    }
+}
+
+impl Drop for BatchStream {
+    fn drop(&mut self) {
+        // Closing the channel makes blocked workers give up
+        self.results.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Align `pairs` on `threads` worker threads and yield each result as soon as
+/// its pair finishes, regardless of input order. Every worker creates its own
+/// aligner with `make_aligner`. Pairs are pulled from the input lazily and at
+/// most `threads` finished results wait to be consumed, so memory stays flat
+/// however large the batch or however slow the consumer. For a callback
+/// interface, call `for_each` on the returned stream.
+pub fn stream_batch<I, F>(pairs: I, threads: usize, make_aligner: F) -> BatchStream
+where
+    I: IntoIterator<Item = BatchPair>,
+    I::IntoIter: Send + 'static,
+    F: Fn() -> AffineWavefronts + Send + Sync + 'static,
+{
+    stream_batch_with_options(pairs, threads, BatchOptions::default(), make_aligner)
+}
+
+/// Like [`stream_batch`], with the scheduling of `options`, e.g. a memory
+/// budget that holds back large pairs while others are in flight.
+pub fn stream_batch_with_options<I, F>(
+    pairs: I,
+    threads: usize,
+    options: BatchOptions,
+    make_aligner: F,
+) -> BatchStream
+where
+    I: IntoIterator<Item = BatchPair>,
+    I::IntoIter: Send + 'static,
+    F: Fn() -> AffineWavefronts + Send + Sync + 'static,
+{
+    let threads = threads.max(1);
+    let options = Arc::new(options);
+    let pairs = Arc::new(Mutex::new(pairs.into_iter().enumerate()));
+    let make_aligner = Arc::new(make_aligner);
+    let (sender, receiver) = mpsc::sync_channel(threads);
+
+    let workers = (0..threads)
+        .map(|worker_index| {
+            let pairs = Arc::clone(&pairs);
+            let make_aligner = Arc::clone(&make_aligner);
+            let options = Arc::clone(&options);
+            let sender = sender.clone();
+            thread::spawn(move || {
+                let _ = options.pinning.apply(worker_index);
+                let mut aligner = make_aligner();
+                if options.record_timing {
+                    aligner.set_record_timing(true);
+                }
+                loop {
+                    let next = pairs.lock().map(|mut pairs| pairs.next());
+                    let Ok(Some((index, pair))) = next else {
+                        break;
+                    };
+                    let reservation = options.reserve(&aligner, &pair.query, &pair.target);
+                    let status = aligner.align(&pair.query, &pair.target);
+                    drop(reservation);
+                    let result = AlignmentResult {
+                        strand: Strand::Forward,
+                        status,
+                        score: aligner.score(),
+                        cigar: aligner.cigar().to_vec(),
+                        elapsed: aligner.last_alignment_duration(),
+                    };
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+            })
+        })
+        .collect();
+
+    BatchStream {
+        results: Some(receiver),
+        workers,
+    }
+}
+
+/// Align `pairs` on rayon's thread pool and return the alignments in input
+/// order. Every worker thread gets its own aligner built from `config` (see
+/// [`AlignerPool`](crate::pool::AlignerPool)), reused for all the pairs it
+/// takes. Fails before aligning anything if `config` is invalid.
+#[cfg(feature = "rayon")]
+pub fn align_pairs_parallel<P, T>(
+    config: &AlignerConfig,
+    pairs: &[(P, T)],
+) -> Result<Vec<Alignment>, BuildError>
+where
+    P: AsRef<[u8]> + Sync,
+    T: AsRef<[u8]> + Sync,
+{
+    align_pairs_parallel_with_options(config, pairs, &BatchOptions::default())
+}
+
+/// Like [`align_pairs_parallel`], with the scheduling of `options`. With
+/// core pinning, the pairs are aligned on a dedicated rayon thread pool whose
+/// threads are pinned as they start, so rayon's global pool is left as is.
+#[cfg(feature = "rayon")]
+pub fn align_pairs_parallel_with_options<P, T>(
+    config: &AlignerConfig,
+    pairs: &[(P, T)],
+    options: &BatchOptions,
+) -> Result<Vec<Alignment>, BuildError>
+where
+    P: AsRef<[u8]> + Sync,
+    T: AsRef<[u8]> + Sync,
+{
+    use rayon::prelude::*;
+
+    // The worker threads are pinned here, not by the aligner pool
+    let pool = crate::pool::AlignerPool::new(AffineWavefrontsBuilder::from(config.clone()))?
+        .with_options(BatchOptions {
+            pinning: CorePinning::None,
+            ..options.clone()
+        });
+    let align = || {
+        pairs
+            .par_iter()
+            .map(|(pattern, text)| pool.align(pattern.as_ref(), text.as_ref()))
+            .collect()
+    };
+    if options.pinning == CorePinning::None {
+        return Ok(align());
+    }
+    let pinning = options.pinning.clone();
+    let threads = rayon::ThreadPoolBuilder::new()
+        .start_handler(move |worker_index| {
+            let _ = pinning.apply(worker_index);
+        })
+        .build();
+    Ok(match threads {
+        Ok(threads) => threads.install(align),
+        // Unpinned, as for workers that cannot be pinned
+        Err(_) => align(),
+    })
+}
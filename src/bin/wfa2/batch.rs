@@ -0,0 +1,190 @@
+//! `wfa2 batch`: align many pairs on several threads.
+
+use crate::options::{self, AlignerOptions};
+use crate::output::OutputFormat;
+use crate::{flag_value, next_token, open_input, Token};
+use lib_wfa2::affine_wavefront::Alignment;
+use lib_wfa2::batch::{stream_batch, BatchPair};
+use lib_wfa2::io::{PairTable, RecordPairs, SequencePair};
+use lib_wfa2::sequence::FastxReader;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::sync::{Arc, Mutex};
+
+const BATCH_USAGE: &str = "\
+Usage: wfa2 batch [OPTIONS] <QUERIES> <TARGETS>
+       wfa2 batch [OPTIONS] --pairs <TSV>
+
+Align the n-th record of QUERIES against the n-th record of TARGETS (FASTA
+or FASTQ), or the pairs of a TSV with `query target` or `id query target`
+lines, and write one record per pair in input order. TSV records have the
+query name, target name, query length, target length, status, score,
+identity and CIGAR. Use `-` to read standard input. SAM output has `@SQ`
+lines for TARGETS when it is a file, and none for TSV pairs.
+
+Options:
+  -f, --output-format <FMT>   tsv, paf, sam or json (JSON Lines) [tsv]
+      --pairs <TSV>           Read pairs from a TSV instead
+  -t, --threads <INT>         Pairs aligned in parallel [all cores]
+      --output <FILE>         Write results to FILE [standard output]
+  -h, --help                  Print help
+";
+
+type PairResult = io::Result<SequencePair>;
+
+pub fn batch(mut parser: lexopt::Parser) -> Result<(), String> {
+    let mut aligner_options = AlignerOptions::default();
+    let mut files = Vec::new();
+    let mut pairs_path: Option<String> = None;
+    let mut output: Option<String> = None;
+    let mut format = OutputFormat::default();
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    while let Some(token) = next_token(&mut parser)? {
+        let flag = match token {
+            Token::Value(value) => {
+                files.push(value);
+                continue;
+            }
+            Token::Flag(flag) => flag,
+        };
+        if aligner_options.parse(&flag, &mut parser)? {
+            continue;
+        }
+        match flag.as_str() {
+            "-f" | "--output-format" => format = flag_value(&mut parser)?,
+            "--pairs" => pairs_path = Some(flag_value(&mut parser)?),
+            "-t" | "--threads" => threads = flag_value(&mut parser)?,
+            "--output" => output = Some(flag_value(&mut parser)?),
+            "-h" | "--help" => {
+                print!("{BATCH_USAGE}\n{}", options::HELP);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option '{flag}'\n\n{BATCH_USAGE}")),
+        }
+    }
+    let pairs: Box<dyn Iterator<Item = PairResult> + Send> = match (&pairs_path, files.as_slice()) {
+        (Some(path), []) => Box::new(PairTable::new(open_input(path)?)),
+        (None, [queries, targets]) => {
+            Box::new(RecordPairs::new(open_input(queries)?, open_input(targets)?))
+        }
+        _ => {
+            return Err(format!(
+                "Expected two sequence files or --pairs\n\n{BATCH_USAGE}"
+            ))
+        }
+    };
+    // Fail on bad options before starting the workers
+    aligner_options.build()?;
+
+    let out: Box<dyn Write> = match &output {
+        Some(path) => {
+            Box::new(File::create(path).map_err(|e| format!("Cannot create '{path}': {e}"))?)
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut out = BufWriter::new(out);
+    let targets = match files.as_slice() {
+        [_, targets] if format == OutputFormat::Sam && targets != "-" => target_lengths(targets)?,
+        _ => Vec::new(),
+    };
+    let targets: Vec<(&str, usize)> = targets
+        .iter()
+        .map(|(name, len)| (name.as_str(), *len))
+        .collect();
+    format
+        .write_header(&mut out, &targets)
+        .map_err(|e| e.to_string())?;
+    run_batch(pairs, threads, aligner_options, format, out)
+}
+
+// Names and lengths of the records of a sequence file
+fn target_lengths(path: &str) -> Result<Vec<(String, usize)>, String> {
+    FastxReader::new(open_input(path)?)
+        .map(|record| {
+            record
+                .map(|record| (record.name, record.sequence.len()))
+                .map_err(|e| format!("Cannot read '{path}': {e}"))
+        })
+        .collect()
+}
+
+// Workers pull pairs from `Pairs`, which keeps every pair until its result is
+// written and records the first input error
+#[derive(Default)]
+struct Shared {
+    pending: HashMap<usize, SequencePair>,
+    error: Option<String>,
+}
+
+struct Pairs {
+    inner: Box<dyn Iterator<Item = PairResult> + Send>,
+    next_index: usize,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Iterator for Pairs {
+    type Item = BatchPair;
+
+    fn next(&mut self) -> Option<BatchPair> {
+        let next = self.inner.next()?;
+        let mut shared = self.shared.lock().unwrap();
+        match next {
+            Ok(pair) => {
+                shared.pending.insert(self.next_index, pair.clone());
+                self.next_index += 1;
+                Some(pair.into())
+            }
+            Err(e) => {
+                shared.error = Some(e.to_string());
+                None
+            }
+        }
+    }
+}
+
+fn run_batch(
+    pairs: Box<dyn Iterator<Item = PairResult> + Send>,
+    threads: usize,
+    aligner_options: AlignerOptions,
+    format: OutputFormat,
+    mut out: impl Write,
+) -> Result<(), String> {
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    let pairs = Pairs {
+        inner: pairs,
+        next_index: 0,
+        shared: Arc::clone(&shared),
+    };
+    let make_aligner = move || {
+        aligner_options
+            .build()
+            .expect("aligner options were checked before starting")
+    };
+
+    // Results arrive in completion order; write them in input order
+    let mut finished = BTreeMap::new();
+    let mut next = 0;
+    for (index, result) in stream_batch(pairs.fuse(), threads, make_aligner) {
+        finished.insert(index, result);
+        while let Some(result) = finished.remove(&next) {
+            let pair = shared.lock().unwrap().pending.remove(&next);
+            let SequencePair { query, target } = pair.expect("every result has its pair");
+            let alignment = Alignment {
+                status: result.status,
+                score: result.score,
+                cigar: result.cigar,
+                pattern_end: query.sequence.len(),
+                text_end: target.sequence.len(),
+                elapsed: result.elapsed,
+            };
+            format
+                .write_record(&mut out, &query, &target, &alignment)
+                .map_err(|e| e.to_string())?;
+            next += 1;
+        }
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    let error = shared.lock().unwrap().error.take();
+    error.map_or(Ok(()), Err)
+}
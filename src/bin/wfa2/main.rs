@@ -0,0 +1,197 @@
+//! `wfa2` command-line aligner (feature `cli`).
+
+mod batch;
+mod options;
+mod output;
+
+use lexopt::prelude::*;
+use lib_wfa2::cigar::{format_alignment_with, FormatOptions};
+use lib_wfa2::sequence::{FastxReader, FastxRecord};
+use options::AlignerOptions;
+use output::OutputFormat;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process;
+
+const USAGE: &str = "\
+Usage: wfa2 <COMMAND> [OPTIONS]
+
+Commands:
+  align   Align two sequences
+  batch   Align pairs of sequences from FASTA/FASTQ files or a TSV
+
+Run `wfa2 <COMMAND> --help` for the options of a command.
+";
+
+const ALIGN_USAGE: &str = "\
+Usage: wfa2 align [OPTIONS] <QUERY> <TARGET>
+
+Align QUERY against TARGET end to end and print the score, CIGAR and
+identity, or a record in the format given by --output-format. Each of them
+is a sequence or the path of a FASTA or FASTQ file, whose first record is
+used.
+
+Options:
+  -f, --output-format <FMT>   tsv, paf, sam or json (JSON Lines)
+  -p, --pretty                Also print the alignment
+  -w, --width <INT>           Columns per block of the alignment, 0 for no
+                              wrapping [60]
+  -h, --help                  Print help
+";
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("wfa2: {e}");
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut parser = lexopt::Parser::from_env();
+    match parser.next().map_err(|e| e.to_string())? {
+        Some(Value(command)) if command == "align" => align(parser),
+        Some(Value(command)) if command == "batch" => batch::batch(parser),
+        Some(Short('h') | Long("help")) | None => {
+            print!("{USAGE}");
+            Ok(())
+        }
+        Some(Value(command)) => Err(format!(
+            "Unknown command '{}'\n\n{USAGE}",
+            command.to_string_lossy()
+        )),
+        Some(arg) => Err(arg.unexpected().to_string()),
+    }
+}
+
+/// A command-line argument, with flags spelled out (`-x` or `--name`) so
+/// that the parser is free to read their value.
+pub enum Token {
+    Flag(String),
+    Value(String),
+}
+
+pub fn next_token(parser: &mut lexopt::Parser) -> Result<Option<Token>, String> {
+    let token = match parser.next().map_err(|e| e.to_string())? {
+        Some(Value(value)) => Token::Value(value.string().map_err(|e| e.to_string())?),
+        Some(Short(c)) => Token::Flag(format!("-{c}")),
+        Some(Long(name)) => Token::Flag(format!("--{name}")),
+        None => return Ok(None),
+    };
+    Ok(Some(token))
+}
+
+/// Value of the current flag, parsed.
+pub fn flag_value<T>(parser: &mut lexopt::Parser) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let value = parser
+        .value()
+        .and_then(|value| value.string())
+        .map_err(|e| e.to_string())?;
+    value
+        .parse()
+        .map_err(|e| format!("Invalid value '{value}': {e}"))
+}
+
+/// Buffered reader of a file, or of standard input for `-`, decompressed if
+/// needed.
+pub fn open_input(path: &str) -> Result<Box<dyn BufRead + Send>, String> {
+    let input = if path == "-" {
+        lib_wfa2::io::reader(io::stdin())
+    } else {
+        lib_wfa2::io::open(path)
+    };
+    input.map_err(|e| e.to_string())
+}
+
+fn align(mut parser: lexopt::Parser) -> Result<(), String> {
+    let mut aligner_options = AlignerOptions::default();
+    let mut sequences = Vec::new();
+    let mut format: Option<OutputFormat> = None;
+    let mut pretty = false;
+    let mut width = 60;
+    while let Some(token) = next_token(&mut parser)? {
+        let flag = match token {
+            Token::Value(value) => {
+                sequences.push(value);
+                continue;
+            }
+            Token::Flag(flag) => flag,
+        };
+        if aligner_options.parse(&flag, &mut parser)? {
+            continue;
+        }
+        match flag.as_str() {
+            "-f" | "--output-format" => format = Some(flag_value(&mut parser)?),
+            "-p" | "--pretty" => pretty = true,
+            "-w" | "--width" => width = flag_value(&mut parser)?,
+            "-h" | "--help" => {
+                print!("{ALIGN_USAGE}\n{}", options::HELP);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option '{flag}'\n\n{ALIGN_USAGE}")),
+        }
+    }
+    let [query, target] = sequences.as_slice() else {
+        return Err(format!("Expected a query and a target\n\n{ALIGN_USAGE}"));
+    };
+    if pretty && format.is_some() {
+        return Err("--pretty cannot be combined with --output-format".to_string());
+    }
+    let query = read_sequence(query, "query")?;
+    let target = read_sequence(target, "target")?;
+
+    let aligner = aligner_options.build()?;
+    let status = aligner.align(&query.sequence, &target.sequence);
+    let mut out = io::stdout().lock();
+    match format {
+        Some(format) => {
+            let alignment = aligner.alignment(status);
+            let targets = [(target.name.as_str(), target.sequence.len())];
+            format
+                .write_header(&mut out, &targets)
+                .and_then(|_| format.write_record(&mut out, &query, &target, &alignment))
+                .map_err(|e| e.to_string())?;
+        }
+        None => {
+            status.ok().map_err(|e| e.to_string())?;
+            writeln!(
+                out,
+                "score\t{}\ncigar\t{}\nidentity\t{:.6}",
+                aligner.score(),
+                aligner.cigar_string(),
+                aligner.stats().identity()
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    if pretty {
+        let options = FormatOptions {
+            width,
+            color: io::stdout().is_terminal(),
+        };
+        let alignment =
+            format_alignment_with(&query.sequence, &target.sequence, aligner.cigar(), &options)?;
+        write!(out, "\n{alignment}").map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// A sequence given on the command line (named `name`), or the first record
+// of the FASTA or FASTQ file at that path
+fn read_sequence(arg: &str, name: &str) -> Result<FastxRecord, String> {
+    if !Path::new(arg).is_file() {
+        return Ok(FastxRecord {
+            name: name.to_string(),
+            sequence: arg.as_bytes().to_vec(),
+            quality: None,
+        });
+    }
+    match FastxReader::new(open_input(arg)?).next() {
+        Some(Ok(record)) => Ok(record),
+        Some(Err(e)) => Err(format!("Cannot read '{arg}': {e}")),
+        None => Err(format!("'{arg}' has no records")),
+    }
+}
@@ -0,0 +1,245 @@
+//! Aligner flags shared by the subcommands.
+
+use crate::flag_value;
+use lib_wfa2::affine_wavefront::{
+    AffineWavefronts, AffineWavefrontsBuilder, DistanceMetric, HeuristicStrategy, MemoryMode,
+    PenaltyPreset,
+};
+
+pub const HELP: &str = "\
+Aligner options:
+      --distance <METRIC>     edit, indel, affine or affine2p [affine, or affine2p
+                              when second gap penalties are given]
+      --preset <NAME>         Penalties of a minimap2 preset (map-ont, map-pb,
+                              map-hifi, asm5, asm10, asm20)
+  -a, --match <INT>           Match score, 0 or negative [0]
+  -x, --mismatch <INT>        Mismatch penalty [4]
+  -o, --gap-open <INT>        Gap opening penalty [6]
+  -e, --gap-ext <INT>         Gap extension penalty [2]
+  -O, --gap-open2 <INT>       Second gap opening penalty (dual affine)
+  -E, --gap-ext2 <INT>        Second gap extension penalty (dual affine)
+  -m, --memory <MODE>         high, med, low or ultralow [high]
+      --heuristic <SPEC>      Add a heuristic (repeatable): none,
+                              banded-static:MIN_K,MAX_K,
+                              banded-adaptive:MIN_K,MAX_K,STEPS,
+                              wf-adaptive:MIN_LEN,MAX_DIST,STEPS,
+                              wf-mash:MIN_LEN,MAX_DIST,STEPS,
+                              xdrop:X,STEPS or zdrop:Z,STEPS
+      --band-divergence <F>   Static band around the main diagonals, widened
+                              by this fraction of the longer sequence
+      --max-steps <INT>       Give up once the score exceeds this
+      --max-memory <BYTES>    Give up once WFA2 uses this much memory
+      --align-threads <INT>   OpenMP threads per alignment [1]
+";
+
+/// Aligner configuration collected from the command line.
+#[derive(Debug, Default)]
+pub struct AlignerOptions {
+    distance: Option<DistanceMetric>,
+    preset: Option<PenaltyPreset>,
+    match_score: Option<i32>,
+    mismatch: Option<i32>,
+    gap_open: Option<i32>,
+    gap_ext: Option<i32>,
+    gap_open2: Option<i32>,
+    gap_ext2: Option<i32>,
+    memory_mode: Option<MemoryMode>,
+    heuristics: Vec<HeuristicStrategy>,
+    band_divergence: Option<f64>,
+    max_steps: Option<i32>,
+    max_memory: Option<u64>,
+    align_threads: Option<usize>,
+}
+
+impl AlignerOptions {
+    /// Take `flag` (`-x` or `--name`) and its value if it is an aligner
+    /// flag. Returns false for flags that belong to the subcommand.
+    pub fn parse(&mut self, flag: &str, parser: &mut lexopt::Parser) -> Result<bool, String> {
+        let text = |parser: &mut lexopt::Parser| flag_value::<String>(parser);
+        match flag {
+            "--distance" => self.distance = Some(parse_distance(&text(parser)?)?),
+            "--preset" => self.preset = Some(flag_value(parser)?),
+            "-a" | "--match" => self.match_score = Some(flag_value(parser)?),
+            "-x" | "--mismatch" => self.mismatch = Some(flag_value(parser)?),
+            "-o" | "--gap-open" => self.gap_open = Some(flag_value(parser)?),
+            "-e" | "--gap-ext" => self.gap_ext = Some(flag_value(parser)?),
+            "-O" | "--gap-open2" => self.gap_open2 = Some(flag_value(parser)?),
+            "-E" | "--gap-ext2" => self.gap_ext2 = Some(flag_value(parser)?),
+            "-m" | "--memory" => self.memory_mode = Some(parse_memory_mode(&text(parser)?)?),
+            "--heuristic" => self.heuristics.push(parse_heuristic(&text(parser)?)?),
+            "--band-divergence" => self.band_divergence = Some(flag_value(parser)?),
+            "--max-steps" => {
+                let steps = flag_value(parser)?;
+                if steps <= 0 {
+                    return Err(format!("--max-steps must be positive, got {steps}"));
+                }
+                self.max_steps = Some(steps);
+            }
+            "--max-memory" => self.max_memory = Some(flag_value(parser)?),
+            "--align-threads" => self.align_threads = Some(flag_value(parser)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    pub fn build(&self) -> Result<AffineWavefronts, String> {
+        // WFA2's default gap-affine penalties, or those of the preset
+        let (mut match_score, mut mismatch, mut gap_open, mut gap_ext) = (0, 4, 6, 2);
+        let (mut gap_open2, mut gap_ext2) = (self.gap_open2, self.gap_ext2);
+        if let Some(preset) = self.preset {
+            let p = preset.penalties();
+            (match_score, mismatch) = (p.match_score, p.mismatch);
+            (gap_open, gap_ext) = (p.gap_opening1, p.gap_extension1);
+            // Only the first gap piece applies to another --distance
+            if matches!(self.distance, None | Some(DistanceMetric::GapAffine2p)) {
+                gap_open2 = gap_open2.or(Some(p.gap_opening2));
+                gap_ext2 = gap_ext2.or(Some(p.gap_extension2));
+            }
+        }
+        let mut builder = AffineWavefrontsBuilder::new().penalties(
+            self.match_score.unwrap_or(match_score),
+            self.mismatch.unwrap_or(mismatch),
+            self.gap_open.unwrap_or(gap_open),
+            self.gap_ext.unwrap_or(gap_ext),
+        );
+        match (gap_open2, gap_ext2) {
+            (Some(gap_open2), Some(gap_ext2)) => {
+                builder = builder.dual_affine_penalties(gap_open2, gap_ext2);
+            }
+            (None, None) => {}
+            _ => return Err("--gap-open2 and --gap-ext2 must be given together".to_string()),
+        }
+        if let Some(distance) = &self.distance {
+            builder = builder.distance_metric(distance.clone());
+        }
+        if let Some(memory_mode) = &self.memory_mode {
+            builder = builder.memory_mode(memory_mode.clone());
+        }
+        builder = builder.heuristics(&self.heuristics);
+        if let Some(divergence) = self.band_divergence {
+            builder = builder.banded_for(divergence);
+        }
+        if let Some(steps) = self.max_steps {
+            builder = builder.max_alignment_steps(steps);
+        }
+        if let Some(bytes) = self.max_memory {
+            builder = builder.max_memory(bytes);
+        }
+        let mut aligner = builder.build().map_err(|e| e.to_string())?;
+        if let Some(threads) = self.align_threads {
+            aligner.set_num_threads(threads)?;
+        }
+        Ok(aligner)
+    }
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e| format!("Invalid value '{value}': {e}"))
+}
+
+fn parse_distance(name: &str) -> Result<DistanceMetric, String> {
+    match name {
+        "edit" => Ok(DistanceMetric::Edit),
+        "indel" => Ok(DistanceMetric::Indel),
+        "affine" => Ok(DistanceMetric::GapAffine),
+        "affine2p" => Ok(DistanceMetric::GapAffine2p),
+        _ => Err(format!(
+            "Unknown distance '{name}', expected one of: edit, indel, affine, affine2p"
+        )),
+    }
+}
+
+fn parse_memory_mode(name: &str) -> Result<MemoryMode, String> {
+    match name {
+        "high" => Ok(MemoryMode::High),
+        "med" | "medium" => Ok(MemoryMode::Medium),
+        "low" => Ok(MemoryMode::Low),
+        "ultralow" => Ok(MemoryMode::Ultralow),
+        _ => Err(format!(
+            "Unknown memory mode '{name}', expected one of: high, med, low, ultralow"
+        )),
+    }
+}
+
+/// Parse a heuristic given as `NAME[:ARG,ARG,...]`, e.g. `xdrop:100,1`.
+fn parse_heuristic(spec: &str) -> Result<HeuristicStrategy, String> {
+    let (name, args) = spec.split_once(':').unwrap_or((spec, ""));
+    let args: Vec<i32> = if args.is_empty() {
+        Vec::new()
+    } else {
+        args.split(',').map(number).collect::<Result<_, _>>()?
+    };
+    let expect = |count: usize| {
+        if args.len() == count {
+            Ok(())
+        } else {
+            Err(format!(
+                "Heuristic '{name}' takes {count} values, got {} in '{spec}'",
+                args.len()
+            ))
+        }
+    };
+    let strategy = match name {
+        "none" => {
+            expect(0)?;
+            HeuristicStrategy::None
+        }
+        "banded-static" => {
+            expect(2)?;
+            HeuristicStrategy::BandedStatic {
+                band_min_k: args[0],
+                band_max_k: args[1],
+            }
+        }
+        "banded-adaptive" => {
+            expect(3)?;
+            HeuristicStrategy::BandedAdaptive {
+                band_min_k: args[0],
+                band_max_k: args[1],
+                score_steps: args[2],
+            }
+        }
+        "wf-adaptive" => {
+            expect(3)?;
+            HeuristicStrategy::WFAdaptive {
+                min_wavefront_length: args[0],
+                max_distance_threshold: args[1],
+                score_steps: args[2],
+            }
+        }
+        "wf-mash" => {
+            expect(3)?;
+            HeuristicStrategy::WFMash {
+                min_wavefront_length: args[0],
+                max_distance_threshold: args[1],
+                score_steps: args[2],
+            }
+        }
+        "xdrop" => {
+            expect(2)?;
+            HeuristicStrategy::XDrop {
+                xdrop: args[0],
+                score_steps: args[1],
+            }
+        }
+        "zdrop" => {
+            expect(2)?;
+            HeuristicStrategy::ZDrop {
+                zdrop: args[0],
+                score_steps: args[1],
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Unknown heuristic '{name}', expected one of: none, banded-static, \
+                 banded-adaptive, wf-adaptive, wf-mash, xdrop, zdrop"
+            ))
+        }
+    };
+    Ok(strategy)
+}
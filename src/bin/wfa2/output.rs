@@ -0,0 +1,116 @@
+//! Result formats of `--output-format`.
+
+use lib_wfa2::affine_wavefront::Alignment;
+use lib_wfa2::cigar::{cigar_string, CigarStats};
+use lib_wfa2::sequence::FastxRecord;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Query and target names and lengths, status, score, identity and
+    /// CIGAR.
+    #[default]
+    Tsv,
+    /// PAF with `cg:Z:`, `AS:i:` and identity tags; alignments that did not
+    /// complete are left out.
+    Paf,
+    /// SAM with `@HD` and `@PG` header lines and an `@SQ` line per target
+    /// known up front.
+    Sam,
+    /// One JSON object per line with the TSV fields.
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "tsv" => Ok(Self::Tsv),
+            "paf" => Ok(Self::Paf),
+            "sam" => Ok(Self::Sam),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "Unknown output format '{name}', expected one of: tsv, paf, sam, json"
+            )),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Lines to write before the first record. `targets` are the names and
+    /// lengths of the targets, for SAM `@SQ` lines.
+    pub fn write_header(&self, out: &mut impl Write, targets: &[(&str, usize)]) -> io::Result<()> {
+        if *self != Self::Sam {
+            return Ok(());
+        }
+        writeln!(out, "@HD\tVN:1.6\tSO:unsorted")?;
+        for (name, len) in targets {
+            writeln!(out, "@SQ\tSN:{name}\tLN:{len}")?;
+        }
+        let command_line: Vec<String> = std::env::args().collect();
+        writeln!(
+            out,
+            "@PG\tID:wfa2\tPN:wfa2\tVN:{}\tCL:{}",
+            env!("CARGO_PKG_VERSION"),
+            command_line.join(" ")
+        )
+    }
+
+    pub fn write_record(
+        &self,
+        out: &mut impl Write,
+        query: &FastxRecord,
+        target: &FastxRecord,
+        alignment: &Alignment,
+    ) -> io::Result<()> {
+        let (query_len, target_len) = (query.sequence.len(), target.sequence.len());
+        let cigar = if alignment.cigar.is_empty() {
+            "*".to_string()
+        } else {
+            cigar_string(&alignment.cigar)
+        };
+        let identity = CigarStats::from_cigar(&alignment.cigar).identity();
+        match self {
+            Self::Tsv => writeln!(
+                out,
+                "{}\t{}\t{query_len}\t{target_len}\t{:?}\t{}\t{identity:.6}\t{cigar}",
+                query.name, target.name, alignment.status, alignment.score
+            ),
+            Self::Paf if !alignment.status.is_complete() => Ok(()),
+            Self::Paf => {
+                let record =
+                    alignment.to_paf(&query.name, query_len, &target.name, target_len, 0, false);
+                writeln!(out, "{record}")
+            }
+            Self::Sam => {
+                let record = alignment.to_sam_record(&query.name, &query.sequence, &target.name, 0);
+                writeln!(out, "{record}")
+            }
+            Self::Json => writeln!(
+                out,
+                "{{\"query\":{},\"target\":{},\"query_len\":{query_len},\
+                 \"target_len\":{target_len},\"status\":\"{:?}\",\"score\":{},\
+                 \"identity\":{identity:.6},\"cigar\":\"{cigar}\"}}",
+                json_string(&query.name),
+                json_string(&target.name),
+                alignment.status,
+                alignment.score
+            ),
+        }
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
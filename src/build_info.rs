@@ -0,0 +1,88 @@
+use std::fmt;
+
+/// Provenance of the native WFA2 code linked into this crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildInfo {
+    pub crate_version: &'static str,
+    pub wfa2_version: &'static str,
+    pub wfa2_commit: &'static str,
+    pub bindgen_version: &'static str,
+    pub target: &'static str,
+    pub compiler: &'static str,
+    pub cc_flags: &'static str,
+    pub openmp: bool,
+    /// `-march` flag passed to the C compiler (`None` for a portable build).
+    /// `native` targets whatever the build host supports, so it does not name
+    /// a SIMD level.
+    pub march: Option<&'static str>,
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "lib_wfa2 {}", self.crate_version)?;
+        writeln!(f, "WFA2-lib {} ({})", self.wfa2_version, self.wfa2_commit)?;
+        writeln!(f, "bindgen {}", self.bindgen_version)?;
+        writeln!(f, "target {}", self.target)?;
+        writeln!(f, "compiler {} {}", self.compiler, self.cc_flags)?;
+        writeln!(f, "openmp {}", if self.openmp { "yes" } else { "no" })?;
+        write!(f, "march {}", self.march.unwrap_or("portable"))
+    }
+}
+
+/// Version (git tag or commit) of the vendored WFA2-lib.
+pub fn wfa2_version() -> &'static str {
+    build_info().wfa2_version
+}
+
+/// Build information for bug reports and result provenance.
+pub fn build_info() -> BuildInfo {
+    let cc_flags = option_env!("LIB_WFA2_CC_FLAGS").unwrap_or("unknown");
+    BuildInfo {
+        crate_version: env!("CARGO_PKG_VERSION"),
+        wfa2_version: option_env!("LIB_WFA2_WFA2_VERSION").unwrap_or("unknown"),
+        wfa2_commit: option_env!("LIB_WFA2_WFA2_COMMIT").unwrap_or("unknown"),
+        bindgen_version: option_env!("LIB_WFA2_BINDGEN_VERSION").unwrap_or("unknown"),
+        target: option_env!("LIB_WFA2_TARGET").unwrap_or("unknown"),
+        compiler: option_env!("LIB_WFA2_CC").unwrap_or("unknown"),
+        cc_flags,
+        openmp: option_env!("LIB_WFA2_OPENMP") == Some("1"),
+        march: cc_flags
+            .split_whitespace()
+            .find_map(|flag| flag.strip_prefix("-march=")),
+    }
+}
+
+/// Optional features supported by the linked WFA2 library.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capabilities {
+    pub openmp: bool,
+    /// Threads a single alignment can use (1 without OpenMP).
+    pub max_threads: usize,
+    /// See [`BuildInfo::march`].
+    pub march: Option<&'static str>,
+    /// Bidirectional WFA, required by `MemoryMode::Ultralow`. `None` if
+    /// unknown, as for an installed library (feature `system-wfa2`).
+    pub biwfa: Option<bool>,
+    /// Wavefront plotting (`wavefront_plot_*`). `None` if unknown, as for
+    /// [`Self::biwfa`].
+    pub plot: Option<bool>,
+}
+
+/// Report which optional features are available so callers can adapt instead of
+/// failing at runtime on a minimal build.
+pub fn capabilities() -> Capabilities {
+    let info = build_info();
+    let max_threads = if info.openmp {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        1
+    };
+    Capabilities {
+        openmp: info.openmp,
+        max_threads,
+        march: info.march,
+        // Recorded by build.rs for the vendored sources only
+        biwfa: option_env!("LIB_WFA2_BIWFA").map(|flag| flag == "1"),
+        plot: option_env!("LIB_WFA2_PLOT").map(|flag| flag == "1"),
+    }
+}
@@ -0,0 +1,275 @@
+//! Persistent alignment result cache, so iterative analyses that align the
+//! same pairs across program runs can skip recomputation.
+
+use crate::affine_wavefront::{
+    AffineWavefronts, AlignmentScope, AlignmentSpan, AlignmentStatus, HeuristicStrategy,
+    MemoryMode, Penalties,
+};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Alignment result as stored in the cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedAlignment {
+    pub status: AlignmentStatus,
+    pub score: i32,
+    pub cigar: Vec<u8>,
+}
+
+// FNV-1a is stable across Rust versions and platforms, unlike std's hasher.
+fn fnv1a(seed: u64, parts: &[&[u8]]) -> u64 {
+    let mut hash = 0xcbf29ce484222325 ^ seed;
+    for part in parts {
+        for &byte in part.iter() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        // Separate parts so ("AB", "C") and ("A", "BC") differ
+        hash ^= part.len() as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Fingerprint of everything in the aligner's configuration that can change
+/// an alignment or its status: penalties, heuristics, span, scope, memory
+/// mode, step and memory limits, per-pair band and bi-WFA fallback. The
+/// settings are hashed in a fixed textual encoding, so keys stay valid
+/// across versions of this crate.
+pub fn config_fingerprint(aligner: &AffineWavefronts) -> u64 {
+    let mut config = String::from("v1");
+    match aligner.get_penalties() {
+        Penalties::Indel => config.push_str("|indel"),
+        Penalties::Edit => config.push_str("|edit"),
+        Penalties::GapAffine(p) => {
+            let _ = write!(
+                config,
+                "|affine:{},{},{},{}",
+                p.match_score, p.mismatch, p.gap_opening, p.gap_extension
+            );
+        }
+        Penalties::GapAffine2p(p) => {
+            let _ = write!(
+                config,
+                "|affine2p:{},{},{},{},{},{}",
+                p.match_score,
+                p.mismatch,
+                p.gap_opening1,
+                p.gap_extension1,
+                p.gap_opening2,
+                p.gap_extension2
+            );
+        }
+    }
+    config.push_str("|heuristics:");
+    for heuristic in aligner.get_heuristics() {
+        let _ = match heuristic {
+            HeuristicStrategy::None => write!(config, "none;"),
+            HeuristicStrategy::BandedStatic {
+                band_min_k,
+                band_max_k,
+            } => write!(config, "banded-static:{band_min_k},{band_max_k};"),
+            HeuristicStrategy::BandedAdaptive {
+                band_min_k,
+                band_max_k,
+                score_steps,
+            } => write!(
+                config,
+                "banded-adaptive:{band_min_k},{band_max_k},{score_steps};"
+            ),
+            HeuristicStrategy::WFAdaptive {
+                min_wavefront_length,
+                max_distance_threshold,
+                score_steps,
+            } => write!(
+                config,
+                "wf-adaptive:{min_wavefront_length},{max_distance_threshold},{score_steps};"
+            ),
+            HeuristicStrategy::WFMash {
+                min_wavefront_length,
+                max_distance_threshold,
+                score_steps,
+            } => write!(
+                config,
+                "wf-mash:{min_wavefront_length},{max_distance_threshold},{score_steps};"
+            ),
+            HeuristicStrategy::XDrop { xdrop, score_steps } => {
+                write!(config, "xdrop:{xdrop},{score_steps};")
+            }
+            HeuristicStrategy::ZDrop { zdrop, score_steps } => {
+                write!(config, "zdrop:{zdrop},{score_steps};")
+            }
+        };
+    }
+    let _ = match aligner.get_alignment_span() {
+        AlignmentSpan::End2End => write!(config, "|end2end"),
+        AlignmentSpan::EndsFree {
+            pattern_begin_free,
+            pattern_end_free,
+            text_begin_free,
+            text_end_free,
+        } => write!(
+            config,
+            "|ends-free:{pattern_begin_free},{pattern_end_free},{text_begin_free},{text_end_free}"
+        ),
+        AlignmentSpan::Undefined => write!(config, "|undefined"),
+    };
+    config.push_str(match aligner.get_alignment_scope() {
+        AlignmentScope::ComputeScore => "|score",
+        AlignmentScope::Alignment => "|alignment",
+        AlignmentScope::Undefined => "|undefined",
+    });
+    config.push('|');
+    config.push_str(memory_mode_name(&aligner.get_memory_mode()));
+    let _ = write!(
+        config,
+        "|steps:{}|memory:{}",
+        aligner.get_max_alignment_steps(),
+        aligner.get_max_memory()
+    );
+    if let Some(divergence) = aligner.get_banded_for() {
+        let _ = write!(config, "|band:{divergence}");
+    }
+    if let Some(fallback) = aligner.biwfa_fallback() {
+        let _ = write!(
+            config,
+            "|fallback:{},{},{}",
+            memory_mode_name(&fallback.memory_mode),
+            fallback.max_memory,
+            fallback.divergence
+        );
+    }
+    fnv1a(0, &[config.as_bytes()])
+}
+
+fn memory_mode_name(mode: &MemoryMode) -> &'static str {
+    match mode {
+        MemoryMode::High => "high",
+        MemoryMode::Medium => "med",
+        MemoryMode::Low => "low",
+        MemoryMode::Ultralow => "ultralow",
+        MemoryMode::Undefined => "undefined",
+    }
+}
+
+fn status_from_name(name: &str) -> AlignmentStatus {
+    match name {
+        "Completed" => AlignmentStatus::Completed,
+        "Partial" => AlignmentStatus::Partial,
+        "MaxStepsReached" => AlignmentStatus::MaxStepsReached,
+        "OOM" => AlignmentStatus::OOM,
+        "Unattainable" => AlignmentStatus::Unattainable,
+        _ => AlignmentStatus::Undefined,
+    }
+}
+
+/// Directory-backed cache with one small file per (configuration, pair) key.
+/// A second, independent hash is stored in each entry and checked on lookup
+/// to rule out key collisions.
+pub struct AlignmentCache {
+    dir: PathBuf,
+}
+
+impl AlignmentCache {
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    fn key(aligner: &AffineWavefronts, query: &[u8], target: &[u8]) -> (u64, u64) {
+        let config = config_fingerprint(aligner).to_le_bytes();
+        (
+            fnv1a(0, &[&config, query, target]),
+            fnv1a(0x9e3779b97f4a7c15, &[&config, query, target]),
+        )
+    }
+
+    fn path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{key:016x}"))
+    }
+
+    pub fn get(
+        &self,
+        aligner: &AffineWavefronts,
+        query: &[u8],
+        target: &[u8],
+    ) -> io::Result<Option<CachedAlignment>> {
+        let (key, check) = Self::key(aligner, query, target);
+        let content = match fs::read_to_string(self.path(key)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let fields: Vec<&str> = content.trim_end_matches('\n').split('\t').collect();
+        match fields.as_slice() {
+            [stored_check, status, score, cigar] if *stored_check == format!("{check:016x}") => {
+                Ok(score.parse().ok().map(|score| CachedAlignment {
+                    status: status_from_name(status),
+                    score,
+                    cigar: cigar.as_bytes().to_vec(),
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn insert(
+        &self,
+        aligner: &AffineWavefronts,
+        query: &[u8],
+        target: &[u8],
+        alignment: &CachedAlignment,
+    ) -> io::Result<()> {
+        let (key, check) = Self::key(aligner, query, target);
+        let path = self.path(key);
+        let tmp = path.with_extension("tmp");
+        fs::write(
+            &tmp,
+            format!(
+                "{check:016x}\t{:?}\t{}\t{}\n",
+                alignment.status,
+                alignment.score,
+                String::from_utf8_lossy(&alignment.cigar)
+            ),
+        )?;
+        fs::rename(tmp, path)
+    }
+
+    /// Return the cached result for the pair, aligning and storing it on a miss.
+    /// Only completed alignments are stored: a result cut short by a step or
+    /// memory limit is recomputed next time.
+    pub fn align(
+        &self,
+        aligner: &AffineWavefronts,
+        query: &[u8],
+        target: &[u8],
+    ) -> io::Result<CachedAlignment> {
+        if let Some(hit) = self.get(aligner, query, target)? {
+            return Ok(hit);
+        }
+        let alignment = CachedAlignment {
+            status: aligner.align(query, target),
+            score: aligner.score(),
+            cigar: aligner.cigar().to_vec(),
+        };
+        if alignment.status == AlignmentStatus::Completed {
+            self.insert(aligner, query, target, &alignment)?;
+        }
+        Ok(alignment)
+    }
+
+    /// Remove all cached entries.
+    pub fn clear(&self) -> io::Result<()> {
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.is_file() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+//! Small, stable C API over the safe aligner (feature `capi`), so that tools
+//! in other languages can embed it instead of driving WFA2 directly. Build
+//! the shared library with
+//! `cargo rustc --release --features capi --crate-type cdylib`; the matching
+//! header is `include/lib_wfa2.h`, generated by cbindgen (see
+//! `cbindgen.toml`).
+//!
+//! An aligner is created from a [`Wfa2Config`], used for any number of
+//! alignments and released with [`wfa2_aligner_free`]. Results (score and
+//! CIGAR) belong to the last alignment and stay valid until the next one.
+
+use crate::affine_wavefront::{
+    AffineWavefronts, AffineWavefrontsBuilder, AlignmentStatus, MemoryMode,
+};
+use std::slice;
+
+pub const WFA2_STATUS_COMPLETED: i32 = 0;
+pub const WFA2_STATUS_PARTIAL: i32 = 1;
+pub const WFA2_STATUS_MAX_STEPS_REACHED: i32 = -100;
+pub const WFA2_STATUS_OOM: i32 = -200;
+pub const WFA2_STATUS_UNATTAINABLE: i32 = -300;
+/// Any other outcome, including a null aligner.
+pub const WFA2_STATUS_UNDEFINED: i32 = -1;
+
+pub const WFA2_MEMORY_HIGH: i32 = 0;
+pub const WFA2_MEMORY_MEDIUM: i32 = 1;
+pub const WFA2_MEMORY_LOW: i32 = 2;
+pub const WFA2_MEMORY_ULTRALOW: i32 = 3;
+
+/// Aligner configuration. Penalties follow the Rust API: `match_score` is 0
+/// or negative (a bonus), the others are positive. The second gap piece is
+/// used (dual-affine penalties) when both `gap_opening2` and
+/// `gap_extension2` are positive.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wfa2Config {
+    pub match_score: i32,
+    pub mismatch: i32,
+    pub gap_opening1: i32,
+    pub gap_extension1: i32,
+    pub gap_opening2: i32,
+    pub gap_extension2: i32,
+    /// One of the `WFA2_MEMORY_*` constants.
+    pub memory_mode: i32,
+    /// Maximum alignment steps, or 0 for WFA2's default.
+    pub max_alignment_steps: i32,
+}
+
+/// Opaque aligner handle.
+pub struct Wfa2Aligner {
+    aligner: AffineWavefronts,
+}
+
+/// Default configuration: gap-affine penalties 0, 4, 6, 2 and high memory
+/// mode.
+#[no_mangle]
+pub extern "C" fn wfa2_config_default() -> Wfa2Config {
+    Wfa2Config {
+        match_score: 0,
+        mismatch: 4,
+        gap_opening1: 6,
+        gap_extension1: 2,
+        gap_opening2: 0,
+        gap_extension2: 0,
+        memory_mode: WFA2_MEMORY_HIGH,
+        max_alignment_steps: 0,
+    }
+}
+
+/// Create an aligner, or return null if the configuration is invalid.
+///
+/// # Safety
+///
+/// `config` must be null or point to a valid `Wfa2Config`.
+#[no_mangle]
+pub unsafe extern "C" fn wfa2_aligner_new(config: *const Wfa2Config) -> *mut Wfa2Aligner {
+    let Some(config) = config.as_ref() else {
+        return std::ptr::null_mut();
+    };
+    let memory_mode = match config.memory_mode {
+        WFA2_MEMORY_HIGH => MemoryMode::High,
+        WFA2_MEMORY_MEDIUM => MemoryMode::Medium,
+        WFA2_MEMORY_LOW => MemoryMode::Low,
+        WFA2_MEMORY_ULTRALOW => MemoryMode::Ultralow,
+        _ => return std::ptr::null_mut(),
+    };
+    if config.max_alignment_steps < 0 {
+        return std::ptr::null_mut();
+    }
+    let mut builder = AffineWavefrontsBuilder::new()
+        .penalties(
+            config.match_score,
+            config.mismatch,
+            config.gap_opening1,
+            config.gap_extension1,
+        )
+        .memory_mode(memory_mode);
+    if config.gap_opening2 > 0 && config.gap_extension2 > 0 {
+        builder = builder.dual_affine_penalties(config.gap_opening2, config.gap_extension2);
+    }
+    if config.max_alignment_steps > 0 {
+        builder = builder.max_alignment_steps(config.max_alignment_steps);
+    }
+    match builder.build() {
+        Ok(aligner) => Box::into_raw(Box::new(Wfa2Aligner { aligner })),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Release an aligner. Null is ignored.
+///
+/// # Safety
+///
+/// `aligner` must be null or come from [`wfa2_aligner_new`], and must not be
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn wfa2_aligner_free(aligner: *mut Wfa2Aligner) {
+    if !aligner.is_null() {
+        drop(Box::from_raw(aligner));
+    }
+}
+
+/// Align `pattern` against `text` and return a `WFA2_STATUS_*` code.
+///
+/// # Safety
+///
+/// `aligner` must be null or a live aligner, and each sequence pointer must
+/// be valid for reads of its length (it may be null if the length is 0).
+#[no_mangle]
+pub unsafe extern "C" fn wfa2_align(
+    aligner: *mut Wfa2Aligner,
+    pattern: *const u8,
+    pattern_len: usize,
+    text: *const u8,
+    text_len: usize,
+) -> i32 {
+    let Some(aligner) = aligner.as_mut() else {
+        return WFA2_STATUS_UNDEFINED;
+    };
+    let sequence = |data: *const u8, len: usize| {
+        if len == 0 {
+            &[][..]
+        } else {
+            slice::from_raw_parts(data, len)
+        }
+    };
+    match aligner
+        .aligner
+        .align(sequence(pattern, pattern_len), sequence(text, text_len))
+    {
+        AlignmentStatus::Completed => WFA2_STATUS_COMPLETED,
+        AlignmentStatus::Partial => WFA2_STATUS_PARTIAL,
+        AlignmentStatus::MaxStepsReached => WFA2_STATUS_MAX_STEPS_REACHED,
+        AlignmentStatus::OOM => WFA2_STATUS_OOM,
+        AlignmentStatus::Unattainable => WFA2_STATUS_UNATTAINABLE,
+        AlignmentStatus::Undefined => WFA2_STATUS_UNDEFINED,
+    }
+}
+
+/// Score of the last alignment (0 for a null aligner).
+///
+/// # Safety
+///
+/// `aligner` must be null or a live aligner.
+#[no_mangle]
+pub unsafe extern "C" fn wfa2_score(aligner: *const Wfa2Aligner) -> i32 {
+    aligner
+        .as_ref()
+        .map_or(0, |aligner| aligner.aligner.score())
+}
+
+/// Compact CIGAR of the last alignment, one `M`, `X`, `I` or `D` per
+/// column and not NUL-terminated; its length is stored in `len`. The
+/// pointer stays valid until the next alignment or until the aligner is
+/// freed. Returns null (and length 0) for a null aligner.
+///
+/// # Safety
+///
+/// `aligner` must be null or a live aligner, and `len` must be null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn wfa2_cigar(aligner: *const Wfa2Aligner, len: *mut usize) -> *const u8 {
+    let cigar = aligner.as_ref().map(|aligner| aligner.aligner.cigar());
+    if let Some(len) = len.as_mut() {
+        *len = cigar.map_or(0, <[u8]>::len);
+    }
+    cigar.map_or(std::ptr::null(), <[u8]>::as_ptr)
+}
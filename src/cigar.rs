@@ -0,0 +1,887 @@
+//! CIGAR representations, conversions and statistics, and utilities for
+//! comparing alignments of the same sequence pair, e.g. a WFA2 alignment
+//! against a record from an existing BAM file.
+
+use crate::affine_wavefront::Penalties;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// One alignment operation of a compact CIGAR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CigarOp {
+    /// `M`: aligned column, match or mismatch.
+    Match,
+    /// `=`: identical bases.
+    Equal,
+    /// `X`: mismatching bases.
+    Mismatch,
+    /// `I`: base present in the query only.
+    Insertion,
+    /// `D`: base present in the target only.
+    Deletion,
+}
+
+impl CigarOp {
+    pub fn from_byte(op: u8) -> Option<Self> {
+        match op {
+            b'M' => Some(CigarOp::Match),
+            b'=' => Some(CigarOp::Equal),
+            b'X' => Some(CigarOp::Mismatch),
+            b'I' => Some(CigarOp::Insertion),
+            b'D' => Some(CigarOp::Deletion),
+            _ => None,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        match self {
+            CigarOp::Match => b'M',
+            CigarOp::Equal => b'=',
+            CigarOp::Mismatch => b'X',
+            CigarOp::Insertion => b'I',
+            CigarOp::Deletion => b'D',
+        }
+    }
+
+    pub fn consumes_query(self) -> bool {
+        consumes_query(self.to_byte())
+    }
+
+    pub fn consumes_target(self) -> bool {
+        consumes_target(self.to_byte())
+    }
+}
+
+/// Iterator over the runs of a compact CIGAR as `(operation, length)`, see
+/// [`cigar_runs`].
+#[derive(Debug, Clone)]
+pub struct CigarRuns<'a> {
+    cigar: &'a [u8],
+}
+
+impl Iterator for CigarRuns<'_> {
+    type Item = (CigarOp, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let &first = self.cigar.first()?;
+            let run = self.cigar.iter().take_while(|&&c| c == first).count();
+            self.cigar = &self.cigar[run..];
+            if let Some(op) = CigarOp::from_byte(first) {
+                return Some((op, run as u32));
+            }
+        }
+    }
+}
+
+/// Run-length view of a compact CIGAR, e.g. `b"MMMID"` yields `(Match, 3)`,
+/// `(Insertion, 1)`, `(Deletion, 1)`. Bytes that are not alignment operations
+/// are skipped.
+pub fn cigar_runs(cigar: &[u8]) -> CigarRuns<'_> {
+    CigarRuns { cigar }
+}
+
+/// Run-length CIGAR, e.g. for assembling the alignment of a long pair from
+/// the alignments of its chunks. Adjacent runs of the same operation are
+/// always merged.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Cigar {
+    runs: Vec<(CigarOp, u32)>,
+}
+
+impl Cigar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// From a compact one-operation-per-base CIGAR such as
+    /// [`AffineWavefronts::cigar`](crate::affine_wavefront::AffineWavefronts::cigar).
+    pub fn from_compact(cigar: &[u8]) -> Self {
+        let mut merged = Self::new();
+        for (op, len) in cigar_runs(cigar) {
+            merged.push(op, len);
+        }
+        merged
+    }
+
+    /// Parse a run-length string such as `"10M2I5M"`.
+    pub fn parse(cigar: &str) -> Result<Self, String> {
+        Ok(Self::from_compact(&expand_cigar(cigar)?))
+    }
+
+    /// Concatenate CIGARs of consecutive, non-overlapping pieces.
+    pub fn concat<I: IntoIterator<Item = Cigar>>(parts: I) -> Self {
+        let mut merged = Self::new();
+        for part in parts {
+            merged.append(&part);
+        }
+        merged
+    }
+
+    pub fn runs(&self) -> &[(CigarOp, u32)] {
+        &self.runs
+    }
+
+    pub fn to_compact(&self) -> Vec<u8> {
+        let mut compact = Vec::with_capacity(self.len());
+        for &(op, len) in &self.runs {
+            compact.resize(compact.len() + len as usize, op.to_byte());
+        }
+        compact
+    }
+
+    /// Number of alignment columns.
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(|&(_, len)| len as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Query bases covered.
+    pub fn query_len(&self) -> usize {
+        self.consumed(CigarOp::consumes_query)
+    }
+
+    /// Target bases covered.
+    pub fn target_len(&self) -> usize {
+        self.consumed(CigarOp::consumes_target)
+    }
+
+    fn consumed(&self, consumes: fn(CigarOp) -> bool) -> usize {
+        self.runs
+            .iter()
+            .filter(|&&(op, _)| consumes(op))
+            .map(|&(_, len)| len as usize)
+            .sum()
+    }
+
+    /// Append `len` operations, extending the last run if it has the same
+    /// operation.
+    pub fn push(&mut self, op: CigarOp, len: u32) {
+        if len == 0 {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some((last, run)) if *last == op => *run += len,
+            _ => self.runs.push((op, len)),
+        }
+    }
+
+    pub fn append(&mut self, other: &Cigar) {
+        for &(op, len) in &other.runs {
+            self.push(op, len);
+        }
+    }
+
+    /// Append the CIGAR of the next chunk, whose alignment starts
+    /// `query_overlap` and `target_overlap` bases before the end of this one.
+    /// Columns are removed from the end of this CIGAR until both overlaps are
+    /// covered; bases removed beyond an overlap are restored as insertions or
+    /// deletions, so the result stays contiguous in both sequences. Cutting
+    /// chunks at a column both alignments share avoids those extra gaps.
+    pub fn merge(
+        &mut self,
+        next: &Cigar,
+        query_overlap: usize,
+        target_overlap: usize,
+    ) -> Result<(), String> {
+        if query_overlap > self.query_len() || target_overlap > self.target_len() {
+            return Err(format!(
+                "Overlap of {query_overlap}x{target_overlap} bases exceeds the {}x{} covered \
+                 so far (query x target)",
+                self.query_len(),
+                self.target_len()
+            ));
+        }
+        let (mut query, mut target) = (0, 0);
+        while query < query_overlap || target < target_overlap {
+            let Some((op, len)) = self.runs.last_mut() else {
+                break;
+            };
+            query += usize::from(op.consumes_query());
+            target += usize::from(op.consumes_target());
+            *len -= 1;
+            if *len == 0 {
+                self.runs.pop();
+            }
+        }
+        self.push(CigarOp::Insertion, (query - query_overlap) as u32);
+        self.push(CigarOp::Deletion, (target - target_overlap) as u32);
+        self.append(next);
+        Ok(())
+    }
+}
+
+/// Part of a [`Cigar`] covering an interval of one sequence, with the query
+/// and target intervals it spans. Coordinates are relative to the start of
+/// the original CIGAR; add the alignment's start positions for absolute ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CigarSlice {
+    pub cigar: Cigar,
+    pub query: Range<usize>,
+    pub target: Range<usize>,
+}
+
+impl Cigar {
+    /// Sub-CIGAR covering the target (reference) bases in `range`, e.g. to
+    /// project an alignment onto an annotation window. Insertions are kept
+    /// only when they fall between two bases of the interval.
+    pub fn slice_ref(&self, range: Range<usize>) -> Result<CigarSlice, String> {
+        self.slice(range, CigarOp::consumes_target, self.target_len(), "target")
+    }
+
+    /// Sub-CIGAR covering the query bases in `range`. Deletions are kept only
+    /// when they fall between two bases of the interval.
+    pub fn slice_query(&self, range: Range<usize>) -> Result<CigarSlice, String> {
+        self.slice(range, CigarOp::consumes_query, self.query_len(), "query")
+    }
+
+    fn slice(
+        &self,
+        range: Range<usize>,
+        on_axis: fn(CigarOp) -> bool,
+        axis_len: usize,
+        axis: &str,
+    ) -> Result<CigarSlice, String> {
+        if range.start > range.end || range.end > axis_len {
+            return Err(format!(
+                "Interval {}..{} is outside the {axis_len} {axis} bases of the CIGAR",
+                range.start, range.end
+            ));
+        }
+        let mut cigar = Cigar::new();
+        let (mut q, mut t, mut pos) = (0, 0, 0);
+        // Coordinates where the interval starts, and after its last column
+        let mut start = None;
+        let mut end = None;
+        for &(op, len) in &self.runs {
+            for _ in 0..len {
+                if pos >= range.start && start.is_none() {
+                    start = Some((q, t));
+                }
+                let included = if on_axis(op) {
+                    range.contains(&pos)
+                } else {
+                    range.start < pos && pos < range.end
+                };
+                q += usize::from(op.consumes_query());
+                t += usize::from(op.consumes_target());
+                pos += usize::from(on_axis(op));
+                if included {
+                    if cigar.is_empty() {
+                        start = Some((
+                            q - usize::from(op.consumes_query()),
+                            t - usize::from(op.consumes_target()),
+                        ));
+                    }
+                    cigar.push(op, 1);
+                    end = Some((q, t));
+                }
+            }
+        }
+        let start = start.unwrap_or((q, t));
+        let end = end.unwrap_or(start);
+        Ok(CigarSlice {
+            cigar,
+            query: start.0..end.0,
+            target: start.1..end.1,
+        })
+    }
+}
+
+impl std::fmt::Display for Cigar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for &(op, len) in &self.runs {
+            write!(f, "{len}{}", op.to_byte() as char)?;
+        }
+        Ok(())
+    }
+}
+
+fn consumes_query(op: u8) -> bool {
+    matches!(op, b'M' | b'=' | b'X' | b'I')
+}
+
+fn consumes_target(op: u8) -> bool {
+    matches!(op, b'M' | b'=' | b'X' | b'D')
+}
+
+/// Expand a run-length CIGAR string (e.g. `"10M2I5M"`) into the compact
+/// one-operation-per-base form used by the aligner. Only `M`, `=`, `X`, `I`
+/// and `D` are accepted; clips must be removed by the caller.
+pub fn expand_cigar(cigar: &str) -> Result<Vec<u8>, String> {
+    let mut expanded = Vec::new();
+    let mut run = String::new();
+    for c in cigar.chars() {
+        if c.is_ascii_digit() {
+            run.push(c);
+            continue;
+        }
+        if !matches!(c, 'M' | '=' | 'X' | 'I' | 'D') {
+            return Err(format!("Unsupported CIGAR operation '{c}'"));
+        }
+        let len: usize = run
+            .parse()
+            .map_err(|_| format!("Missing length before CIGAR operation '{c}'"))?;
+        expanded.resize(expanded.len() + len, c as u8);
+        run.clear();
+    }
+    if !run.is_empty() {
+        return Err(format!("Trailing length '{run}' without an operation"));
+    }
+    Ok(expanded)
+}
+
+/// Run-length encode a compact CIGAR (e.g. `b"MMMID"` becomes `"3M1I1D"`).
+pub fn compress_cigar(cigar: &[u8]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < cigar.len() {
+        let op = cigar[i];
+        let run = cigar[i..].iter().take_while(|&&c| c == op).count();
+        out.push_str(&format!("{run}{}", op as char));
+        i += run;
+    }
+    out
+}
+
+// Remove the gap runs at both ends of a compact CIGAR, as left by ends-free
+// alignment. Returns the query and target bases skipped before the first
+// aligned column, and the remaining CIGAR.
+pub(crate) fn strip_gap_ends(cigar: &[u8]) -> (usize, usize, &[u8]) {
+    let is_gap = |op: &&u8| matches!(op, b'I' | b'D');
+    let start = cigar.iter().take_while(is_gap).count();
+    let (leading, rest) = cigar.split_at(start);
+    let body = &rest[..rest.len() - rest.iter().rev().take_while(is_gap).count()];
+    let query_skipped = leading.iter().filter(|&&op| op == b'I').count();
+    (query_skipped, leading.len() - query_skipped, body)
+}
+
+/// Standard run-length CIGAR string of an aligner CIGAR, with `=` for the
+/// matches WFA2 reports as `M` (e.g. `"12=1X3I8="`), as used in SAM and PAF.
+pub fn cigar_string(cigar: &[u8]) -> String {
+    let extended: Vec<u8> = cigar
+        .iter()
+        .map(|&op| if op == b'M' { b'=' } else { op })
+        .collect();
+    compress_cigar(&extended)
+}
+
+/// SAM CIGAR string of an aligner CIGAR. With `collapse_matches`, matches and
+/// mismatches are merged into `M` runs as in classic SAM (e.g. `"17M3I8M"`);
+/// otherwise they are kept apart as in [`cigar_string`].
+pub fn sam_cigar(cigar: &[u8], collapse_matches: bool) -> String {
+    if !collapse_matches {
+        return cigar_string(cigar);
+    }
+    let collapsed: Vec<u8> = cigar
+        .iter()
+        .map(|&op| if matches!(op, b'=' | b'X') { b'M' } else { op })
+        .collect();
+    compress_cigar(&collapsed)
+}
+
+/// BLAST trace-back operations (BTOP) for an alignment of `query` against
+/// `target`: runs of identical bases as a count, and every other column as
+/// the query base followed by the target base, with `-` for the gap side
+/// (e.g. `"7AG3-C2"`). `M` operations are resolved against the sequences.
+pub fn btop(query: &[u8], target: &[u8], cigar: &[u8]) -> Result<String, String> {
+    let resolved = resolve_matches(query, target, cigar)?;
+    let mut out = String::new();
+    let (mut q, mut t, mut identical) = (0, 0, 0);
+    for op in resolved {
+        if op == b'=' {
+            identical += 1;
+            q += 1;
+            t += 1;
+            continue;
+        }
+        if identical > 0 {
+            out.push_str(&identical.to_string());
+            identical = 0;
+        }
+        let (query_base, target_base) = match op {
+            b'X' => (query[q], target[t]),
+            b'I' => (query[q], b'-'),
+            _ => (b'-', target[t]),
+        };
+        out.push(query_base as char);
+        out.push(target_base as char);
+        q += usize::from(consumes_query(op));
+        t += usize::from(consumes_target(op));
+    }
+    if identical > 0 {
+        out.push_str(&identical.to_string());
+    }
+    Ok(out)
+}
+
+/// Layout of [`format_alignment_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FormatOptions {
+    /// Columns per block; 0 for no wrapping.
+    pub width: usize,
+    /// Highlight mismatches in red and gaps in yellow with ANSI escapes. The
+    /// caller decides, e.g. by checking that the output is a terminal.
+    pub color: bool,
+}
+
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Classic three-line view of an alignment: query, a marker line (`|` for a
+/// match, `.` for a mismatch, blank for a gap) and target, with `-` in the
+/// gapped sequence. Lines are wrapped every `width` columns (0 for no
+/// wrapping), with a blank line between blocks. `M` operations are resolved
+/// against the sequences.
+pub fn format_alignment(
+    query: &[u8],
+    target: &[u8],
+    cigar: &[u8],
+    width: usize,
+) -> Result<String, String> {
+    let options = FormatOptions {
+        width,
+        color: false,
+    };
+    format_alignment_with(query, target, cigar, &options)
+}
+
+/// [`format_alignment`] with optional coloring, see [`FormatOptions`].
+pub fn format_alignment_with(
+    query: &[u8],
+    target: &[u8],
+    cigar: &[u8],
+    options: &FormatOptions,
+) -> Result<String, String> {
+    let resolved = resolve_matches(query, target, cigar)?;
+    let (mut top, mut middle, mut bottom) = (Vec::new(), Vec::new(), Vec::new());
+    let (mut q, mut t) = (0, 0);
+    for &op in &resolved {
+        top.push(if consumes_query(op) { query[q] } else { b'-' });
+        bottom.push(if consumes_target(op) { target[t] } else { b'-' });
+        middle.push(match op {
+            b'=' => b'|',
+            b'X' => b'.',
+            _ => b' ',
+        });
+        q += usize::from(consumes_query(op));
+        t += usize::from(consumes_target(op));
+    }
+    // Sequence line with runs of mismatch and gap columns colored
+    let paint = |line: &[u8], ops: &[u8]| {
+        if !options.color {
+            return String::from_utf8_lossy(line).into_owned();
+        }
+        let mut painted = String::new();
+        let mut current = None;
+        for (&c, &op) in line.iter().zip(ops) {
+            let color = match op {
+                b'X' => Some(RED),
+                b'I' | b'D' => Some(YELLOW),
+                _ => None,
+            };
+            if color != current {
+                painted.push_str(color.unwrap_or(RESET));
+                current = color;
+            }
+            painted.push(c as char);
+        }
+        if current.is_some() {
+            painted.push_str(RESET);
+        }
+        painted
+    };
+    let width = if options.width == 0 {
+        resolved.len().max(1)
+    } else {
+        options.width
+    };
+    let blocks: Vec<String> = (0..resolved.len())
+        .step_by(width)
+        .map(|start| {
+            let columns = start..(start + width).min(resolved.len());
+            let ops = &resolved[columns.clone()];
+            format!(
+                "{}\n{}\n{}\n",
+                paint(&top[columns.clone()], ops),
+                String::from_utf8_lossy(&middle[columns.clone()]),
+                paint(&bottom[columns], ops)
+            )
+        })
+        .collect();
+    Ok(blocks.join("\n"))
+}
+
+/// Operation counts of an alignment and identity derived from them. `M`
+/// counts as a match, as WFA2 reports mismatches separately as `X`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CigarStats {
+    pub matches: usize,
+    pub mismatches: usize,
+    /// Inserted bases (`I`).
+    pub insertions: usize,
+    /// Deleted bases (`D`).
+    pub deletions: usize,
+    /// Runs of insertions, i.e. gap openings in the target.
+    pub insertion_events: usize,
+    /// Runs of deletions, i.e. gap openings in the query.
+    pub deletion_events: usize,
+}
+
+impl CigarStats {
+    pub fn from_cigar(cigar: &[u8]) -> Self {
+        let mut stats = Self::default();
+        for (i, &op) in cigar.iter().enumerate() {
+            let opens = i == 0 || cigar[i - 1] != op;
+            match op {
+                b'M' | b'=' => stats.matches += 1,
+                b'X' => stats.mismatches += 1,
+                b'I' => {
+                    stats.insertions += 1;
+                    stats.insertion_events += usize::from(opens);
+                }
+                b'D' => {
+                    stats.deletions += 1;
+                    stats.deletion_events += usize::from(opens);
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    /// Number of alignment columns.
+    pub fn aligned_length(&self) -> usize {
+        self.matches + self.mismatches + self.insertions + self.deletions
+    }
+
+    /// Fraction of alignment columns that are matches (0.0 for an empty
+    /// alignment). This is the BLAST-style block identity.
+    pub fn identity(&self) -> f64 {
+        ratio(self.matches, self.aligned_length())
+    }
+
+    /// BLAST-style block identity: matches over alignment columns, so every
+    /// gap base counts as a difference. Same as [`identity`](Self::identity).
+    pub fn block_identity(&self) -> f64 {
+        self.identity()
+    }
+
+    /// Gap-compressed identity as reported by minimap2 and wfmash: every gap
+    /// counts as a single difference whatever its length, so long indels do
+    /// not dominate the identity.
+    pub fn gap_compressed_identity(&self) -> f64 {
+        let differences = self.mismatches + self.insertion_events + self.deletion_events;
+        ratio(self.matches, self.matches + differences)
+    }
+}
+
+fn ratio(num: usize, den: usize) -> f64 {
+    if den == 0 {
+        0.0
+    } else {
+        num as f64 / den as f64
+    }
+}
+
+/// Stretch where two alignments of the same pair take different paths. The
+/// paths agree up to the start of `query` and `target` and meet again at their
+/// ends; coordinates are offsets relative to the CIGAR start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DivergentRegion {
+    pub query: Range<usize>,
+    pub target: Range<usize>,
+    /// Operations of the first alignment over the region.
+    pub cigar_a: Vec<u8>,
+    /// Operations of the second alignment over the region.
+    pub cigar_b: Vec<u8>,
+    pub score_a: i64,
+    pub score_b: i64,
+}
+
+impl DivergentRegion {
+    /// Score of the second alignment minus the first over this region;
+    /// positive when the second alignment scores better here.
+    pub fn score_delta(&self) -> i64 {
+        self.score_b - self.score_a
+    }
+}
+
+// Move through the DP matrix; `M`, `=` and `X` take the same diagonal step
+fn step(op: u8) -> (bool, bool) {
+    (consumes_query(op), consumes_target(op))
+}
+
+// Cell reached after each operation, starting from (0, 0)
+fn path(cigar: &[u8]) -> Vec<(usize, usize)> {
+    let (mut q, mut t) = (0, 0);
+    cigar
+        .iter()
+        .map(|&op| {
+            q += usize::from(consumes_query(op));
+            t += usize::from(consumes_target(op));
+            (q, t)
+        })
+        .collect()
+}
+
+/// Score a compact CIGAR under `penalties` (e.g. from
+/// [`AffineWavefronts::get_penalties`](crate::affine_wavefront::AffineWavefronts::get_penalties)
+/// or a [`Distance`](crate::affine_wavefront::Distance) converted with `into()`),
+/// in the sign convention of
+/// [`AffineWavefronts::score`](crate::affine_wavefront::AffineWavefronts::score):
+/// the negated total penalty, so 0 is a perfect match without a match bonus.
+/// `M` and `=` count as matches, as in WFA2 output; CIGARs from other sources
+/// may need their `M` operations split into `=` and `X` first. Under
+/// dual-affine penalties each gap is charged the cheaper piece. Returns `None`
+/// on overflow.
+pub fn score_cigar(cigar: &[u8], penalties: &Penalties) -> Option<i64> {
+    // (match, mismatch, gap pieces) as i64 costs
+    let (match_, mismatch, pieces) = match *penalties {
+        Penalties::Indel | Penalties::Edit => (0, 1, vec![(0, 1)]),
+        Penalties::GapAffine(p) => (
+            p.match_score,
+            p.mismatch,
+            vec![(p.gap_opening, p.gap_extension)],
+        ),
+        Penalties::GapAffine2p(p) => (
+            p.match_score,
+            p.mismatch,
+            vec![
+                (p.gap_opening1, p.gap_extension1),
+                (p.gap_opening2, p.gap_extension2),
+            ],
+        ),
+    };
+    let gap_cost = |len: i64| {
+        pieces
+            .iter()
+            .map(|&(o, e)| i64::from(o).checked_add(i64::from(e).checked_mul(len)?))
+            .try_fold(i64::MAX, |cheapest, cost| Some(cheapest.min(cost?)))
+    };
+
+    // Penalties are accumulated as positive costs and negated at the end
+    let mut penalty: i64 = 0;
+    for (op, len) in cigar_runs(cigar) {
+        let len = i64::from(len);
+        let cost = match op {
+            CigarOp::Match | CigarOp::Equal => i64::from(match_).checked_mul(len)?,
+            CigarOp::Mismatch => i64::from(mismatch).checked_mul(len)?,
+            CigarOp::Insertion | CigarOp::Deletion => gap_cost(len)?,
+        };
+        penalty = penalty.checked_add(cost)?;
+    }
+    penalty.checked_neg()
+}
+
+/// Compare two compact CIGARs of the same pair and report the regions where
+/// their paths diverge, each scored under `penalties` (see [`score_cigar`]).
+/// Regions are scored in isolation, so a gap cut by a region boundary is
+/// charged its opening cost on both sides.
+pub fn diff_cigars(
+    penalties: &Penalties,
+    a: &[u8],
+    b: &[u8],
+) -> Result<Vec<DivergentRegion>, String> {
+    let (path_a, path_b) = (path(a), path(b));
+    let end_a = path_a.last().copied().unwrap_or_default();
+    let end_b = path_b.last().copied().unwrap_or_default();
+    if end_a != end_b {
+        return Err(format!(
+            "CIGARs cover different sequences: {}x{} and {}x{} (query x target)",
+            end_a.0, end_a.1, end_b.0, end_b.1
+        ));
+    }
+    // Monotone paths meet in the same order on both, so the first cell of `a`
+    // that `b` also visits after a divergence is where they rejoin.
+    let cells_b: HashMap<(usize, usize), usize> = path_b
+        .iter()
+        .enumerate()
+        .map(|(i, &cell)| (cell, i + 1))
+        .collect();
+
+    let score = |cigar: &[u8]| {
+        score_cigar(cigar, penalties)
+            .ok_or_else(|| "Score overflow while comparing CIGARs".to_string())
+    };
+    let mut regions = Vec::new();
+    let (mut ia, mut ib) = (0, 0);
+    let mut cell = (0, 0);
+    while ia < a.len() && ib < b.len() {
+        if step(a[ia]) == step(b[ib]) {
+            cell = path_a[ia];
+            ia += 1;
+            ib += 1;
+            continue;
+        }
+        let (rejoin_a, rejoin_b) = (ia..a.len())
+            .find_map(|i| {
+                cells_b
+                    .get(&path_a[i])
+                    .filter(|&&j| j > ib)
+                    .map(|&j| (i + 1, j))
+            })
+            .unwrap_or((a.len(), b.len()));
+        let end = path_a[rejoin_a - 1];
+        let (cigar_a, cigar_b) = (&a[ia..rejoin_a], &b[ib..rejoin_b]);
+        regions.push(DivergentRegion {
+            query: cell.0..end.0,
+            target: cell.1..end.1,
+            cigar_a: cigar_a.to_vec(),
+            cigar_b: cigar_b.to_vec(),
+            score_a: score(cigar_a)?,
+            score_b: score(cigar_b)?,
+        });
+        cell = end;
+        ia = rejoin_a;
+        ib = rejoin_b;
+    }
+    Ok(regions)
+}
+
+/// Result of comparing two alignments of the same pair, see
+/// [`alignments_equivalent`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equivalence {
+    pub score_a: i64,
+    pub score_b: i64,
+    /// Whether every divergent region is the same gaps placed differently
+    /// between exact matches, as happens when a gap can slide along a repeat.
+    pub gap_shifts_only: bool,
+}
+
+impl Equivalence {
+    pub fn equal_score(&self) -> bool {
+        self.score_a == self.score_b
+    }
+
+    /// Equal score and no differences other than gap placement.
+    pub fn is_equivalent(&self) -> bool {
+        self.equal_score() && self.gap_shifts_only
+    }
+}
+
+// Replace `M` by `=` or `X` according to the sequences, checking that the
+// CIGAR spans both of them exactly.
+pub(crate) fn resolve_matches(
+    query: &[u8],
+    target: &[u8],
+    cigar: &[u8],
+) -> Result<Vec<u8>, String> {
+    let end = path(cigar).last().copied().unwrap_or_default();
+    if end != (query.len(), target.len()) {
+        return Err(format!(
+            "CIGAR covers {}x{} bases but the sequences are {}x{} (query x target)",
+            end.0,
+            end.1,
+            query.len(),
+            target.len()
+        ));
+    }
+    let (mut q, mut t) = (0, 0);
+    Ok(cigar
+        .iter()
+        .map(|&op| {
+            let resolved = match op {
+                b'M' | b'=' | b'X' if query[q] == target[t] => b'=',
+                b'M' | b'=' | b'X' => b'X',
+                _ => op,
+            };
+            q += usize::from(consumes_query(op));
+            t += usize::from(consumes_target(op));
+            resolved
+        })
+        .collect())
+}
+
+// Gap runs of one type, e.g. "==II=" has one insertion run
+fn gap_runs(cigar: &[u8], gap: u8) -> usize {
+    cigar
+        .iter()
+        .enumerate()
+        .filter(|&(i, &op)| op == gap && (i == 0 || cigar[i - 1] != gap))
+        .count()
+}
+
+// Same gaps, only exact matches around them
+fn is_gap_shift(a: &[u8], b: &[u8]) -> bool {
+    let count = |cigar: &[u8], op: u8| cigar.iter().filter(|&&c| c == op).count();
+    !a.contains(&b'X')
+        && !b.contains(&b'X')
+        && [b'I', b'D']
+            .iter()
+            .all(|&gap| count(a, gap) == count(b, gap) && gap_runs(a, gap) == gap_runs(b, gap))
+}
+
+/// Normalize an alignment of `query` against `target` by shifting every
+/// insertion and deletion as far left as it can go without changing the
+/// aligned sequences, as variant callers expect. Gaps only move across exact
+/// matches, so the score is unchanged. `M` operations are resolved against the
+/// sequences, and the result uses `=`/`X`.
+pub fn left_align_indels(query: &[u8], target: &[u8], cigar: &[u8]) -> Result<Vec<u8>, String> {
+    let resolved = resolve_matches(query, target, cigar)?;
+    let mut aligned: Vec<u8> = Vec::with_capacity(resolved.len());
+    let (mut q, mut t) = (0, 0);
+    let mut i = 0;
+    while i < resolved.len() {
+        let op = resolved[i];
+        let run = resolved[i..].iter().take_while(|&&c| c == op).count();
+        if matches!(op, b'I' | b'D') {
+            // Gap bases come from one sequence; the match before the gap can
+            // move behind it when its base equals the gap's last base.
+            let (seq, start) = if op == b'I' { (query, q) } else { (target, t) };
+            let mut shift = 0;
+            while aligned.last() == Some(&b'=')
+                && seq[start - 1 - shift] == seq[start + run - 1 - shift]
+            {
+                aligned.pop();
+                shift += 1;
+            }
+            aligned.resize(aligned.len() + run, op);
+            aligned.resize(aligned.len() + shift, b'=');
+        } else {
+            aligned.resize(aligned.len() + run, op);
+        }
+        if consumes_query(op) {
+            q += run;
+        }
+        if consumes_target(op) {
+            t += run;
+        }
+        i += run;
+    }
+    Ok(aligned)
+}
+
+/// Decide whether two CIGARs for the same `query`/`target` pair are
+/// equivalent under `penalties` (see [`score_cigar`]): equal score, and
+/// differing only in where gaps are placed within repeats. `M` operations are
+/// resolved against the sequences, so CIGARs without `=`/`X` (e.g. from BAM
+/// records) are scored correctly, and no aligner is created. Useful in
+/// regression tests where bit-identical CIGARs are too strict.
+pub fn alignments_equivalent(
+    query: &[u8],
+    target: &[u8],
+    a: &[u8],
+    b: &[u8],
+    penalties: &Penalties,
+) -> Result<Equivalence, String> {
+    let a = resolve_matches(query, target, a)?;
+    let b = resolve_matches(query, target, b)?;
+    let score = |cigar: &[u8]| {
+        score_cigar(cigar, penalties)
+            .ok_or_else(|| "Score overflow while comparing CIGARs".to_string())
+    };
+    let gap_shifts_only = diff_cigars(penalties, &a, &b)?
+        .iter()
+        .all(|region| is_gap_shift(&region.cigar_a, &region.cigar_b));
+    Ok(Equivalence {
+        score_a: score(&a)?,
+        score_b: score(&b)?,
+        gap_shifts_only,
+    })
+}
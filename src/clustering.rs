@@ -0,0 +1,197 @@
+//! Guide trees from distance matrices (feature `clustering`), e.g. of WFA
+//! alignment penalties (see [`DistanceMatrix::all_vs_all`]), to order
+//! progressive multiple alignment or to get a quick overview of a dataset.
+
+use crate::distance_matrix::DistanceMatrix;
+use std::fmt::Write;
+
+/// Binary tree over the sequences of a distance matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuideTree {
+    /// A sequence, by its index in the distance matrix.
+    Leaf(usize),
+    /// Two subtrees and the lengths of the branches leading to them.
+    Node {
+        left: Box<GuideTree>,
+        right: Box<GuideTree>,
+        left_length: f64,
+        right_length: f64,
+    },
+}
+
+impl GuideTree {
+    fn join(left: GuideTree, left_length: f64, right: GuideTree, right_length: f64) -> Self {
+        GuideTree::Node {
+            left: Box::new(left),
+            right: Box::new(right),
+            left_length,
+            right_length,
+        }
+    }
+
+    /// Indices of the sequences, from left to right.
+    pub fn leaves(&self) -> Vec<usize> {
+        let mut leaves = Vec::new();
+        self.collect_leaves(&mut leaves);
+        leaves
+    }
+
+    fn collect_leaves(&self, leaves: &mut Vec<usize>) {
+        match self {
+            GuideTree::Leaf(index) => leaves.push(*index),
+            GuideTree::Node { left, right, .. } => {
+                left.collect_leaves(leaves);
+                right.collect_leaves(leaves);
+            }
+        }
+    }
+
+    /// Newick representation with branch lengths, leaves named by `names`
+    /// (written as is, so they should not contain Newick punctuation).
+    pub fn to_newick(&self, names: &[String]) -> String {
+        let mut newick = String::new();
+        self.write_newick(names, &mut newick);
+        newick.push(';');
+        newick
+    }
+
+    fn write_newick(&self, names: &[String], newick: &mut String) {
+        match self {
+            GuideTree::Leaf(index) => newick.push_str(&names[*index]),
+            GuideTree::Node {
+                left,
+                right,
+                left_length,
+                right_length,
+            } => {
+                newick.push('(');
+                left.write_newick(names, newick);
+                let _ = write!(newick, ":{left_length},");
+                right.write_newick(names, newick);
+                let _ = write!(newick, ":{right_length})");
+            }
+        }
+    }
+}
+
+// Distances between the clusters still to be joined, shrunk as they are
+struct Clusters<T> {
+    clusters: Vec<T>,
+    distances: Vec<Vec<f64>>,
+}
+
+impl<T> Clusters<T> {
+    fn new(matrix: &DistanceMatrix, leaf: impl Fn(usize) -> T) -> Self {
+        let n = matrix.len();
+        Self {
+            clusters: (0..n).map(leaf).collect(),
+            distances: (0..n)
+                .map(|i| (0..n).map(|j| matrix.get(i, j)).collect())
+                .collect(),
+        }
+    }
+
+    // Pair i < j minimizing `criterion`
+    fn closest(&self, criterion: impl Fn(usize, usize) -> f64) -> (usize, usize) {
+        let n = self.clusters.len();
+        let mut best = (0, 1);
+        let mut best_value = f64::INFINITY;
+        for i in 0..n {
+            for j in i + 1..n {
+                let value = criterion(i, j);
+                if value < best_value {
+                    best = (i, j);
+                    best_value = value;
+                }
+            }
+        }
+        best
+    }
+
+    // Replace clusters i < j by `joined`, with `distance(k)` to every other
+    // cluster k
+    fn merge(&mut self, i: usize, j: usize, joined: T, distance: impl Fn(&Self, usize) -> f64) {
+        let new_distances: Vec<f64> = (0..self.clusters.len())
+            .map(|k| {
+                if k == i || k == j {
+                    0.0
+                } else {
+                    distance(self, k)
+                }
+            })
+            .collect();
+        for (k, &d) in new_distances.iter().enumerate() {
+            self.distances[i][k] = d;
+            self.distances[k][i] = d;
+        }
+        self.clusters[i] = joined;
+        self.clusters.remove(j);
+        self.distances.remove(j);
+        for row in &mut self.distances {
+            row.remove(j);
+        }
+    }
+}
+
+/// Rooted tree by UPGMA: repeatedly join the two closest clusters, with the
+/// distance between clusters the average over their sequences. Branch
+/// lengths put every leaf at the same depth, half the distance of each join.
+/// `None` for an empty matrix.
+pub fn upgma(matrix: &DistanceMatrix) -> Option<GuideTree> {
+    // Subtree, number of sequences and height of each cluster
+    let mut clusters = Clusters::new(matrix, |i| (GuideTree::Leaf(i), 1usize, 0.0));
+    while clusters.clusters.len() > 1 {
+        let (i, j) = clusters.closest(|i, j| clusters.distances[i][j]);
+        let height = clusters.distances[i][j] / 2.0;
+        let (size_i, size_j) = (clusters.clusters[i].1, clusters.clusters[j].1);
+        let (left, _, left_height) = clusters.clusters[i].clone();
+        let (right, _, right_height) = clusters.clusters[j].clone();
+        let joined = GuideTree::join(
+            left,
+            (height - left_height).max(0.0),
+            right,
+            (height - right_height).max(0.0),
+        );
+        clusters.merge(i, j, (joined, size_i + size_j, height), |c, k| {
+            (size_i as f64 * c.distances[i][k] + size_j as f64 * c.distances[j][k])
+                / (size_i + size_j) as f64
+        });
+    }
+    clusters.clusters.pop().map(|(tree, _, _)| tree)
+}
+
+/// Tree by neighbor joining, which unlike UPGMA does not assume a constant
+/// rate of divergence. Neighbor joining builds an unrooted tree; it is
+/// rooted at the middle of the last branch joined. Negative branch lengths
+/// are set to 0. `None` for an empty matrix.
+pub fn neighbor_joining(matrix: &DistanceMatrix) -> Option<GuideTree> {
+    let mut clusters = Clusters::new(matrix, GuideTree::Leaf);
+    while clusters.clusters.len() > 2 {
+        let n = clusters.clusters.len();
+        let totals: Vec<f64> = clusters
+            .distances
+            .iter()
+            .map(|row| row.iter().sum())
+            .collect();
+        let (i, j) = clusters
+            .closest(|i, j| (n - 2) as f64 * clusters.distances[i][j] - totals[i] - totals[j]);
+        let d_ij = clusters.distances[i][j];
+        let length_i = d_ij / 2.0 + (totals[i] - totals[j]) / (2.0 * (n - 2) as f64);
+        let length_j = d_ij - length_i;
+        let joined = GuideTree::join(
+            clusters.clusters[i].clone(),
+            length_i.max(0.0),
+            clusters.clusters[j].clone(),
+            length_j.max(0.0),
+        );
+        clusters.merge(i, j, joined, |c, k| {
+            (c.distances[i][k] + c.distances[j][k] - d_ij) / 2.0
+        });
+    }
+    let right = clusters.clusters.pop()?;
+    let Some(left) = clusters.clusters.pop() else {
+        return Some(right);
+    };
+    let half = (clusters.distances[0][1] / 2.0).max(0.0);
+    Some(GuideTree::join(left, half, right, half))
+}
@@ -0,0 +1,51 @@
+//! Aligner configurations in TOML files (feature `config`), so pipelines can
+//! version their alignment parameters and rebuild identical aligners.
+//!
+//! A file holds the fields of [`AlignerConfig`]; missing fields keep their
+//! defaults:
+//!
+//! ```toml
+//! distance_metric = "GapAffine2p"
+//! mismatch = 4
+//! gap_opening1 = 6
+//! gap_extension1 = 2
+//! gap_opening2 = 24
+//! gap_extension2 = 1
+//! memory_mode = "Ultralow"
+//! heuristics = [{ XDrop = { xdrop = 100, score_steps = 1 } }]
+//! ```
+
+use crate::affine_wavefront::{AffineWavefrontsBuilder, AlignerConfig};
+use std::fs;
+use std::path::Path;
+
+impl AffineWavefrontsBuilder {
+    /// Builder with the configuration in `text`, see the [module
+    /// documentation](crate::config) for the format. Settings are only
+    /// checked when building.
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        AlignerConfig::from_toml(text).map(Self::from)
+    }
+
+    /// Builder with the configuration in the TOML file at `path`.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read '{}': {e}", path.display()))?;
+        Self::from_toml(&text)
+            .map_err(|e| format!("Invalid configuration '{}': {e}", path.display()))
+    }
+}
+
+impl AlignerConfig {
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    /// This configuration as TOML, readable by
+    /// [`AffineWavefrontsBuilder::from_toml`]. Unset optional fields are
+    /// left out.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("aligner configurations are valid TOML")
+    }
+}
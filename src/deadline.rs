@@ -0,0 +1,165 @@
+//! Deadline-aware batch alignment: every pair gets a share of the time left
+//! until a global deadline, so a batch finishes in bounded time even when a
+//! few pairs are pathologically expensive.
+//!
+//! WFA2 cannot be interrupted on a timer, so time budgets are enforced through
+//! its step limit. The conversion from time to steps is calibrated from the
+//! alignments already run, which makes budgets approximate: an individual pair
+//! can overrun its share, and later pairs get correspondingly less.
+
+use crate::affine_wavefront::{AffineWavefronts, AlignmentStatus, HeuristicStrategy};
+use crate::batch::BatchPair;
+use crate::options::{AlignOptions, AlignmentResult};
+use std::time::{Duration, Instant};
+
+/// Outcome of one pair in a [`DeadlineBatch`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetedAlignment {
+    /// The aligner finished within the budget. `result.status` still reports
+    /// failures unrelated to time, such as running out of memory.
+    Aligned {
+        result: AlignmentResult,
+        /// Whether the result comes from the retry with the fallback
+        /// heuristic, and so may not be optimal.
+        retried: bool,
+        elapsed: Duration,
+    },
+    /// The pair did not finish within its budget (or was never started
+    /// because the deadline had passed).
+    TimedOut {
+        /// Lower bound on the optimal penalty: the step limit that was hit,
+        /// or 0 if the pair was not attempted.
+        min_penalty: i32,
+        elapsed: Duration,
+    },
+}
+
+// Alignment time modeled as `c * (steps^2 + sequence length)`: wavefronts
+// grow linearly with the score and extension is linear in the sequences.
+struct SpeedModel {
+    secs_per_unit: f64,
+}
+
+impl SpeedModel {
+    fn units(len: usize, steps: i32) -> f64 {
+        f64::from(steps).powi(2) + len as f64
+    }
+
+    fn max_steps(&self, budget: Duration, len: usize) -> i32 {
+        let units = budget.as_secs_f64() / self.secs_per_unit - len as f64;
+        units.max(1.0).sqrt().min(f64::from(i32::MAX)) as i32
+    }
+
+    fn observe(&mut self, elapsed: Duration, len: usize, steps: i32) {
+        let sample = elapsed.as_secs_f64() / Self::units(len, steps);
+        if sample > 0.0 {
+            self.secs_per_unit = 0.7 * self.secs_per_unit + 0.3 * sample;
+        }
+    }
+}
+
+/// Batch runner that aligns pairs in order under a global deadline. Each pair
+/// may use the time left divided by the number of pairs left; pairs that hit
+/// their budget are optionally retried with a faster heuristic in the budget
+/// that remains, and reported as timed out otherwise.
+pub struct DeadlineBatch {
+    deadline: Duration,
+    retry_heuristic: Option<HeuristicStrategy>,
+}
+
+impl DeadlineBatch {
+    pub fn new(deadline: Duration) -> Self {
+        Self {
+            deadline,
+            retry_heuristic: Some(HeuristicStrategy::WFAdaptive {
+                min_wavefront_length: 10,
+                max_distance_threshold: 50,
+                score_steps: 1,
+            }),
+        }
+    }
+
+    /// Heuristic used to retry pairs that ran out of time (default adaptive
+    /// wavefront reduction); `None` disables retries.
+    pub fn retry_heuristic(mut self, heuristic: Option<HeuristicStrategy>) -> Self {
+        self.retry_heuristic = heuristic;
+        self
+    }
+
+    /// Align all pairs, returning one outcome per pair in input order. The
+    /// aligner's own settings are left unchanged.
+    pub fn run(
+        &self,
+        aligner: &mut AffineWavefronts,
+        pairs: &[BatchPair],
+    ) -> Vec<BudgetedAlignment> {
+        let start = Instant::now();
+        let mut model = SpeedModel {
+            secs_per_unit: 5e-9,
+        };
+        let mut outcomes = Vec::with_capacity(pairs.len());
+        for (i, pair) in pairs.iter().enumerate() {
+            let len = pair.query.len() + pair.target.len();
+            let pair_start = Instant::now();
+            // Share of the remaining time, recomputed before every attempt
+            let budget =
+                || self.deadline.saturating_sub(start.elapsed()) / (pairs.len() - i) as u32;
+
+            let mut attempt = |heuristic: Option<&HeuristicStrategy>| {
+                let budget = budget();
+                if budget.is_zero() {
+                    return None;
+                }
+                let limit = model.max_steps(budget, len);
+                let attempt_start = Instant::now();
+                let options = AlignOptions {
+                    heuristic: heuristic.cloned(),
+                    max_steps: Some(limit),
+                    ..Default::default()
+                };
+                let result = aligner.align_with_options(&pair.query, &pair.target, &options);
+                let steps = if result.status.is_complete() {
+                    result.score.saturating_neg().max(0)
+                } else {
+                    limit
+                };
+                model.observe(attempt_start.elapsed(), len, steps);
+                Some((result, limit))
+            };
+
+            let timed_out = |status: &AlignmentStatus| *status == AlignmentStatus::MaxStepsReached;
+            let outcome = match attempt(None) {
+                None => BudgetedAlignment::TimedOut {
+                    min_penalty: 0,
+                    elapsed: Duration::ZERO,
+                },
+                Some((result, limit)) if timed_out(&result.status) => {
+                    let retry = match &self.retry_heuristic {
+                        Some(heuristic) => attempt(Some(heuristic)),
+                        None => None,
+                    };
+                    match retry {
+                        Some((result, _)) if !timed_out(&result.status) => {
+                            BudgetedAlignment::Aligned {
+                                result,
+                                retried: true,
+                                elapsed: pair_start.elapsed(),
+                            }
+                        }
+                        _ => BudgetedAlignment::TimedOut {
+                            min_penalty: limit,
+                            elapsed: pair_start.elapsed(),
+                        },
+                    }
+                }
+                Some((result, _)) => BudgetedAlignment::Aligned {
+                    result,
+                    retried: false,
+                    elapsed: pair_start.elapsed(),
+                },
+            };
+            outcomes.push(outcome);
+        }
+        outcomes
+    }
+}
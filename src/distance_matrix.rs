@@ -0,0 +1,165 @@
+//! All-vs-all distance matrices of score-only alignments, written as PHYLIP
+//! or square TSV for tree-building tools (e.g. RapidNJ, FastME, QuickTree).
+
+use crate::affine_wavefront::{AffineWavefrontsBuilder, AlignError, BuildError};
+use crate::score_only::scores_for_pairs;
+use std::io::{self, Write};
+
+/// Why [`DistanceMatrix::all_vs_all`] failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatrixError {
+    /// The aligner configuration is invalid.
+    Build(BuildError),
+    /// A pair could not be aligned.
+    Align(AlignError),
+}
+
+impl std::fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatrixError::Build(e) => e.fmt(f),
+            MatrixError::Align(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MatrixError::Build(e) => Some(e),
+            MatrixError::Align(e) => Some(e),
+        }
+    }
+}
+
+impl From<BuildError> for MatrixError {
+    fn from(e: BuildError) -> Self {
+        MatrixError::Build(e)
+    }
+}
+
+impl From<AlignError> for MatrixError {
+    fn from(e: AlignError) -> Self {
+        MatrixError::Align(e)
+    }
+}
+
+/// Symmetric matrix of pairwise distances between named sequences.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceMatrix {
+    names: Vec<String>,
+    // Row-major, `names.len()` squared
+    distances: Vec<f64>,
+}
+
+impl DistanceMatrix {
+    /// Matrix of `distances` in row-major order, one row and column per name.
+    pub fn new(names: Vec<String>, distances: Vec<f64>) -> Result<Self, String> {
+        let n = names.len();
+        if distances.len() != n * n {
+            return Err(format!(
+                "{} distances given for {n} names, expected {}",
+                distances.len(),
+                n * n
+            ));
+        }
+        Ok(Self { names, distances })
+    }
+
+    /// Align every pair of `sequences` with score-only aligners built from
+    /// `config`, on `threads` threads (see [`scores_for_pairs`]). The distance
+    /// of a pair is its alignment penalty, i.e. the negated score, so it is
+    /// only a proper distance without a match bonus. Each unordered pair is
+    /// aligned once; an invalid `config` or the first pair that fails fails
+    /// the whole matrix.
+    pub fn all_vs_all(
+        config: &AffineWavefrontsBuilder,
+        sequences: &[(&str, &[u8])],
+        threads: usize,
+    ) -> Result<Self, MatrixError> {
+        let n = sequences.len();
+        let pairs: Vec<(usize, usize)> = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect();
+        let sequence_pairs: Vec<(&[u8], &[u8])> = pairs
+            .iter()
+            .map(|&(i, j)| (sequences[i].1, sequences[j].1))
+            .collect();
+        let scores = scores_for_pairs(config, &sequence_pairs, threads)?;
+
+        let mut distances = vec![0.0; n * n];
+        for (&(i, j), score) in pairs.iter().zip(scores) {
+            let distance = -f64::from(score?);
+            distances[i * n + j] = distance;
+            distances[j * n + i] = distance;
+        }
+        let names = sequences.iter().map(|(name, _)| name.to_string()).collect();
+        Ok(Self { names, distances })
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Distance between the `i`-th and `j`-th sequences.
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        self.distances[i * self.len() + j]
+    }
+
+    fn row(&self, i: usize) -> &[f64] {
+        let n = self.len();
+        &self.distances[i * n..(i + 1) * n]
+    }
+
+    /// Write the matrix in relaxed PHYLIP format: the number of sequences,
+    /// then one line per sequence with its name and its distances, separated
+    /// by spaces. Names must not contain whitespace, which would end them
+    /// early for PHYLIP readers.
+    pub fn write_phylip<W: Write>(&self, mut out: W) -> io::Result<()> {
+        if let Some(name) = self
+            .names
+            .iter()
+            .find(|name| name.is_empty() || name.contains(char::is_whitespace))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Name '{name}' cannot be written to PHYLIP: empty or contains whitespace"),
+            ));
+        }
+        writeln!(out, "{}", self.len())?;
+        for (i, name) in self.names.iter().enumerate() {
+            write!(out, "{name}")?;
+            for distance in self.row(i) {
+                write!(out, " {distance}")?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Write the matrix as a square TSV: a header with an empty first cell
+    /// and the names, then one line per sequence with its name and its
+    /// distances.
+    pub fn write_tsv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        for name in &self.names {
+            write!(out, "\t{name}")?;
+        }
+        writeln!(out)?;
+        for (i, name) in self.names.iter().enumerate() {
+            write!(out, "{name}")?;
+            for distance in self.row(i) {
+                write!(out, "\t{distance}")?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
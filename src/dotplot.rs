@@ -0,0 +1,163 @@
+//! Dot-plot export of alignment paths, e.g. to compare where a heuristic
+//! alignment leaves the optimal one. Paths are written as TSV vertices for
+//! plotting elsewhere or, with the `dotplot-png` feature, rendered directly
+//! to a PNG image.
+
+use crate::cigar::cigar_runs;
+use std::io::{self, Write};
+
+/// Vertices of the path of a compact CIGAR through the DP matrix, as
+/// `(query, target)` positions starting at `(0, 0)`. Only the ends of
+/// operation runs are kept, so consecutive vertices are joined by a straight
+/// diagonal, vertical or horizontal segment.
+pub fn alignment_path(cigar: &[u8]) -> Vec<(usize, usize)> {
+    let mut vertices = vec![(0, 0)];
+    let (mut q, mut t) = (0, 0);
+    for (op, len) in cigar_runs(cigar) {
+        let len = len as usize;
+        if op.consumes_query() {
+            q += len;
+        }
+        if op.consumes_target() {
+            t += len;
+        }
+        vertices.push((q, t));
+    }
+    vertices
+}
+
+/// Write the paths of labeled compact CIGARs as TSV with a
+/// `path\tquery\ttarget` header and one line per vertex (see
+/// [`alignment_path`]).
+pub fn write_path_tsv<W: Write>(mut out: W, paths: &[(&str, &[u8])]) -> io::Result<()> {
+    writeln!(out, "path\tquery\ttarget")?;
+    for (label, cigar) in paths {
+        for (q, t) in alignment_path(cigar) {
+            writeln!(out, "{label}\t{q}\t{t}")?;
+        }
+    }
+    Ok(())
+}
+
+// Colors of successive paths in PNG plots
+#[cfg(feature = "dotplot-png")]
+const PALETTE: [[u8; 3]; 6] = [
+    [0, 0, 0],
+    [214, 39, 40],
+    [31, 119, 180],
+    [44, 160, 44],
+    [255, 127, 14],
+    [148, 103, 189],
+];
+
+/// Render the paths of compact CIGARs for a `query_len` x `target_len` pair
+/// as a PNG dot plot, target on the x axis and query on the y axis (top to
+/// bottom). The longer sequence is scaled to `size` pixels. Paths are drawn
+/// in order in black, red, blue, green, orange and purple, repeating.
+#[cfg(feature = "dotplot-png")]
+pub fn write_path_png<W: Write>(
+    out: W,
+    query_len: usize,
+    target_len: usize,
+    cigars: &[&[u8]],
+    size: u32,
+) -> io::Result<()> {
+    let longest = query_len.max(target_len).max(1) as f64;
+    let scale = f64::from(size.max(1)) / longest;
+    let pixels = |len: usize| ((len as f64 * scale).ceil() as u32).max(1);
+    let (width, height) = (pixels(target_len), pixels(query_len));
+    let mut image = vec![255u8; (width * height * 3) as usize];
+    for (i, cigar) in cigars.iter().enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+        for segment in alignment_path(cigar).windows(2) {
+            let ((q0, t0), (q1, t1)) = (segment[0], segment[1]);
+            let (x0, y0) = (t0 as f64 * scale, q0 as f64 * scale);
+            let (x1, y1) = (t1 as f64 * scale, q1 as f64 * scale);
+            let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as u32;
+            for step in 0..=steps {
+                let f = f64::from(step) / f64::from(steps);
+                let x = ((x0 + f * (x1 - x0)) as u32).min(width - 1);
+                let y = ((y0 + f * (y1 - y0)) as u32).min(height - 1);
+                let pixel = ((y * width + x) * 3) as usize;
+                image[pixel..pixel + 3].copy_from_slice(&color);
+            }
+        }
+    }
+    png::write_rgb(out, width, height, &image)
+}
+
+// Minimal PNG encoder: 8-bit RGB, no filtering, uncompressed deflate blocks.
+// Dot plots are small enough that compression is not worth a dependency.
+#[cfg(feature = "dotplot-png")]
+mod png {
+    use std::io::{self, Write};
+
+    pub fn write_rgb<W: Write>(mut out: W, width: u32, height: u32, rgb: &[u8]) -> io::Result<()> {
+        let row_len = width as usize * 3;
+        let mut raw = Vec::with_capacity((row_len + 1) * height as usize);
+        for row in rgb.chunks(row_len) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        header.extend([8, 2, 0, 0, 0]);
+
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+        chunk(&mut out, b"IHDR", &header)?;
+        chunk(&mut out, b"IDAT", &zlib_stored(&raw))?;
+        chunk(&mut out, b"IEND", &[])
+    }
+
+    fn chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+        out.write_all(&(data.len() as u32).to_be_bytes())?;
+        out.write_all(kind)?;
+        out.write_all(data)?;
+        let crc = crc32(kind.iter().chain(data));
+        out.write_all(&crc.to_be_bytes())
+    }
+
+    fn zlib_stored(data: &[u8]) -> Vec<u8> {
+        let mut stream = vec![0x78, 0x01];
+        let mut blocks = data.chunks(u16::MAX as usize).peekable();
+        if blocks.peek().is_none() {
+            stream.extend([1, 0, 0, 0xff, 0xff]);
+        }
+        while let Some(block) = blocks.next() {
+            let last = u8::from(blocks.peek().is_none());
+            let len = block.len() as u16;
+            stream.push(last);
+            stream.extend(len.to_le_bytes());
+            stream.extend((!len).to_le_bytes());
+            stream.extend_from_slice(block);
+        }
+        stream.extend(adler32(data).to_be_bytes());
+        stream
+    }
+
+    fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+        let mut crc = u32::MAX;
+        for &byte in bytes {
+            crc ^= u32::from(byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xedb8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    fn adler32(data: &[u8]) -> u32 {
+        let (mut a, mut b) = (1u32, 0u32);
+        for &byte in data {
+            a = (a + u32::from(byte)) % 65521;
+            b = (b + a) % 65521;
+        }
+        (b << 16) | a
+    }
+}
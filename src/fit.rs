@@ -0,0 +1,144 @@
+//! Empirical penalty fitting: estimate gap-affine (or dual-affine) penalties
+//! that reproduce a set of trusted alignments.
+//!
+//! Operation frequencies are turned into log-odds costs relative to a match.
+//! With per-column match and mismatch frequencies `p_m`, `p_x`, gap opening
+//! frequency `p_o` and gap continuation probability `p_e`, a mismatch costs
+//! `ln(p_m / p_x)` and a gap of length `l` costs `ln(p_e / p_o) +
+//! l * ln(p_m / p_e)`, i.e. WFA's `o + e * l`.
+
+use crate::affine_wavefront::Distance;
+
+/// Penalties in natural-log units, before conversion to integers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FittedPenalties {
+    pub mismatch: f64,
+    pub gap_opening: f64,
+    pub gap_extension: f64,
+    /// Opening and extension fitted on long gaps only, if requested.
+    pub long_gap: Option<(f64, f64)>,
+}
+
+impl FittedPenalties {
+    /// Integer penalties with the smallest one scaled to `precision` (higher
+    /// keeps the fitted ratios more faithfully, at the cost of larger scores
+    /// and slower alignment), reduced by their common divisor.
+    pub fn to_distance(&self, precision: i32) -> Distance {
+        let mut all = vec![self.mismatch, self.gap_opening, self.gap_extension];
+        if let Some((opening, extension)) = self.long_gap {
+            all.extend([opening, extension]);
+        }
+        let smallest = all.iter().copied().fold(f64::INFINITY, f64::min);
+        let scale = f64::from(precision.max(1)) / smallest;
+        let int = |penalty: f64| ((penalty * scale).round() as i32).max(1);
+        match self.long_gap {
+            None => Distance::GapAffine {
+                mismatch: int(self.mismatch),
+                gap_opening: int(self.gap_opening),
+                gap_extension: int(self.gap_extension),
+            },
+            Some((opening, extension)) => Distance::GapAffine2p {
+                mismatch: int(self.mismatch),
+                gap_opening1: int(self.gap_opening),
+                gap_extension1: int(self.gap_extension),
+                gap_opening2: int(opening),
+                gap_extension2: int(extension),
+            },
+        }
+        .reduced()
+    }
+}
+
+// Penalties that are practically free would make WFA's scores degenerate
+const MIN_PENALTY: f64 = 0.01;
+
+/// Fit penalties to trusted alignments given as compact CIGARs (with `X` for
+/// mismatches). With `long_gap_threshold`, gaps at least that long are fitted
+/// separately as the second piece of a dual-affine scheme. One pseudocount is
+/// added to every frequency, so operations absent from the examples get a
+/// finite, high cost.
+pub fn fit_penalties<'a, I>(
+    cigars: I,
+    long_gap_threshold: Option<usize>,
+) -> Result<FittedPenalties, String>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let (mut columns, mut matches, mut mismatches) = (0usize, 0usize, 0usize);
+    let mut gaps = Vec::new();
+    for cigar in cigars {
+        columns += cigar.len();
+        let mut i = 0;
+        while i < cigar.len() {
+            let op = cigar[i];
+            let run = cigar[i..].iter().take_while(|&&c| c == op).count();
+            match op {
+                b'M' | b'=' => matches += run,
+                b'X' => mismatches += run,
+                b'I' | b'D' => gaps.push(run),
+                _ => {}
+            }
+            i += run;
+        }
+    }
+    if columns == 0 {
+        return Err("No alignment columns to fit penalties to".to_string());
+    }
+
+    let columns = columns as f64 + 2.0;
+    let p_match = (matches as f64 + 1.0) / columns;
+    let p_mismatch = (mismatches as f64 + 1.0) / columns;
+    // Opening and extension of a geometric gap length model
+    let gap_model = |lengths: &[usize]| {
+        let runs = lengths.len() as f64 + 1.0;
+        let bases = lengths.iter().sum::<usize>() as f64 + 2.0;
+        let p_open = runs / columns;
+        let p_extend = (bases - runs) / bases;
+        (
+            (p_extend / p_open).ln().max(MIN_PENALTY),
+            (p_match / p_extend).ln().max(MIN_PENALTY),
+        )
+    };
+
+    let (short, long): (Vec<usize>, Vec<usize>) = match long_gap_threshold {
+        Some(threshold) => gaps.iter().partition(|&&len| len < threshold),
+        None => (gaps, Vec::new()),
+    };
+    let (gap_opening, gap_extension) = gap_model(&short);
+    Ok(FittedPenalties {
+        mismatch: (p_match / p_mismatch).ln().max(MIN_PENALTY),
+        gap_opening,
+        gap_extension,
+        long_gap: long_gap_threshold.map(|_| gap_model(&long)),
+    })
+}
+
+/// Fit penalties to raw sequence pairs known to be homologous, EM-style:
+/// align all pairs with the current penalties, refit on the resulting
+/// alignments and repeat for `iterations` rounds (or until the penalties stop
+/// changing). `initial` seeds the first round.
+pub fn fit_penalties_from_pairs(
+    pairs: &[(Vec<u8>, Vec<u8>)],
+    initial: &Distance,
+    iterations: usize,
+    long_gap_threshold: Option<usize>,
+    precision: i32,
+) -> Result<Distance, String> {
+    let mut distance = *initial;
+    for _ in 0..iterations {
+        let aligner = distance.create_aligner(None, None);
+        let mut cigars = Vec::with_capacity(pairs.len());
+        for (query, target) in pairs {
+            if aligner.align(query, target).is_complete() {
+                cigars.push(aligner.cigar().to_vec());
+            }
+        }
+        let fitted = fit_penalties(cigars.iter().map(Vec::as_slice), long_gap_threshold)?
+            .to_distance(precision);
+        if fitted == distance {
+            break;
+        }
+        distance = fitted;
+    }
+    Ok(distance)
+}
@@ -0,0 +1,73 @@
+//! Annotation of intron-scale reference gaps, for approximating spliced
+//! cDNA-to-genome alignment with `Distance::long_gap_intron()`.
+
+/// Long reference gap (`D` run) that may correspond to an intron. Positions are
+/// offsets into the aligned query and target, relative to the CIGAR start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandidateIntron {
+    /// Query position at which the gap occurs.
+    pub query_pos: usize,
+    pub target_start: usize,
+    pub target_end: usize,
+    /// Whether the gap is flanked by a canonical splice motif (GT..AG, or
+    /// CT..AC on the reverse strand). `None` when no target was given.
+    pub canonical: Option<bool>,
+}
+
+impl CandidateIntron {
+    pub fn len(&self) -> usize {
+        self.target_end - self.target_start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.target_end == self.target_start
+    }
+}
+
+/// Classify reference gaps of at least `min_len` bases as candidate introns.
+/// `target` is the aligned part of the reference, starting where the CIGAR
+/// starts; when given, each candidate is checked for a splice motif.
+pub fn annotate_introns(
+    cigar: &[u8],
+    min_len: usize,
+    target: Option<&[u8]>,
+) -> Vec<CandidateIntron> {
+    let mut introns = Vec::new();
+    let (mut query_pos, mut target_pos) = (0, 0);
+    let mut i = 0;
+    while i < cigar.len() {
+        let op = cigar[i];
+        let run = cigar[i..].iter().take_while(|&&c| c == op).count();
+        match op {
+            b'D' => {
+                if run >= min_len {
+                    let (start, end) = (target_pos, target_pos + run);
+                    introns.push(CandidateIntron {
+                        query_pos,
+                        target_start: start,
+                        target_end: end,
+                        canonical: target.map(|t| splice_motif(t, start, end)),
+                    });
+                }
+                target_pos += run;
+            }
+            b'I' => query_pos += run,
+            b'M' | b'=' | b'X' => {
+                query_pos += run;
+                target_pos += run;
+            }
+            _ => {}
+        }
+        i += run;
+    }
+    introns
+}
+
+fn splice_motif(target: &[u8], start: usize, end: usize) -> bool {
+    if end < start + 4 || end > target.len() {
+        return false;
+    }
+    let donor = target[start..start + 2].to_ascii_uppercase();
+    let acceptor = target[end - 2..end].to_ascii_uppercase();
+    (donor == b"GT" && acceptor == b"AG") || (donor == b"CT" && acceptor == b"AC")
+}
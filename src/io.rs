@@ -0,0 +1,176 @@
+//! Reading sequence files and pairs of sequences to align. Inputs compressed
+//! with gzip or bgzip (a series of gzip members) are decompressed
+//! transparently with the `gzip` feature; compression is recognized from the
+//! first bytes, not the file extension.
+
+use crate::batch::BatchPair;
+use crate::sequence::{FastxReader, FastxRecord};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Buffered reader of the file at `path`, decompressed if needed.
+pub fn open(path: impl AsRef<Path>) -> io::Result<Box<dyn BufRead + Send>> {
+    let path = path.as_ref();
+    let file = File::open(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("Cannot open '{}': {e}", path.display())))?;
+    reader(file)
+}
+
+/// Buffered reader of `input` (e.g. standard input), decompressed if needed.
+pub fn reader<R: Read + Send + 'static>(mut input: R) -> io::Result<Box<dyn BufRead + Send>> {
+    // Pipes may hand out fewer bytes per read than the magic number
+    let mut magic = [0; GZIP_MAGIC.len()];
+    let mut filled = 0;
+    while filled < magic.len() {
+        match input.read(&mut magic[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    let input = BufReader::new(io::Cursor::new(magic[..filled].to_vec()).chain(input));
+    if magic[..filled] != GZIP_MAGIC {
+        return Ok(Box::new(input));
+    }
+    decompress(input)
+}
+
+#[cfg(feature = "gzip")]
+fn decompress<R: BufRead + Send + 'static>(input: R) -> io::Result<Box<dyn BufRead + Send>> {
+    // Multi-member, so that every block of a bgzip file is read
+    let decoder = flate2::bufread::MultiGzDecoder::new(input);
+    Ok(Box::new(BufReader::new(decoder)))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn decompress<R: BufRead + Send + 'static>(_input: R) -> io::Result<Box<dyn BufRead + Send>> {
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Input is gzip-compressed; build with the `gzip` feature to read it",
+    ))
+}
+
+/// Query and target records to align.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencePair {
+    pub query: FastxRecord,
+    pub target: FastxRecord,
+}
+
+impl From<SequencePair> for BatchPair {
+    /// Pair named after its query.
+    fn from(pair: SequencePair) -> Self {
+        Self {
+            id: pair.query.name,
+            query: pair.query.sequence,
+            target: pair.target.sequence,
+        }
+    }
+}
+
+/// Records of two FASTA/FASTQ streams paired by position: the n-th query
+/// with the n-th target. Streams of different lengths end with an error.
+pub struct RecordPairs<Q, T> {
+    queries: FastxReader<Q>,
+    targets: FastxReader<T>,
+}
+
+impl<Q: BufRead, T: BufRead> RecordPairs<Q, T> {
+    pub fn new(queries: Q, targets: T) -> Self {
+        Self {
+            queries: FastxReader::new(queries),
+            targets: FastxReader::new(targets),
+        }
+    }
+}
+
+impl<Q: BufRead, T: BufRead> Iterator for RecordPairs<Q, T> {
+    type Item = io::Result<SequencePair>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pair = match (self.queries.next(), self.targets.next()) {
+            (None, None) => return None,
+            (Some(Ok(query)), Some(Ok(target))) => Ok(SequencePair { query, target }),
+            (Some(Err(e)), _) | (_, Some(Err(e))) => Err(e),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Query and target files have different numbers of records",
+            )),
+        };
+        Some(pair)
+    }
+}
+
+/// Pairs of a TSV with `query target` or `id query target` lines. Both
+/// records of a pair are named after its id, or its line number (from 1)
+/// without one. Empty lines are skipped.
+pub struct PairTable<R> {
+    lines: io::Lines<R>,
+    line_number: usize,
+}
+
+impl<R: BufRead> PairTable<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line_number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PairTable<R> {
+    type Item = io::Result<SequencePair>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            self.line_number += 1;
+            let fields: Vec<&str> = line.trim_end().split('\t').collect();
+            let (id, query, target) = match fields.as_slice() {
+                [""] => continue,
+                [query, target] => (self.line_number.to_string(), query, target),
+                [id, query, target] => (id.to_string(), query, target),
+                _ => {
+                    return Some(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "Line {} of the pair table has {} fields, expected 2 or 3",
+                            self.line_number,
+                            fields.len()
+                        ),
+                    )))
+                }
+            };
+            let record = |sequence: &str| FastxRecord {
+                name: id.clone(),
+                sequence: sequence.as_bytes().to_vec(),
+                quality: None,
+            };
+            return Some(Ok(SequencePair {
+                query: record(query),
+                target: record(target),
+            }));
+        }
+    }
+}
+
+/// Pairs of the records of two sequence files, see [`RecordPairs`]. Either
+/// file may be compressed (see [`open`]).
+pub fn read_pairs(
+    queries: impl AsRef<Path>,
+    targets: impl AsRef<Path>,
+) -> io::Result<RecordPairs<Box<dyn BufRead + Send>, Box<dyn BufRead + Send>>> {
+    Ok(RecordPairs::new(open(queries)?, open(targets)?))
+}
+
+/// Pairs of a TSV file, see [`PairTable`].
+pub fn read_pair_table(path: impl AsRef<Path>) -> io::Result<PairTable<Box<dyn BufRead + Send>>> {
+    Ok(PairTable::new(open(path)?))
+}
@@ -1,7 +1,76 @@
+#[cfg(feature = "native")]
 pub mod affine_wavefront;
+pub mod affinity;
+pub mod alphabet;
+#[cfg(feature = "native")]
+pub mod batch;
 /// Include the generated bindings into a separate module.
+#[cfg(feature = "native")]
 #[allow(non_upper_case_globals)]
 #[allow(non_snake_case)]
 #[allow(non_camel_case_types)]
 #[allow(unused)]
 pub mod bindings;
+#[cfg(feature = "native")]
+pub mod build_info;
+#[cfg(feature = "native")]
+pub mod cache;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "native")]
+pub mod cigar;
+#[cfg(feature = "clustering")]
+pub mod clustering;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "native")]
+pub mod deadline;
+#[cfg(feature = "native")]
+pub mod distance_matrix;
+#[cfg(feature = "native")]
+pub mod dotplot;
+#[cfg(feature = "native")]
+pub mod fit;
+pub mod introns;
+#[cfg(feature = "native")]
+pub mod io;
+#[cfg(feature = "native")]
+pub mod liftover;
+#[cfg(feature = "mapper")]
+pub mod mapper;
+#[cfg(feature = "native")]
+pub mod masking;
+#[cfg(feature = "noodles")]
+pub mod noodles;
+#[cfg(feature = "native")]
+pub mod one_to_many;
+#[cfg(feature = "native")]
+pub mod options;
+#[cfg(feature = "native")]
+pub mod orient;
+#[cfg(feature = "paf")]
+pub mod paf;
+#[cfg(feature = "native")]
+pub mod pool;
+#[cfg(feature = "pure-rust")]
+pub mod pure_rust;
+pub mod qv;
+#[cfg(feature = "html-report")]
+pub mod report;
+#[cfg(feature = "rust-bio")]
+pub mod rust_bio;
+#[cfg(feature = "native")]
+pub mod sam;
+#[cfg(feature = "native")]
+pub mod scheduler;
+#[cfg(feature = "native")]
+pub mod score_only;
+pub mod sequence;
+#[cfg(feature = "native")]
+pub mod trim;
+pub mod types;
+#[cfg(feature = "native")]
+pub mod variants;
+
+#[cfg(feature = "native")]
+pub use build_info::{build_info, capabilities, wfa2_version};
@@ -0,0 +1,167 @@
+//! Projection of coordinates and intervals between the query and the target
+//! of an alignment.
+
+use crate::cigar::{cigar_runs, CigarOp};
+use std::ops::Range;
+
+// One CIGAR run with the query and target positions where it starts
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    query: usize,
+    target: usize,
+    op: CigarOp,
+    len: usize,
+}
+
+impl Segment {
+    fn query_end(&self) -> usize {
+        self.query
+            + if self.op.consumes_query() {
+                self.len
+            } else {
+                0
+            }
+    }
+
+    fn target_end(&self) -> usize {
+        self.target
+            + if self.op.consumes_target() {
+                self.len
+            } else {
+                0
+            }
+    }
+
+    fn is_aligned(&self) -> bool {
+        self.op.consumes_query() && self.op.consumes_target()
+    }
+}
+
+/// An interval projected through an alignment. Gaps inside the interval
+/// split it into several blocks; bases that are deleted, inserted or outside
+/// the alignment have no projection and make the mapping partial.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LiftedInterval {
+    /// Ungapped pieces as `(source, destination)` ranges, in order.
+    pub blocks: Vec<(Range<usize>, Range<usize>)>,
+    /// Source bases without a projection.
+    pub unmapped: usize,
+}
+
+impl LiftedInterval {
+    /// Whether every base of the source interval was projected.
+    pub fn is_complete(&self) -> bool {
+        self.unmapped == 0
+    }
+
+    /// Whether the projection is discontinuous in the destination, i.e. the
+    /// other sequence has bases inside the interval that are not aligned to it.
+    pub fn is_split(&self) -> bool {
+        self.blocks
+            .windows(2)
+            .any(|pair| pair[0].1.end != pair[1].1.start)
+    }
+
+    /// Destination range from the first to the last projected base, covering
+    /// any gaps in between; `None` if nothing was projected.
+    pub fn span(&self) -> Option<Range<usize>> {
+        let first = self.blocks.first()?;
+        let last = self.blocks.last()?;
+        Some(first.1.start..last.1.end)
+    }
+}
+
+/// Position lookup through an alignment in `O(log n)` for `n` CIGAR runs.
+/// Positions are 0-based and absolute: the alignment starts at `query_start`
+/// in the query and `target_start` in the target (reference).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordinateMap {
+    segments: Vec<Segment>,
+}
+
+impl CoordinateMap {
+    /// Map for a compact CIGAR, as returned by `AffineWavefronts::cigar`.
+    pub fn new(cigar: &[u8], query_start: usize, target_start: usize) -> Self {
+        let (mut query, mut target) = (query_start, target_start);
+        let segments = cigar_runs(cigar)
+            .map(|(op, len)| {
+                let segment = Segment {
+                    query,
+                    target,
+                    op,
+                    len: len as usize,
+                };
+                query = segment.query_end();
+                target = segment.target_end();
+                segment
+            })
+            .collect();
+        Self { segments }
+    }
+
+    /// Target position aligned to query position `pos`, or `None` if that base
+    /// is inserted or outside the alignment.
+    pub fn query_to_ref(&self, pos: usize) -> Option<usize> {
+        let i = self.segments.partition_point(|s| s.query_end() <= pos);
+        let segment = self.segments.get(i)?;
+        (segment.is_aligned() && pos >= segment.query)
+            .then(|| segment.target + (pos - segment.query))
+    }
+
+    /// Query position aligned to target position `pos`, or `None` if that
+    /// base is deleted from the query or outside the alignment.
+    pub fn ref_to_query(&self, pos: usize) -> Option<usize> {
+        let i = self.segments.partition_point(|s| s.target_end() <= pos);
+        let segment = self.segments.get(i)?;
+        (segment.is_aligned() && pos >= segment.target)
+            .then(|| segment.query + (pos - segment.target))
+    }
+
+    /// Project the target (reference) interval `range`, e.g. a BED record,
+    /// onto the query.
+    pub fn ref_interval_to_query(&self, range: Range<usize>) -> LiftedInterval {
+        self.lift(range, |s| (s.target, s.target_end(), s.query))
+    }
+
+    /// Project the query interval `range` onto the target.
+    pub fn query_interval_to_ref(&self, range: Range<usize>) -> LiftedInterval {
+        self.lift(range, |s| (s.query, s.query_end(), s.target))
+    }
+
+    // `axis` gives a segment's source start and end and its destination start
+    fn lift(
+        &self,
+        range: Range<usize>,
+        axis: impl Fn(&Segment) -> (usize, usize, usize),
+    ) -> LiftedInterval {
+        let mut lifted = LiftedInterval::default();
+        let mut covered = 0;
+        let first = self.segments.partition_point(|s| axis(s).1 <= range.start);
+        for segment in &self.segments[first..] {
+            let (start, end, dest) = axis(segment);
+            if start >= range.end {
+                break;
+            }
+            let (from, to) = (start.max(range.start), end.min(range.end));
+            if from >= to {
+                continue;
+            }
+            covered += to - from;
+            if !segment.is_aligned() {
+                lifted.unmapped += to - from;
+                continue;
+            }
+            let projected = dest + (from - start)..dest + (to - start);
+            match lifted.blocks.last_mut() {
+                Some((source, target)) if source.end == from && target.end == projected.start => {
+                    source.end = to;
+                    target.end = projected.end;
+                }
+                _ => lifted.blocks.push((from..to, projected)),
+            }
+        }
+        // Bases outside the alignment
+        lifted.unmapped += range.len() - covered;
+        lifted
+    }
+}
@@ -0,0 +1,312 @@
+//! Minimal read-to-reference mapper: k-mer seeding, colinear chaining, anchored
+//! WFA alignment and PAF/SAM output. Meant as an end-to-end example of the crate
+//! and a template to fork, not as a replacement for a production mapper.
+
+use crate::affine_wavefront::{AffineWavefronts, AlignmentSpan, AlignmentStatus, MemoryMode};
+use crate::cigar::cigar_string;
+use crate::sequence::{kmers, reverse_complement};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapperOptions {
+    /// Seed length (at most 32).
+    pub k: usize,
+    /// Minimum number of anchors for a chain to be aligned.
+    pub min_anchors: usize,
+    /// Maximum distance between consecutive anchors of a chain.
+    pub max_gap: usize,
+    /// Extra reference bases aligned beyond the ends implied by the chain.
+    pub flank: usize,
+    pub memory_mode: MemoryMode,
+}
+
+impl Default for MapperOptions {
+    fn default() -> Self {
+        Self {
+            k: 15,
+            min_anchors: 2,
+            max_gap: 5000,
+            flank: 100,
+            memory_mode: MemoryMode::High,
+        }
+    }
+}
+
+/// Exact k-mer hit shared by the query and a reference sequence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    pub query_pos: usize,
+    pub target_pos: usize,
+}
+
+/// Colinear set of anchors on one reference sequence and strand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    pub target_id: usize,
+    pub reverse: bool,
+    pub anchors: Vec<Anchor>,
+}
+
+/// Base-level mapping of a query onto a reference sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mapping {
+    pub query_name: String,
+    pub query_len: usize,
+    pub query_start: usize,
+    pub query_end: usize,
+    pub reverse: bool,
+    pub target_name: String,
+    pub target_len: usize,
+    pub target_start: usize,
+    pub target_end: usize,
+    pub matches: usize,
+    pub block_len: usize,
+    pub mapq: u8,
+    pub score: i32,
+    /// Compact CIGAR (one operation per base) over the mapped region.
+    pub cigar: Vec<u8>,
+}
+
+impl Mapping {
+    /// PAF line (without trailing newline) with the CIGAR in a `cg:Z:` tag.
+    pub fn to_paf(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\tAS:i:{}\tcg:Z:{}",
+            self.query_name,
+            self.query_len,
+            self.query_start,
+            self.query_end,
+            if self.reverse { '-' } else { '+' },
+            self.target_name,
+            self.target_len,
+            self.target_start,
+            self.target_end,
+            self.matches,
+            self.block_len,
+            self.mapq,
+            self.score,
+            cigar_string(&self.cigar)
+        )
+    }
+
+    /// SAM line (without trailing newline). Unaligned query ends are soft-clipped.
+    pub fn to_sam(&self, query: &[u8]) -> String {
+        let seq = if self.reverse {
+            reverse_complement(query)
+        } else {
+            query.to_vec()
+        };
+        let mut cigar = String::new();
+        if self.query_start > 0 {
+            cigar.push_str(&format!("{}S", self.query_start));
+        }
+        cigar.push_str(&cigar_string(&self.cigar));
+        if self.query_end < self.query_len {
+            cigar.push_str(&format!("{}S", self.query_len - self.query_end));
+        }
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t*\t0\t0\t{}\t*\tAS:i:{}",
+            self.query_name,
+            if self.reverse { 16 } else { 0 },
+            self.target_name,
+            self.target_start + 1,
+            self.mapq,
+            cigar,
+            String::from_utf8_lossy(&seq),
+            self.score
+        )
+    }
+}
+
+/// K-mer index over a set of reference sequences.
+pub struct ReferenceIndex {
+    k: usize,
+    names: Vec<String>,
+    sequences: Vec<Vec<u8>>,
+    kmers: HashMap<u64, Vec<(usize, usize)>>,
+}
+
+impl ReferenceIndex {
+    pub fn new(k: usize) -> Self {
+        assert!((1..=32).contains(&k), "k must be between 1 and 32");
+        Self {
+            k,
+            names: Vec::new(),
+            sequences: Vec::new(),
+            kmers: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, name: &str, sequence: &[u8]) {
+        let id = self.sequences.len();
+        for (pos, kmer) in kmers(sequence, self.k) {
+            self.kmers.entry(kmer).or_default().push((id, pos));
+        }
+        self.names.push(name.to_string());
+        self.sequences.push(sequence.to_vec());
+    }
+
+    pub fn len(&self) -> usize {
+        self.sequences.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+}
+
+pub struct Mapper {
+    index: ReferenceIndex,
+    options: MapperOptions,
+    aligner: AffineWavefronts,
+}
+
+impl Mapper {
+    pub fn new(index: ReferenceIndex, options: MapperOptions) -> Self {
+        let aligner = AffineWavefronts::with_penalties_affine2p_and_memory_mode(
+            0,
+            4,
+            6,
+            2,
+            26,
+            1,
+            options.memory_mode.clone(),
+        );
+        Self {
+            index,
+            options,
+            aligner,
+        }
+    }
+
+    /// Best chain of the query over both strands, if any reaches `min_anchors`.
+    pub fn chain(&self, query: &[u8]) -> Option<Chain> {
+        let forward = self.best_chain(query, false);
+        let reverse = self.best_chain(&reverse_complement(query), true);
+        [forward, reverse]
+            .into_iter()
+            .flatten()
+            .max_by_key(|chain| chain.anchors.len())
+            .filter(|chain| chain.anchors.len() >= self.options.min_anchors)
+    }
+
+    /// Map one query: seed, chain, then align it against the chained region.
+    pub fn map(&mut self, name: &str, query: &[u8]) -> Option<Mapping> {
+        let chain = self.chain(query)?;
+        let oriented = if chain.reverse {
+            reverse_complement(query)
+        } else {
+            query.to_vec()
+        };
+        let target = &self.index.sequences[chain.target_id];
+
+        // Reference window covering the whole query around the chain
+        let first = chain.anchors.first()?;
+        let last = chain.anchors.last()?;
+        let window_start = first
+            .target_pos
+            .saturating_sub(first.query_pos + self.options.flank);
+        let window_end = (last.target_pos + (oriented.len() - last.query_pos) + self.options.flank)
+            .min(target.len());
+        let window = &target[window_start..window_end];
+
+        // Global on the query, free ends on the reference window
+        self.aligner.set_alignment_span(AlignmentSpan::EndsFree {
+            pattern_begin_free: 0,
+            pattern_end_free: 0,
+            text_begin_free: window.len() as i32,
+            text_end_free: window.len() as i32,
+        });
+        if self.aligner.align(&oriented, window) != AlignmentStatus::Completed {
+            return None;
+        }
+
+        // Reference-only operations at the ends are the unused part of the window
+        let cigar = self.aligner.cigar();
+        let leading = cigar.iter().take_while(|&&op| op == b'D').count();
+        let trailing = cigar[leading..]
+            .iter()
+            .rev()
+            .take_while(|&&op| op == b'D')
+            .count();
+        let core = cigar[leading..cigar.len() - trailing].to_vec();
+        let target_span = core.iter().filter(|&&op| op != b'I').count();
+        let matches = core.iter().filter(|&&op| op == b'M' || op == b'=').count();
+        let target_start = window_start + leading;
+
+        Some(Mapping {
+            query_name: name.to_string(),
+            query_len: query.len(),
+            query_start: 0,
+            query_end: query.len(),
+            reverse: chain.reverse,
+            target_name: self.index.names[chain.target_id].clone(),
+            target_len: target.len(),
+            target_start,
+            target_end: target_start + target_span,
+            matches,
+            block_len: core.len(),
+            mapq: mapq(chain.anchors.len()),
+            score: self.aligner.score(),
+            cigar: core,
+        })
+    }
+
+    fn best_chain(&self, query: &[u8], reverse: bool) -> Option<Chain> {
+        let mut hits: HashMap<usize, Vec<Anchor>> = HashMap::new();
+        for (query_pos, kmer) in kmers(query, self.index.k) {
+            for &(target_id, target_pos) in self.index.kmers.get(&kmer).into_iter().flatten() {
+                hits.entry(target_id).or_default().push(Anchor {
+                    query_pos,
+                    target_pos,
+                });
+            }
+        }
+        hits.into_iter()
+            .map(|(target_id, anchors)| Chain {
+                target_id,
+                reverse,
+                anchors: self.chain_anchors(anchors),
+            })
+            .max_by_key(|chain| chain.anchors.len())
+    }
+
+    // Longest colinear chain (O(n * lookback) dynamic programming)
+    fn chain_anchors(&self, mut anchors: Vec<Anchor>) -> Vec<Anchor> {
+        const LOOKBACK: usize = 64;
+        anchors.sort_by_key(|a| (a.target_pos, a.query_pos));
+        let mut best = vec![1usize; anchors.len()];
+        let mut prev = vec![usize::MAX; anchors.len()];
+        for i in 0..anchors.len() {
+            for j in i.saturating_sub(LOOKBACK)..i {
+                let (a, b) = (anchors[j], anchors[i]);
+                let colinear = a.query_pos < b.query_pos && a.target_pos < b.target_pos;
+                if !colinear
+                    || b.query_pos - a.query_pos > self.options.max_gap
+                    || b.target_pos - a.target_pos > self.options.max_gap
+                {
+                    continue;
+                }
+                if best[j] + 1 > best[i] {
+                    best[i] = best[j] + 1;
+                    prev[i] = j;
+                }
+            }
+        }
+        let Some(mut i) = (0..anchors.len()).max_by_key(|&i| best[i]) else {
+            return Vec::new();
+        };
+        let mut chain = vec![anchors[i]];
+        while prev[i] != usize::MAX {
+            i = prev[i];
+            chain.push(anchors[i]);
+        }
+        chain.reverse();
+        chain
+    }
+}
+
+// Crude mapping quality from chain support
+fn mapq(anchors: usize) -> u8 {
+    (anchors * 6).min(60) as u8
+}
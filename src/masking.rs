@@ -0,0 +1,212 @@
+//! Alignment of sequences containing masked regions (runs of `N`, hard-masked
+//! or user-supplied intervals). Masked stretches are not aligned; the pieces
+//! in between are aligned separately and stitched back together.
+
+use crate::affine_wavefront::{AffineWavefronts, AlignmentStatus};
+use std::ops::Range;
+
+/// Piece of a stitched alignment. Coordinates are 0-based, end-exclusive
+/// positions in the full query (pattern) and target (text).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Aligned {
+        query: Range<usize>,
+        target: Range<usize>,
+        status: AlignmentStatus,
+        /// `None` if the score overflowed `i64`.
+        score: Option<i64>,
+        cigar: Vec<u8>,
+    },
+    /// Masked stretch left unaligned. Either range may be empty.
+    Masked {
+        query: Range<usize>,
+        target: Range<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StitchedAlignment {
+    pub blocks: Vec<Block>,
+}
+
+impl StitchedAlignment {
+    /// Sum of the scores of the aligned blocks; masked blocks cost nothing.
+    /// `None` if a block score is missing or the sum overflows.
+    pub fn score(&self) -> Option<i64> {
+        self.blocks
+            .iter()
+            .try_fold(0i64, |total, block| match block {
+                Block::Aligned { score, .. } => total.checked_add((*score)?),
+                Block::Masked { .. } => Some(total),
+            })
+    }
+
+    /// Whether every aligned block completed.
+    pub fn is_complete(&self) -> bool {
+        self.blocks.iter().all(|block| match block {
+            Block::Aligned { status, .. } => *status == AlignmentStatus::Completed,
+            Block::Masked { .. } => true,
+        })
+    }
+}
+
+/// Runs of `N`/`n` of at least `min_len` bases.
+pub fn n_runs(sequence: &[u8], min_len: usize) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < sequence.len() {
+        let len = sequence[i..]
+            .iter()
+            .take_while(|&&b| b == b'N' || b == b'n')
+            .count();
+        if len > 0 && len >= min_len.max(1) {
+            runs.push(i..i + len);
+        }
+        i += len.max(1);
+    }
+    runs
+}
+
+// Unmasked segments between sorted, non-overlapping masked intervals
+fn segments(len: usize, masked: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for interval in masked {
+        segments.push(start..interval.start.min(len));
+        start = interval.end.min(len);
+    }
+    segments.push(start..len);
+    segments
+}
+
+/// Split both sequences at runs of at least `min_n_run` `N`s and align the
+/// unmasked segments pairwise in order. See [`align_masked`].
+pub fn align_split_on_n(
+    aligner: &AffineWavefronts,
+    query: &[u8],
+    target: &[u8],
+    min_n_run: usize,
+) -> Result<StitchedAlignment, String> {
+    align_masked(
+        aligner,
+        query,
+        &n_runs(query, min_n_run),
+        target,
+        &n_runs(target, min_n_run),
+    )
+}
+
+/// Align `query` and `target` around masked intervals (sorted and
+/// non-overlapping). The masked intervals of the two sequences must
+/// correspond one-to-one, e.g. two versions of a scaffold with the same gaps:
+/// the `i`-th unmasked segment of the query is aligned to the `i`-th unmasked
+/// segment of the target and each pair of masked intervals becomes one
+/// `Block::Masked`.
+pub fn align_masked(
+    aligner: &AffineWavefronts,
+    query: &[u8],
+    query_masked: &[Range<usize>],
+    target: &[u8],
+    target_masked: &[Range<usize>],
+) -> Result<StitchedAlignment, String> {
+    if query_masked.len() != target_masked.len() {
+        return Err(format!(
+            "Masked regions do not correspond: {} in the query, {} in the target",
+            query_masked.len(),
+            target_masked.len()
+        ));
+    }
+    let query_segments = segments(query.len(), query_masked);
+    let target_segments = segments(target.len(), target_masked);
+
+    let mut stitched = StitchedAlignment::default();
+    for (i, (q, t)) in query_segments.iter().zip(&target_segments).enumerate() {
+        if i > 0 {
+            stitched.blocks.push(Block::Masked {
+                query: query_masked[i - 1].clone(),
+                target: target_masked[i - 1].clone(),
+            });
+        }
+        if q.is_empty() && t.is_empty() {
+            continue;
+        }
+        let status = aligner.align(&query[q.clone()], &target[t.clone()]);
+        stitched.blocks.push(Block::Aligned {
+            query: q.clone(),
+            target: t.clone(),
+            status,
+            score: Some(aligner.score_i64()),
+            cigar: aligner.cigar().to_vec(),
+        });
+    }
+    Ok(stitched)
+}
+
+/// Align `query` against `target` while skipping the `excluded` target
+/// intervals (sorted and non-overlapping), e.g. known segmental duplications.
+///
+/// The excluded intervals are cut out of the target and the query is aligned
+/// once against what remains, so the query needs no corresponding breakpoints.
+/// The CIGAR is then split where the cuts were and each excluded interval is
+/// reported as a `Block::Masked` with an empty query range. Block scores are
+/// recomputed from their CIGARs, so gaps that span a cut are charged to the
+/// block they start in.
+pub fn align_excluding_target(
+    aligner: &AffineWavefronts,
+    query: &[u8],
+    target: &[u8],
+    excluded: &[Range<usize>],
+) -> StitchedAlignment {
+    let kept = segments(target.len(), excluded);
+    let cut: Vec<u8> = kept
+        .iter()
+        .flat_map(|segment| target[segment.clone()].iter().copied())
+        .collect();
+    let status = aligner.align(query, &cut);
+    let cigar = aligner.cigar();
+
+    let mut stitched = StitchedAlignment::default();
+    let (mut query_pos, mut cut_pos) = (0, 0);
+    let mut op_index = 0;
+    for (i, segment) in kept.iter().enumerate() {
+        if i > 0 {
+            stitched.blocks.push(Block::Masked {
+                query: query_pos..query_pos,
+                target: excluded[i - 1].clone(),
+            });
+        }
+        // Take operations until this segment's target bases are consumed;
+        // trailing query-only operations go to the last segment.
+        let (query_start, segment_end) = (query_pos, cut_pos + segment.len());
+        let op_start = op_index;
+        while op_index < cigar.len() {
+            let op = cigar[op_index];
+            let consumes_target = matches!(op, b'M' | b'=' | b'X' | b'D');
+            if consumes_target && cut_pos == segment_end {
+                break;
+            }
+            if i + 1 < kept.len() && !consumes_target && cut_pos == segment_end {
+                break;
+            }
+            if consumes_target {
+                cut_pos += 1;
+            }
+            if matches!(op, b'M' | b'=' | b'X' | b'I') {
+                query_pos += 1;
+            }
+            op_index += 1;
+        }
+        let block_cigar = cigar[op_start..op_index].to_vec();
+        if block_cigar.is_empty() && segment.is_empty() {
+            continue;
+        }
+        stitched.blocks.push(Block::Aligned {
+            query: query_start..query_pos,
+            target: segment.clone(),
+            status: status.clone(),
+            score: aligner.score_cigar_i64(&block_cigar),
+            cigar: block_cigar,
+        });
+    }
+    stitched
+}
@@ -0,0 +1,84 @@
+//! Conversions for writing alignments with noodles (feature `noodles`):
+//! CIGARs as `noodles_sam` CIGARs, and alignments as fields of a
+//! `noodles_sam` record builder, e.g. for BAM output.
+
+use crate::affine_wavefront::Alignment;
+use crate::cigar::{cigar_runs, CigarOp};
+use noodles_core::Position;
+use noodles_sam::alignment::record::cigar::{op::Kind, Op};
+use noodles_sam::alignment::record::Flags;
+use noodles_sam::alignment::record_buf::{Builder, Cigar, Sequence};
+
+impl From<CigarOp> for Kind {
+    fn from(op: CigarOp) -> Self {
+        match op {
+            // WFA2 reports mismatches separately, so `M` is a match
+            CigarOp::Match | CigarOp::Equal => Kind::SequenceMatch,
+            CigarOp::Mismatch => Kind::SequenceMismatch,
+            CigarOp::Insertion => Kind::Insertion,
+            CigarOp::Deletion => Kind::Deletion,
+        }
+    }
+}
+
+fn cigar_ops(cigar: &[u8], collapse_matches: bool) -> impl Iterator<Item = Op> + '_ {
+    cigar_runs(cigar).map(move |(op, len)| {
+        let kind = match Kind::from(op) {
+            Kind::SequenceMatch | Kind::SequenceMismatch if collapse_matches => Kind::Match,
+            kind => kind,
+        };
+        Op::new(kind, len as usize)
+    })
+}
+
+/// noodles CIGAR for a compact CIGAR. With `collapse_matches`, matches and
+/// mismatches use `M`; otherwise `=` and `X`. Adjacent runs that collapse to
+/// the same operation are merged.
+pub fn to_noodles_cigar(cigar: &[u8], collapse_matches: bool) -> Cigar {
+    let mut ops: Vec<Op> = Vec::new();
+    for op in cigar_ops(cigar, collapse_matches) {
+        match ops.last_mut() {
+            Some(last) if last.kind() == op.kind() => {
+                *last = Op::new(op.kind(), last.len() + op.len())
+            }
+            _ => ops.push(op),
+        }
+    }
+    Cigar::from(ops)
+}
+
+impl Alignment {
+    /// Fill the alignment fields of a noodles record builder: flags, 1-based
+    /// alignment start (from the 0-based `target_offset` of the aligned target
+    /// region), CIGAR with soft clips for unaligned query ends (see
+    /// [`Alignment::to_sam_record`]) and the query sequence. Name, reference
+    /// sequence ID and data fields are left to the caller. An alignment that
+    /// did not complete is marked unmapped.
+    pub fn populate_record_builder(
+        &self,
+        builder: Builder,
+        query: &[u8],
+        target_offset: usize,
+    ) -> Builder {
+        let builder = builder.set_sequence(Sequence::from(query.to_vec()));
+        if !self.status.is_complete() {
+            return builder.set_flags(Flags::UNMAPPED);
+        }
+        let layout = self.sam_layout(query.len());
+        let mut ops = Vec::new();
+        if layout.clip_start > 0 {
+            ops.push(Op::new(Kind::SoftClip, layout.clip_start));
+        }
+        ops.extend(to_noodles_cigar(layout.body, true).as_ref().iter().copied());
+        if layout.clip_end > 0 {
+            ops.push(Op::new(Kind::SoftClip, layout.clip_end));
+        }
+        let builder = builder
+            .set_flags(Flags::empty())
+            .set_cigar(Cigar::from(ops));
+        match Position::new(target_offset + layout.skipped + 1) {
+            Some(start) => builder.set_alignment_start(start),
+            None => builder,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! One-vs-many alignment: a fixed pattern against many texts with a single
+//! aligner, e.g. a read against candidate targets for pseudo-mapping.
+
+use crate::affine_wavefront::{AffineWavefronts, Alignment};
+
+/// Alignment of the pattern against one of the texts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextAlignment {
+    /// Position of the text in the input.
+    pub index: usize,
+    pub alignment: Alignment,
+}
+
+impl AffineWavefronts {
+    /// Align `pattern` against each of `texts`, reusing this aligner, and
+    /// return one result per text in input order, whatever its status.
+    pub fn align_one_to_many<T: AsRef<[u8]>>(
+        &self,
+        pattern: &[u8],
+        texts: &[T],
+    ) -> Vec<TextAlignment> {
+        texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| TextAlignment {
+                index,
+                alignment: self.align_owned(pattern, text.as_ref()),
+            })
+            .collect()
+    }
+
+    /// The `k` best completed alignments of `pattern` against `texts`, best
+    /// score first; ties keep input order. Only the current best `k` are kept
+    /// while aligning, so many texts can be scanned without holding every
+    /// CIGAR.
+    pub fn align_one_to_many_best<T: AsRef<[u8]>>(
+        &self,
+        pattern: &[u8],
+        texts: &[T],
+        k: usize,
+    ) -> Vec<TextAlignment> {
+        let mut best: Vec<TextAlignment> = Vec::with_capacity(k.min(texts.len()) + 1);
+        if k == 0 {
+            return best;
+        }
+        for (index, text) in texts.iter().enumerate() {
+            let status = self.align(pattern, text.as_ref());
+            if !status.is_complete() {
+                continue;
+            }
+            let score = self.score();
+            // Skip the CIGAR copy for texts that would not make the cut
+            if best.len() == k && best[k - 1].alignment.score >= score {
+                continue;
+            }
+            let position = best.partition_point(|hit| hit.alignment.score >= score);
+            best.insert(
+                position,
+                TextAlignment {
+                    index,
+                    alignment: self.alignment(status),
+                },
+            );
+            best.truncate(k);
+        }
+        best
+    }
+}
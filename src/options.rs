@@ -0,0 +1,134 @@
+//! Per-call alignment options, so callers (e.g. batch drivers) can carry a
+//! different option set for each pair without reconfiguring shared aligners.
+
+use crate::affine_wavefront::{
+    AffineWavefronts, AlignmentScope, AlignmentSpan, AlignmentStatus, HeuristicStrategy,
+};
+use crate::orient::{OrientOptions, Strand};
+use std::time::{Duration, Instant};
+
+/// Which orientations of the query to try.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Orientation {
+    #[default]
+    Forward,
+    /// Try both strands and keep the better one, see
+    /// [`AffineWavefronts::align_auto_orient_with`].
+    Auto(OrientOptions),
+}
+
+/// How much of the alignment to compute and return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputDetail {
+    /// Score only; no CIGAR is computed.
+    ScoreOnly,
+    #[default]
+    Cigar,
+}
+
+/// Options for a single alignment. `None` keeps the aligner's own setting.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AlignOptions {
+    /// Ends-free spans need an aligner without a match bonus, see
+    /// [`AffineWavefronts::set_alignment_span`].
+    pub span: Option<AlignmentSpan>,
+    pub heuristic: Option<HeuristicStrategy>,
+    pub max_steps: Option<i32>,
+    /// Upper bound on the alignment penalty. WFA2 advances one score per step,
+    /// so this is applied together with `max_steps` (the smaller one wins).
+    pub max_score: Option<i32>,
+    pub orientation: Orientation,
+    pub detail: OutputDetail,
+}
+
+/// Owned result of one alignment. For `Strand::Reverse` the CIGAR refers to
+/// the reverse complement of the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignmentResult {
+    pub strand: Strand,
+    pub status: AlignmentStatus,
+    pub score: i32,
+    /// Empty with `OutputDetail::ScoreOnly`.
+    pub cigar: Vec<u8>,
+    /// Wall-clock duration of the alignment (of both orientations with
+    /// `Orientation::Auto`), if the aligner records timing (see
+    /// [`AffineWavefronts::set_record_timing`]).
+    pub elapsed: Option<Duration>,
+}
+
+impl AffineWavefronts {
+    /// Align `query` against `target` with `options` applied for this call
+    /// only; the aligner's previous span, heuristic, step limit and scope are
+    /// restored before returning.
+    ///
+    /// # Panics
+    ///
+    /// If `options.span` is ends-free and the aligner has a non-zero match
+    /// score, see [`AffineWavefronts::set_alignment_span`].
+    pub fn align_with_options(
+        &mut self,
+        query: &[u8],
+        target: &[u8],
+        options: &AlignOptions,
+    ) -> AlignmentResult {
+        let span = self.get_alignment_span();
+        let heuristics = self.get_heuristics();
+        let max_steps = self.get_max_alignment_steps();
+        let scope = self.get_alignment_scope();
+
+        if let Some(new_span) = &options.span {
+            self.set_alignment_span(new_span.clone());
+        }
+        if let Some(new_heuristic) = &options.heuristic {
+            self.set_heuristic(new_heuristic);
+        }
+        let limit = match (options.max_steps, options.max_score) {
+            (Some(steps), Some(score)) => Some(steps.min(score)),
+            (steps, score) => steps.or(score),
+        };
+        if let Some(limit) = limit {
+            self.set_max_alignment_steps(limit);
+        }
+        self.set_alignment_scope(match options.detail {
+            OutputDetail::ScoreOnly => AlignmentScope::ComputeScore,
+            OutputDetail::Cigar => AlignmentScope::Alignment,
+        });
+
+        let start = self.get_record_timing().then(Instant::now);
+        let mut result = match &options.orientation {
+            Orientation::Forward => {
+                let status = self.align(query, target);
+                AlignmentResult {
+                    strand: Strand::Forward,
+                    status,
+                    score: self.score(),
+                    cigar: self.cigar().to_vec(),
+                    elapsed: None,
+                }
+            }
+            Orientation::Auto(orient) => {
+                let best = self.align_auto_orient_with(query, target, orient);
+                AlignmentResult {
+                    strand: best.strand,
+                    status: best.status,
+                    score: best.score,
+                    cigar: best.cigar,
+                    elapsed: None,
+                }
+            }
+        };
+        result.elapsed = start.map(|start| start.elapsed());
+        if options.detail == OutputDetail::ScoreOnly {
+            // Whatever CIGAR the aligner holds belongs to an earlier alignment
+            result.cigar.clear();
+        }
+
+        self.set_alignment_span(span);
+        self.set_heuristics(&heuristics);
+        self.set_max_alignment_steps(max_steps);
+        if scope != AlignmentScope::Undefined {
+            self.set_alignment_scope(scope);
+        }
+        result
+    }
+}
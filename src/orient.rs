@@ -0,0 +1,121 @@
+//! Automatic orientation selection: align the query and its reverse
+//! complement and keep the better alignment.
+
+use crate::affine_wavefront::{AffineWavefronts, AlignmentStatus};
+use crate::sequence::{kmers, reverse_complement};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strand {
+    Forward,
+    Reverse,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrientOptions {
+    /// Count shared k-mers of this length to decide which strand to try
+    /// first. `None` always starts with the forward strand. K-mers are packed
+    /// into a `u64`, so `k` is clamped to `1..=32`.
+    pub prefilter_k: Option<usize>,
+    /// Skip the second orientation when the first reaches this identity.
+    pub skip_identity: f64,
+}
+
+impl Default for OrientOptions {
+    fn default() -> Self {
+        Self {
+            prefilter_k: Some(15),
+            skip_identity: 0.99,
+        }
+    }
+}
+
+/// Alignment of the query in the orientation that scored best. For
+/// `Strand::Reverse` the CIGAR refers to the reverse complement of the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrientedAlignment {
+    pub strand: Strand,
+    pub status: AlignmentStatus,
+    pub score: i32,
+    pub cigar: Vec<u8>,
+}
+
+impl OrientedAlignment {
+    fn identity(&self) -> f64 {
+        if self.cigar.is_empty() {
+            return 0.0;
+        }
+        let matches = self
+            .cigar
+            .iter()
+            .filter(|&&op| op == b'M' || op == b'=')
+            .count();
+        matches as f64 / self.cigar.len() as f64
+    }
+}
+
+// Strand sharing more k-mers with the target
+fn likely_strand(query: &[u8], reverse: &[u8], target: &[u8], k: usize) -> Strand {
+    let k = k.clamp(1, 32);
+    let target_kmers: HashSet<u64> = kmers(target, k).map(|(_, kmer)| kmer).collect();
+    let shared = |seq: &[u8]| {
+        kmers(seq, k)
+            .filter(|(_, kmer)| target_kmers.contains(kmer))
+            .count()
+    };
+    if shared(reverse) > shared(query) {
+        Strand::Reverse
+    } else {
+        Strand::Forward
+    }
+}
+
+impl AffineWavefronts {
+    /// Align `query` and its reverse complement against `target` with default
+    /// [`OrientOptions`] and return the better alignment.
+    pub fn align_auto_orient(&self, query: &[u8], target: &[u8]) -> OrientedAlignment {
+        self.align_auto_orient_with(query, target, &OrientOptions::default())
+    }
+
+    pub fn align_auto_orient_with(
+        &self,
+        query: &[u8],
+        target: &[u8],
+        options: &OrientOptions,
+    ) -> OrientedAlignment {
+        let reverse = reverse_complement(query);
+        let first = match options.prefilter_k {
+            Some(k) => likely_strand(query, &reverse, target, k),
+            None => Strand::Forward,
+        };
+
+        let align = |strand: Strand| {
+            let oriented = match strand {
+                Strand::Forward => query,
+                Strand::Reverse => &reverse,
+            };
+            let status = self.align(oriented, target);
+            OrientedAlignment {
+                strand,
+                status,
+                score: self.score(),
+                cigar: self.cigar().to_vec(),
+            }
+        };
+
+        let best = align(first);
+        if best.status == AlignmentStatus::Completed && best.identity() >= options.skip_identity {
+            return best;
+        }
+        let second = align(match first {
+            Strand::Forward => Strand::Reverse,
+            Strand::Reverse => Strand::Forward,
+        });
+        let completed = |a: &OrientedAlignment| a.status == AlignmentStatus::Completed;
+        if (completed(&second), second.score) > (completed(&best), best.score) {
+            second
+        } else {
+            best
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Base-level refinement of approximate PAF mappings: each mapped region is
+//! fetched from the query and target sequences, realigned with WFA2 and
+//! written back with an exact `cg:Z:` CIGAR and recomputed identity.
+
+use crate::affine_wavefront::{AffineWavefronts, Alignment, AlignmentStatus};
+use crate::cigar::{cigar_string, strip_gap_ends, CigarStats};
+use crate::sequence::reverse_complement;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// One PAF line: the twelve mandatory columns plus optional SAM-style tags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PafRecord {
+    pub query_name: String,
+    pub query_len: usize,
+    pub query_start: usize,
+    pub query_end: usize,
+    pub reverse: bool,
+    pub target_name: String,
+    pub target_len: usize,
+    pub target_start: usize,
+    pub target_end: usize,
+    pub matches: usize,
+    pub block_len: usize,
+    pub mapq: u8,
+    pub tags: Vec<String>,
+}
+
+impl PafRecord {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.trim_end().split('\t').collect();
+        if fields.len() < 12 {
+            return Err(format!(
+                "PAF line has {} columns, expected at least 12",
+                fields.len()
+            ));
+        }
+        let number = |i: usize| -> Result<usize, String> {
+            fields[i]
+                .parse()
+                .map_err(|_| format!("Invalid number '{}' in PAF column {}", fields[i], i + 1))
+        };
+        let reverse = match fields[4] {
+            "+" => false,
+            "-" => true,
+            other => return Err(format!("Invalid PAF strand '{other}'")),
+        };
+        Ok(Self {
+            query_name: fields[0].to_string(),
+            query_len: number(1)?,
+            query_start: number(2)?,
+            query_end: number(3)?,
+            reverse,
+            target_name: fields[5].to_string(),
+            target_len: number(6)?,
+            target_start: number(7)?,
+            target_end: number(8)?,
+            matches: number(9)?,
+            block_len: number(10)?,
+            mapq: fields[11]
+                .parse()
+                .map_err(|_| format!("Invalid mapping quality '{}'", fields[11]))?,
+            tags: fields[12..].iter().map(|tag| tag.to_string()).collect(),
+        })
+    }
+}
+
+impl fmt::Display for PafRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.query_name,
+            self.query_len,
+            self.query_start,
+            self.query_end,
+            if self.reverse { '-' } else { '+' },
+            self.target_name,
+            self.target_len,
+            self.target_start,
+            self.target_end,
+            self.matches,
+            self.block_len,
+            self.mapq
+        )?;
+        for tag in &self.tags {
+            write!(f, "\t{tag}")?;
+        }
+        Ok(())
+    }
+}
+
+// Tags describing the old alignment, replaced on realignment
+const REPLACED_TAGS: [&str; 4] = ["cg:Z:", "gi:f:", "bi:f:", "AS:i:"];
+
+/// Realign the region of one record. `query` and `target` are the full
+/// sequences named in the record. On success the record gets exact match and
+/// block counts, and `cg:Z:` (CIGAR with `=`/`X`), `gi:f:` (gap-compressed
+/// identity), `bi:f:` (block identity) and `AS:i:` (score) tags replacing any
+/// previous ones.
+pub fn realign_record(
+    aligner: &AffineWavefronts,
+    record: &PafRecord,
+    query: &[u8],
+    target: &[u8],
+) -> Result<PafRecord, String> {
+    if record.query_start > record.query_end || record.query_end > query.len() {
+        return Err(format!(
+            "Query range {}-{} is outside '{}' ({} bp)",
+            record.query_start,
+            record.query_end,
+            record.query_name,
+            query.len()
+        ));
+    }
+    if record.target_start > record.target_end || record.target_end > target.len() {
+        return Err(format!(
+            "Target range {}-{} is outside '{}' ({} bp)",
+            record.target_start,
+            record.target_end,
+            record.target_name,
+            target.len()
+        ));
+    }
+    let region = &query[record.query_start..record.query_end];
+    let region = if record.reverse {
+        reverse_complement(region)
+    } else {
+        region.to_vec()
+    };
+    let target_region = &target[record.target_start..record.target_end];
+    let status = aligner.align(&region, target_region);
+    if status != AlignmentStatus::Completed {
+        return Err(format!(
+            "Realignment of '{}' to '{}' did not complete: {status:?}",
+            record.query_name, record.target_name
+        ));
+    }
+
+    let mut realigned = record.clone();
+    realigned
+        .tags
+        .retain(|tag| !REPLACED_TAGS.iter().any(|prefix| tag.starts_with(prefix)));
+    realigned.set_alignment(aligner.cigar(), aligner.score());
+    Ok(realigned)
+}
+
+impl PafRecord {
+    // Match and block counts and the alignment tags from a compact CIGAR
+    fn set_alignment(&mut self, cigar: &[u8], score: i32) {
+        let stats = CigarStats::from_cigar(cigar);
+        self.matches = stats.matches;
+        self.block_len = stats.aligned_length();
+        self.tags.extend([
+            format!("gi:f:{:.6}", stats.gap_compressed_identity()),
+            format!("bi:f:{:.6}", stats.block_identity()),
+            format!("AS:i:{score}"),
+            format!("cg:Z:{}", cigar_string(cigar)),
+        ]);
+    }
+}
+
+impl Alignment {
+    /// PAF record for this alignment of a query (named `query_name`, of
+    /// `query_len` bases) against the target region that starts at 0-based
+    /// `target_offset` in `target_name`. With `reverse`, the alignment is of
+    /// the reverse complement of the query and query coordinates are
+    /// reported on the forward strand, as minimap2 does. Gaps at the ends of
+    /// the CIGAR (from ends-free alignment) are excluded from the mapped
+    /// region. Mapping quality is unknown and written as 255.
+    pub fn to_paf(
+        &self,
+        query_name: &str,
+        query_len: usize,
+        target_name: &str,
+        target_len: usize,
+        target_offset: usize,
+        reverse: bool,
+    ) -> PafRecord {
+        let (query_skipped, target_skipped, body) = strip_gap_ends(&self.cigar);
+        let query_start = query_skipped;
+        let query_end = query_start + body.iter().filter(|&&op| op != b'D').count();
+        let target_start = target_offset + target_skipped;
+        let (query_start, query_end) = if reverse {
+            (query_len - query_end, query_len - query_start)
+        } else {
+            (query_start, query_end)
+        };
+        let mut record = PafRecord {
+            query_name: query_name.to_string(),
+            query_len,
+            query_start,
+            query_end,
+            reverse,
+            target_name: target_name.to_string(),
+            target_len,
+            target_start,
+            target_end: target_start + body.iter().filter(|&&op| op != b'I').count(),
+            matches: 0,
+            block_len: 0,
+            mapq: 255,
+            tags: Vec::new(),
+        };
+        record.set_alignment(body, self.score);
+        record
+    }
+}
+
+/// Records processed by [`realign_paf`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RealignSummary {
+    pub realigned: usize,
+    /// Records written back unchanged because a sequence was missing or the
+    /// realignment failed.
+    pub failed: usize,
+}
+
+/// Stream PAF records from `input`, realign each against the `queries` and
+/// `targets` sequences (e.g. from [`crate::sequence::read_fasta`]) and write
+/// the refined records to `output`. Records that cannot be realigned are
+/// written unchanged and counted as failed; malformed lines are an error.
+pub fn realign_paf<R: BufRead, W: Write>(
+    aligner: &AffineWavefronts,
+    queries: &HashMap<String, Vec<u8>>,
+    targets: &HashMap<String, Vec<u8>>,
+    input: R,
+    mut output: W,
+) -> io::Result<RealignSummary> {
+    let mut summary = RealignSummary::default();
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record =
+            PafRecord::parse(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let realigned = match (
+            queries.get(&record.query_name),
+            targets.get(&record.target_name),
+        ) {
+            (Some(query), Some(target)) => realign_record(aligner, &record, query, target).ok(),
+            _ => None,
+        };
+        match realigned {
+            Some(realigned) => {
+                summary.realigned += 1;
+                writeln!(output, "{realigned}")?;
+            }
+            None => {
+                summary.failed += 1;
+                writeln!(output, "{record}")?;
+            }
+        }
+    }
+    Ok(summary)
+}
@@ -0,0 +1,171 @@
+//! Pool of aligners sharing one configuration, for data-parallel drivers such
+//! as rayon where every worker thread needs an aligner of its own.
+
+use crate::affine_wavefront::{
+    AffineWavefronts, AffineWavefrontsBuilder, AlignerConfig, Alignment, BuildError,
+};
+use crate::affinity::CorePinning;
+use crate::batch::BatchOptions;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ops::Deref;
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+
+/// Thread-safe pool handing out aligners built from a shared configuration.
+/// Aligners are created on demand and returned to the pool when the guard
+/// from [`AlignerPool::get`] is dropped, so a pool used from `n` threads holds
+/// at most `n` aligners.
+///
+/// ```ignore
+/// let pool = AlignerPool::new(AffineWavefrontsBuilder::new().penalties(0, 4, 6, 2))?;
+/// let scores: Vec<i32> = pairs
+///     .par_iter()
+///     .map(|(query, target)| pool.get().align_owned(query, target).score)
+///     .collect();
+/// ```
+pub struct AlignerPool {
+    config: AffineWavefrontsBuilder,
+    idle: Mutex<Vec<AffineWavefronts>>,
+    options: BatchOptions,
+    // Threads already pinned, in order, as worker indices for the pinning
+    pinned: Mutex<HashSet<ThreadId>>,
+}
+
+impl AlignerPool {
+    /// Pool of aligners built from `config`, which is checked here (see
+    /// [`AffineWavefrontsBuilder::check`]) so that building them later on
+    /// worker threads cannot fail.
+    pub fn new(config: AffineWavefrontsBuilder) -> Result<Self, BuildError> {
+        config.check()?;
+        Ok(Self {
+            config,
+            idle: Mutex::new(Vec::new()),
+            options: BatchOptions::default(),
+            pinned: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Schedule [`Self::align`] by `options`, e.g. within a memory budget,
+    /// and pin the threads using the pool: each is pinned on its first
+    /// [`Self::get`], before it takes or builds an aligner. Set options before
+    /// the first `get`, as aligners already built keep their timing setting.
+    pub fn with_options(mut self, options: BatchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// An idle aligner, or a newly built one if all are in use.
+    pub fn get(&self) -> PooledAligner<'_> {
+        if self.options.pinning != CorePinning::None {
+            self.pin_current_thread();
+        }
+        let idle = self.idle.lock().ok().and_then(|mut idle| idle.pop());
+        PooledAligner {
+            pool: self,
+            aligner: Some(idle.unwrap_or_else(|| {
+                let mut aligner = self.config.clone().build_unchecked();
+                if self.options.record_timing {
+                    aligner.set_record_timing(true);
+                }
+                aligner
+            })),
+        }
+    }
+
+    /// Align `pattern` against `text` with an aligner from the pool. With a
+    /// memory budget (see [`BatchOptions`]), the estimated memory of the pair
+    /// is reserved while it is aligned, waiting until it fits.
+    pub fn align(&self, pattern: &[u8], text: &[u8]) -> Alignment {
+        let aligner = self.get();
+        let _reservation = self.options.reserve(&aligner, pattern, text);
+        aligner.align_owned(pattern, text)
+    }
+
+    fn pin_current_thread(&self) {
+        let Ok(mut pinned) = self.pinned.lock() else {
+            return;
+        };
+        if !pinned.insert(thread::current().id()) {
+            return;
+        }
+        let worker_index = pinned.len() - 1;
+        drop(pinned);
+        let _ = self.options.pinning.apply(worker_index);
+    }
+
+    /// Number of aligners currently waiting in the pool.
+    pub fn idle(&self) -> usize {
+        self.idle.lock().map_or(0, |idle| idle.len())
+    }
+}
+
+/// Aligner borrowed from an [`AlignerPool`], returned to it on drop. It is
+/// only lent out as `&AffineWavefronts`, so every aligner in the pool keeps
+/// the pool's configuration.
+pub struct PooledAligner<'a> {
+    pool: &'a AlignerPool,
+    aligner: Option<AffineWavefronts>,
+}
+
+impl Deref for PooledAligner<'_> {
+    type Target = AffineWavefronts;
+
+    fn deref(&self) -> &AffineWavefronts {
+        self.aligner
+            .as_ref()
+            .expect("aligner is present until drop")
+    }
+}
+
+impl Drop for PooledAligner<'_> {
+    fn drop(&mut self) {
+        if let (Some(aligner), Ok(mut idle)) = (self.aligner.take(), self.pool.idle.lock()) {
+            idle.push(aligner);
+        }
+    }
+}
+
+thread_local! {
+    // Aligners of the current thread, by configuration
+    static LOCAL_ALIGNERS: RefCell<Vec<(AlignerConfig, AffineWavefronts)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Run `f` with the current thread's aligner for `config`, built on first use
+/// and kept until the thread exits or [`clear_thread_local_aligners`] is
+/// called. Unlike an [`AlignerPool`], nothing needs to be shared between the
+/// threads, e.g. across rayon closures:
+///
+/// ```ignore
+/// let scores: Vec<i32> = pairs
+///     .par_iter()
+///     .map(|(query, target)| {
+///         thread_local_aligner(&config, |aligner| aligner.align_owned(query, target).score)
+///     })
+///     .collect();
+/// ```
+///
+/// Panics if `config` is invalid, like [`AlignerConfig::build`].
+pub fn thread_local_aligner<R>(
+    config: &AlignerConfig,
+    f: impl FnOnce(&AffineWavefronts) -> R,
+) -> R {
+    // Taken out of the cache while `f` runs, so that `f` can use thread-local
+    // aligners too
+    let cached = LOCAL_ALIGNERS.with(|aligners| {
+        let mut aligners = aligners.borrow_mut();
+        let position = aligners.iter().position(|(cached, _)| cached == config)?;
+        Some(aligners.swap_remove(position))
+    });
+    let (config, aligner) = cached.unwrap_or_else(|| (config.clone(), config.build()));
+    let result = f(&aligner);
+    LOCAL_ALIGNERS.with(|aligners| aligners.borrow_mut().push((config, aligner)));
+    result
+}
+
+/// Drop the aligners cached by [`thread_local_aligner`] on the current
+/// thread, e.g. to release their memory after a burst of work.
+pub fn clear_thread_local_aligners() {
+    LOCAL_ALIGNERS.with(|aligners| aligners.borrow_mut().clear());
+}
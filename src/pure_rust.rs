@@ -0,0 +1,342 @@
+//! Gap-affine wavefront alignment in pure Rust (feature `pure-rust`), for
+//! dependents that need an aligner without the WFA2 C library in the loop,
+//! e.g. to run their tests under miri. It computes end-to-end alignments
+//! with the same scores as WFA2 (see the parity tests) and keeps every
+//! wavefront for the traceback, so it is slower and uses more memory than
+//! any of WFA2's memory modes.
+
+use crate::types::{AffinePenalties, Alignment, AlignmentStatus, PenaltyError};
+
+// Offset of an unreachable cell; far enough from i32::MIN to add to safely
+const NONE: i32 = i32::MIN / 2;
+
+// Wavefronts of one score, indexed by diagonal `k = h - v` (text position
+// minus pattern position) from `lo` to `hi`. Offsets are text positions.
+struct Wavefront {
+    lo: i32,
+    hi: i32,
+    m: Vec<i32>,
+    i: Vec<i32>,
+    d: Vec<i32>,
+}
+
+impl Wavefront {
+    fn get(values: &[i32], lo: i32, hi: i32, k: i32) -> i32 {
+        if k < lo || k > hi {
+            NONE
+        } else {
+            values[(k - lo) as usize]
+        }
+    }
+
+    fn m(&self, k: i32) -> i32 {
+        Self::get(&self.m, self.lo, self.hi, k)
+    }
+
+    fn i(&self, k: i32) -> i32 {
+        Self::get(&self.i, self.lo, self.hi, k)
+    }
+
+    fn d(&self, k: i32) -> i32 {
+        Self::get(&self.d, self.lo, self.hi, k)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Matrix {
+    M,
+    I,
+    D,
+}
+
+/// Gap-affine aligner with the core API of
+/// [`AffineWavefronts`](crate::affine_wavefront::AffineWavefronts): align,
+/// then read the score (negated penalty, as WFA2 reports it) and the compact
+/// CIGAR (`M`, `X`, `I` consuming the pattern, `D` consuming the text).
+pub struct PureAffineWavefronts {
+    penalties: AffinePenalties,
+    max_alignment_steps: i32,
+    score: i32,
+    cigar: Vec<u8>,
+}
+
+impl Default for PureAffineWavefronts {
+    /// WFA2's default gap-affine penalties: mismatch 4, gap opening 6, gap
+    /// extension 2.
+    fn default() -> Self {
+        Self::with_penalties(4, 6, 2).expect("default penalties are valid")
+    }
+}
+
+impl PureAffineWavefronts {
+    /// Aligner for `mismatch`, `gap_opening` and `gap_extension` penalties
+    /// (a gap of length `l` costs `gap_opening + l * gap_extension`).
+    /// Matches are free.
+    pub fn with_penalties(
+        mismatch: i32,
+        gap_opening: i32,
+        gap_extension: i32,
+    ) -> Result<Self, PenaltyError> {
+        let penalties = AffinePenalties {
+            match_score: 0,
+            mismatch,
+            gap_opening,
+            gap_extension,
+        };
+        penalties.validate()?;
+        Ok(Self {
+            penalties,
+            max_alignment_steps: i32::MAX,
+            score: 0,
+            cigar: Vec::new(),
+        })
+    }
+
+    pub fn get_penalties(&self) -> AffinePenalties {
+        self.penalties
+    }
+
+    /// Give up with `AlignmentStatus::MaxStepsReached` once the penalty
+    /// exceeds `steps`.
+    pub fn set_max_alignment_steps(&mut self, steps: i32) {
+        self.max_alignment_steps = steps;
+    }
+
+    pub fn get_max_alignment_steps(&self) -> i32 {
+        self.max_alignment_steps
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn cigar(&self) -> &[u8] {
+        &self.cigar
+    }
+
+    /// Align `pattern` end to end against `text`.
+    pub fn align(&mut self, pattern: &[u8], text: &[u8]) -> AlignmentStatus {
+        self.score = 0;
+        self.cigar.clear();
+        let (n, m) = (pattern.len() as i32, text.len() as i32);
+        let end_k = m - n;
+
+        let mut wavefronts: Vec<Option<Wavefront>> = Vec::new();
+        let mut initial = Wavefront {
+            lo: 0,
+            hi: 0,
+            m: vec![0],
+            i: vec![NONE],
+            d: vec![NONE],
+        };
+        extend(&mut initial, pattern, text);
+        wavefronts.push(Some(initial));
+
+        let mut score = 0;
+        loop {
+            if let Some(wavefront) = &wavefronts[score as usize] {
+                if wavefront.m(end_k) >= m {
+                    break;
+                }
+            }
+            if score >= self.max_alignment_steps {
+                return AlignmentStatus::MaxStepsReached;
+            }
+            score += 1;
+            let wavefront = self
+                .next_wavefront(&wavefronts, score, n, m)
+                .map(|mut wavefront| {
+                    extend(&mut wavefront, pattern, text);
+                    wavefront
+                });
+            wavefronts.push(wavefront);
+        }
+
+        self.score = -score;
+        self.cigar = self.traceback(&wavefronts, score, end_k, m, n);
+        AlignmentStatus::Completed
+    }
+
+    /// Align and return the result by value.
+    pub fn align_owned(&mut self, pattern: &[u8], text: &[u8]) -> Alignment {
+        let status = self.align(pattern, text);
+        let completed = status == AlignmentStatus::Completed;
+        Alignment {
+            status,
+            score: self.score,
+            cigar: self.cigar.clone(),
+            pattern_end: if completed { pattern.len() } else { 0 },
+            text_end: if completed { text.len() } else { 0 },
+            elapsed: None,
+        }
+    }
+
+    fn source(wavefronts: &[Option<Wavefront>], score: i32) -> Option<&Wavefront> {
+        if score < 0 {
+            None
+        } else {
+            wavefronts[score as usize].as_ref()
+        }
+    }
+
+    // Best offsets reaching diagonal `k` at `score` through each operation,
+    // before matches are extended; NONE where unreachable
+    fn sources(
+        &self,
+        wavefronts: &[Option<Wavefront>],
+        score: i32,
+        k: i32,
+        n: i32,
+        m: i32,
+    ) -> [i32; 3] {
+        let p = &self.penalties;
+        let x = Self::source(wavefronts, score - p.mismatch);
+        let open = Self::source(wavefronts, score - p.gap_opening - p.gap_extension);
+        let extend = Self::source(wavefronts, score - p.gap_extension);
+        let valid = |h: i32| {
+            if h >= 0 && h <= m && h - k >= 0 && h - k <= n {
+                h
+            } else {
+                NONE
+            }
+        };
+
+        let mismatch = x.map_or(NONE, |w| w.m(k) + 1);
+        // Pattern-only step from diagonal k + 1, text offset unchanged
+        let insertion = open
+            .map_or(NONE, |w| w.m(k + 1))
+            .max(extend.map_or(NONE, |w| w.i(k + 1)));
+        // Text-only step from diagonal k - 1
+        let deletion = open
+            .map_or(NONE, |w| w.m(k - 1))
+            .max(extend.map_or(NONE, |w| w.d(k - 1)))
+            + 1;
+        [valid(mismatch), valid(insertion), valid(deletion)]
+    }
+
+    fn next_wavefront(
+        &self,
+        wavefronts: &[Option<Wavefront>],
+        score: i32,
+        n: i32,
+        m: i32,
+    ) -> Option<Wavefront> {
+        let p = &self.penalties;
+        let inputs = [
+            score - p.mismatch,
+            score - p.gap_opening - p.gap_extension,
+            score - p.gap_extension,
+        ];
+        let (mut lo, mut hi) = (i32::MAX, i32::MIN);
+        for input in inputs {
+            if let Some(w) = Self::source(wavefronts, input) {
+                lo = lo.min(w.lo - 1);
+                hi = hi.max(w.hi + 1);
+            }
+        }
+        if lo > hi {
+            return None;
+        }
+        // Diagonals outside the DP matrix are never reachable
+        let (lo, hi) = (lo.max(-n), hi.min(m));
+
+        let len = (hi - lo + 1) as usize;
+        let mut wavefront = Wavefront {
+            lo,
+            hi,
+            m: vec![NONE; len],
+            i: vec![NONE; len],
+            d: vec![NONE; len],
+        };
+        for k in lo..=hi {
+            let [mismatch, insertion, deletion] = self.sources(wavefronts, score, k, n, m);
+            let cell = (k - lo) as usize;
+            wavefront.i[cell] = insertion;
+            wavefront.d[cell] = deletion;
+            wavefront.m[cell] = mismatch.max(insertion).max(deletion);
+        }
+        Some(wavefront)
+    }
+
+    fn traceback(
+        &self,
+        wavefronts: &[Option<Wavefront>],
+        mut score: i32,
+        mut k: i32,
+        mut h: i32,
+        n: i32,
+    ) -> Vec<u8> {
+        let p = self.penalties;
+        let m = h;
+        let mut ops = Vec::new();
+        let mut matrix = Matrix::M;
+        loop {
+            match matrix {
+                Matrix::M => {
+                    if score == 0 {
+                        // Only the initial matches on diagonal 0 remain
+                        ops.extend(std::iter::repeat(b'M').take(h as usize));
+                        break;
+                    }
+                    let [mismatch, insertion, deletion] = self.sources(wavefronts, score, k, n, m);
+                    let base = mismatch.max(insertion).max(deletion);
+                    ops.extend(std::iter::repeat(b'M').take((h - base) as usize));
+                    h = base;
+                    if base == mismatch {
+                        ops.push(b'X');
+                        score -= p.mismatch;
+                        h -= 1;
+                    } else if base == deletion {
+                        matrix = Matrix::D;
+                    } else {
+                        matrix = Matrix::I;
+                    }
+                }
+                Matrix::I => {
+                    ops.push(b'I');
+                    let open = Self::source(wavefronts, score - p.gap_opening - p.gap_extension);
+                    k += 1;
+                    if open.is_some_and(|w| w.m(k) == h) {
+                        score -= p.gap_opening + p.gap_extension;
+                        matrix = Matrix::M;
+                    } else {
+                        score -= p.gap_extension;
+                    }
+                }
+                Matrix::D => {
+                    ops.push(b'D');
+                    let open = Self::source(wavefronts, score - p.gap_opening - p.gap_extension);
+                    k -= 1;
+                    h -= 1;
+                    if open.is_some_and(|w| w.m(k) == h) {
+                        score -= p.gap_opening + p.gap_extension;
+                        matrix = Matrix::M;
+                    } else {
+                        score -= p.gap_extension;
+                    }
+                }
+            }
+        }
+        ops.reverse();
+        ops
+    }
+}
+
+// Follow matches along every diagonal of the M wavefront
+fn extend(wavefront: &mut Wavefront, pattern: &[u8], text: &[u8]) {
+    for (cell, k) in (wavefront.lo..=wavefront.hi).enumerate() {
+        let mut h = wavefront.m[cell];
+        if h < 0 {
+            continue;
+        }
+        let mut v = h - k;
+        while (v as usize) < pattern.len()
+            && (h as usize) < text.len()
+            && pattern[v as usize] == text[h as usize]
+        {
+            h += 1;
+            v += 1;
+        }
+        wavefront.m[cell] = h;
+    }
+}
@@ -0,0 +1,168 @@
+//! Consensus quality (QV) estimation of an assembly from read-to-assembly
+//! alignments.
+
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QvOptions {
+    /// Ignore indels inside homopolymer runs, the dominant error mode of some
+    /// sequencing technologies that would otherwise be blamed on the assembly.
+    pub exclude_homopolymer_indels: bool,
+    /// Shortest run of one base treated as a homopolymer.
+    pub min_homopolymer_len: usize,
+}
+
+impl Default for QvOptions {
+    fn default() -> Self {
+        Self {
+            exclude_homopolymer_indels: false,
+            min_homopolymer_len: 3,
+        }
+    }
+}
+
+/// Error counts aggregated over alignments. Indels are counted per base.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ErrorCounts {
+    /// Assembly bases covered by alignments.
+    pub aligned_bases: u64,
+    pub mismatches: u64,
+    pub insertions: u64,
+    pub deletions: u64,
+    /// Homopolymer indel bases left out of the error count.
+    pub excluded_indels: u64,
+}
+
+impl ErrorCounts {
+    pub fn errors(&self) -> u64 {
+        self.mismatches + self.insertions + self.deletions
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        if self.aligned_bases == 0 {
+            return 0.0;
+        }
+        self.errors() as f64 / self.aligned_bases as f64
+    }
+
+    /// Phred-scaled consensus quality, `-10 * log10(error_rate)`. With no
+    /// observed errors the value is capped by the amount of evidence
+    /// (one error in `aligned_bases + 1`).
+    pub fn qv(&self) -> f64 {
+        let floor = 1.0 / (self.aligned_bases as f64 + 1.0);
+        -10.0 * self.error_rate().max(floor).log10()
+    }
+
+    fn add(&mut self, other: &ErrorCounts) {
+        self.aligned_bases += other.aligned_bases;
+        self.mismatches += other.mismatches;
+        self.insertions += other.insertions;
+        self.deletions += other.deletions;
+        self.excluded_indels += other.excluded_indels;
+    }
+}
+
+/// Accumulates read-to-assembly alignments and reports QV per contig and
+/// overall.
+#[derive(Debug, Clone, Default)]
+pub struct QvEstimator {
+    options: QvOptions,
+    contigs: BTreeMap<String, ErrorCounts>,
+}
+
+impl QvEstimator {
+    pub fn new(options: QvOptions) -> Self {
+        Self {
+            options,
+            contigs: BTreeMap::new(),
+        }
+    }
+
+    /// Add one alignment of a read (pattern) to `contig` (text). `target` is
+    /// the part of the contig covered by the alignment, starting where the
+    /// CIGAR starts; it is only used to recognize homopolymers.
+    pub fn add_alignment(&mut self, contig: &str, target: &[u8], cigar: &[u8]) {
+        let mut counts = ErrorCounts::default();
+        let mut target_pos = 0;
+        for &op in cigar {
+            match op {
+                b'M' | b'=' => {
+                    counts.aligned_bases += 1;
+                    target_pos += 1;
+                }
+                b'X' => {
+                    counts.aligned_bases += 1;
+                    counts.mismatches += 1;
+                    target_pos += 1;
+                }
+                b'I' => {
+                    if self.in_homopolymer(target, target_pos, false) {
+                        counts.excluded_indels += 1;
+                    } else {
+                        counts.insertions += 1;
+                    }
+                }
+                b'D' => {
+                    if self.in_homopolymer(target, target_pos, true) {
+                        counts.excluded_indels += 1;
+                    } else {
+                        counts.deletions += 1;
+                    }
+                    counts.aligned_bases += 1;
+                    target_pos += 1;
+                }
+                _ => {}
+            }
+        }
+        self.contigs
+            .entry(contig.to_string())
+            .or_default()
+            .add(&counts);
+    }
+
+    // Whether an indel at `pos` touches a homopolymer run in the target. A
+    // deletion removes target[pos]; an insertion sits between pos - 1 and pos.
+    fn in_homopolymer(&self, target: &[u8], pos: usize, deletion: bool) -> bool {
+        if !self.options.exclude_homopolymer_indels {
+            return false;
+        }
+        let run_len = |at: usize| {
+            let Some(&base) = target.get(at) else {
+                return 0;
+            };
+            let before = target[..at]
+                .iter()
+                .rev()
+                .take_while(|&&b| b == base)
+                .count();
+            let after = target[at..].iter().take_while(|&&b| b == base).count();
+            before + after
+        };
+        let run = if deletion {
+            run_len(pos)
+        } else {
+            run_len(pos).max(pos.checked_sub(1).map_or(0, run_len))
+        };
+        run >= self.options.min_homopolymer_len
+    }
+
+    pub fn contig(&self, name: &str) -> Option<&ErrorCounts> {
+        self.contigs.get(name)
+    }
+
+    /// Per-contig counts, ordered by contig name.
+    pub fn contigs(&self) -> impl Iterator<Item = (&str, &ErrorCounts)> {
+        self.contigs
+            .iter()
+            .map(|(name, counts)| (name.as_str(), counts))
+    }
+
+    /// Counts aggregated over all contigs.
+    pub fn overall(&self) -> ErrorCounts {
+        let mut total = ErrorCounts::default();
+        for counts in self.contigs.values() {
+            total.add(counts);
+        }
+        total
+    }
+}
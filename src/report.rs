@@ -0,0 +1,207 @@
+//! Standalone HTML reports for visual QC of alignments, e.g. a sample of
+//! pairs from a batch run. Each alignment gets an identity track, markers
+//! for insertions and deletions, and a sequence panel with a zoom control.
+//! The page has no external dependencies.
+
+use crate::affine_wavefront::Alignment;
+use crate::cigar::{cigar_runs, resolve_matches, CigarOp, CigarStats};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+/// One alignment to show in a report, with the sequences it was computed
+/// for.
+#[derive(Debug, Clone, Copy)]
+pub struct ReportEntry<'a> {
+    pub name: &'a str,
+    pub query: &'a [u8],
+    pub target: &'a [u8],
+    pub alignment: &'a Alignment,
+}
+
+/// Layout of the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportOptions {
+    /// Columns per window of the identity track.
+    pub window: usize,
+    /// Columns per row of the sequence panel.
+    pub row_width: usize,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            window: 100,
+            row_width: 100,
+        }
+    }
+}
+
+// Drawing area of the tracks, in SVG user units
+const TRACK_WIDTH: f64 = 1000.0;
+const TRACK_HEIGHT: f64 = 60.0;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+section{border-top:1px solid #ccc;padding:1em 0}\
+svg{display:block;width:100%;max-width:1000px;background:#fafafa}\
+.panel{overflow:auto;max-height:30em;border:1px solid #ddd;padding:.5em;font-size:12px}\
+.panel pre{margin:0 0 1em 0;font-size:inherit}\
+.x{background:#f99}.g{background:#fd6}";
+
+// The zoom slider scales the sequence panel of its section, in percent
+const SCRIPT: &str = "function zoom(input){\
+input.closest('section').querySelector('.panel').style.fontSize=0.12*input.value+'px';}";
+
+/// Write a self-contained HTML page titled `title` showing every entry.
+/// Fails with `InvalidInput` if a CIGAR does not match its sequences.
+pub fn write_html_report<W: Write>(
+    mut out: W,
+    title: &str,
+    entries: &[ReportEntry],
+    options: &ReportOptions,
+) -> io::Result<()> {
+    let mut sections = String::new();
+    for entry in entries {
+        let section = render_entry(entry, options)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        sections.push_str(&section);
+    }
+    let title = escape(title);
+    write!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>{STYLE}</style>\n<script>{SCRIPT}</script>\n\
+         </head>\n<body>\n<h1>{title}</h1>\n{sections}</body>\n</html>\n"
+    )
+}
+
+fn render_entry(entry: &ReportEntry, options: &ReportOptions) -> Result<String, String> {
+    let ops = resolve_matches(entry.query, entry.target, &entry.alignment.cigar)?;
+    let stats = CigarStats::from_cigar(&ops);
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<section>\n<h2>{}</h2>\n<p>{:?}, score {}, {} columns, \
+         gap-compressed identity {:.4}, {} insertions, {} deletions</p>\n",
+        escape(entry.name),
+        entry.alignment.status,
+        entry.alignment.score,
+        ops.len(),
+        stats.gap_compressed_identity(),
+        stats.insertion_events,
+        stats.deletion_events
+    );
+    html.push_str(&identity_track(&ops, options.window.max(1)));
+    html.push_str(&indel_track(&ops));
+    html.push_str(
+        "<label>Zoom <input type=\"range\" min=\"25\" max=\"200\" value=\"100\" \
+         oninput=\"zoom(this)\"></label>\n<div class=\"panel\">\n",
+    );
+    html.push_str(&sequence_panel(
+        entry.query,
+        entry.target,
+        &ops,
+        options.row_width.max(1),
+    ));
+    html.push_str("</div>\n</section>\n");
+    Ok(html)
+}
+
+// Horizontal position of alignment column `column`
+fn column_x(column: usize, columns: usize) -> f64 {
+    column as f64 * TRACK_WIDTH / columns.max(1) as f64
+}
+
+// Identity (matches over columns) per window, drawn as a polyline
+fn identity_track(ops: &[u8], window: usize) -> String {
+    let mut points = String::new();
+    for (i, chunk) in ops.chunks(window).enumerate() {
+        let matches = chunk.iter().filter(|&&op| op == b'=').count();
+        let identity = matches as f64 / chunk.len() as f64;
+        let center = i * window + chunk.len() / 2;
+        let _ = write!(
+            points,
+            "{:.1},{:.1} ",
+            column_x(center, ops.len()),
+            (1.0 - identity) * TRACK_HEIGHT
+        );
+    }
+    format!(
+        "<svg viewBox=\"0 0 {TRACK_WIDTH} {TRACK_HEIGHT}\" preserveAspectRatio=\"none\">\
+         <title>Identity per {window} columns</title>\
+         <polyline fill=\"none\" stroke=\"#36c\" points=\"{}\"/></svg>\n",
+        points.trim_end()
+    )
+}
+
+// One tick per insertion (top half) or deletion (bottom half)
+fn indel_track(ops: &[u8]) -> String {
+    let mut ticks = String::new();
+    let mut column = 0;
+    for (op, len) in cigar_runs(ops) {
+        let len = len as usize;
+        let kind = match op {
+            CigarOp::Insertion => Some((0.0, "#c33", "insertion")),
+            CigarOp::Deletion => Some((TRACK_HEIGHT / 2.0, "#e90", "deletion")),
+            _ => None,
+        };
+        if let Some((y, color, kind)) = kind {
+            let _ = write!(
+                ticks,
+                "<rect x=\"{:.1}\" y=\"{y}\" width=\"{:.1}\" height=\"{}\" fill=\"{color}\">\
+                 <title>{len} bp {kind} at column {column}</title></rect>",
+                column_x(column, ops.len()),
+                column_x(len, ops.len()).max(1.0),
+                TRACK_HEIGHT / 2.0
+            );
+        }
+        column += len;
+    }
+    format!(
+        "<svg viewBox=\"0 0 {TRACK_WIDTH} {TRACK_HEIGHT}\" preserveAspectRatio=\"none\">\
+         {ticks}</svg>\n"
+    )
+}
+
+// Query and target rows with mismatches and gaps highlighted
+fn sequence_panel(query: &[u8], target: &[u8], ops: &[u8], row_width: usize) -> String {
+    let (mut top, mut bottom) = (Vec::new(), Vec::new());
+    let (mut q, mut t) = (0, 0);
+    for &op in ops {
+        top.push(if op == b'D' { b'-' } else { query[q] });
+        bottom.push(if op == b'I' { b'-' } else { target[t] });
+        q += usize::from(op != b'D');
+        t += usize::from(op != b'I');
+    }
+    let row = |line: &[u8], ops: &[u8]| {
+        let mut html = String::new();
+        for (&c, &op) in line.iter().zip(ops) {
+            let c = escape(&(c as char).to_string());
+            match op {
+                b'X' => html.push_str(&format!("<span class=\"x\">{c}</span>")),
+                b'I' | b'D' => html.push_str(&format!("<span class=\"g\">{c}</span>")),
+                _ => html.push_str(&c),
+            }
+        }
+        html
+    };
+    let mut panel = String::new();
+    for start in (0..ops.len()).step_by(row_width) {
+        let columns = start..(start + row_width).min(ops.len());
+        let _ = writeln!(
+            panel,
+            "<pre>{:>10} {}\n{:>10} {}</pre>",
+            start,
+            row(&top[columns.clone()], &ops[columns.clone()]),
+            "",
+            row(&bottom[columns.clone()], &ops[columns])
+        );
+    }
+    panel
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,63 @@
+//! Conversions to rust-bio's alignment types (feature `rust-bio`), so code
+//! built on `bio::alignment::Alignment` can use WFA2 as its engine. rust-bio's
+//! `x` is the query (pattern) and `y` the target (text).
+
+use crate::affine_wavefront::Alignment;
+use crate::cigar::{cigar_runs, CigarOp};
+use bio_types::alignment::{Alignment as BioAlignment, AlignmentMode, AlignmentOperation};
+
+impl From<CigarOp> for AlignmentOperation {
+    fn from(op: CigarOp) -> Self {
+        match op {
+            // WFA2 reports mismatches separately, so `M` is a match
+            CigarOp::Match | CigarOp::Equal => AlignmentOperation::Match,
+            CigarOp::Mismatch => AlignmentOperation::Subst,
+            CigarOp::Insertion => AlignmentOperation::Ins,
+            CigarOp::Deletion => AlignmentOperation::Del,
+        }
+    }
+}
+
+/// rust-bio operations for a compact CIGAR, one per alignment column.
+pub fn to_bio_operations(cigar: &[u8]) -> Vec<AlignmentOperation> {
+    cigar_runs(cigar)
+        .flat_map(|(op, len)| std::iter::repeat(AlignmentOperation::from(op)).take(len as usize))
+        .collect()
+}
+
+impl Alignment {
+    /// rust-bio alignment of a query of `query_len` bases against a target of
+    /// `target_len` bases. An alignment covering both sequences is `Global`;
+    /// otherwise the uncovered ends become trailing `Xclip`/`Yclip`
+    /// operations in `Custom` mode.
+    pub fn to_bio_alignment(&self, query_len: usize, target_len: usize) -> BioAlignment {
+        let mut operations = to_bio_operations(&self.cigar);
+        let xend = self.cigar.iter().filter(|&&op| op != b'D').count();
+        let yend = self.cigar.iter().filter(|&&op| op != b'I').count();
+        let (x_clip, y_clip) = (
+            query_len.saturating_sub(xend),
+            target_len.saturating_sub(yend),
+        );
+        if x_clip > 0 {
+            operations.push(AlignmentOperation::Xclip(x_clip));
+        }
+        if y_clip > 0 {
+            operations.push(AlignmentOperation::Yclip(y_clip));
+        }
+        BioAlignment {
+            score: self.score,
+            xstart: 0,
+            ystart: 0,
+            xend,
+            yend,
+            xlen: query_len,
+            ylen: target_len,
+            operations,
+            mode: if x_clip == 0 && y_clip == 0 {
+                AlignmentMode::Global
+            } else {
+                AlignmentMode::Custom
+            },
+        }
+    }
+}
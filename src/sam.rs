@@ -0,0 +1,67 @@
+//! SAM output for single alignments.
+
+use crate::affine_wavefront::Alignment;
+use crate::cigar::{sam_cigar, strip_gap_ends, CigarStats};
+
+// Placement of an alignment in a SAM record: query bases soft-clipped at both
+// ends, target bases skipped before the first aligned column, and the CIGAR
+// of the aligned part.
+pub(crate) struct SamLayout<'a> {
+    pub clip_start: usize,
+    pub skipped: usize,
+    pub body: &'a [u8],
+    pub clip_end: usize,
+}
+
+impl Alignment {
+    pub(crate) fn sam_layout(&self, query_len: usize) -> SamLayout<'_> {
+        let (clip_start, skipped, body) = strip_gap_ends(&self.cigar);
+        let aligned_query = body.iter().filter(|&&op| op != b'D').count();
+        SamLayout {
+            clip_start,
+            skipped,
+            body,
+            clip_end: query_len.saturating_sub(clip_start + aligned_query),
+        }
+    }
+
+    /// Complete SAM line (without trailing newline) for this alignment of
+    /// `query` (named `query_name`) against the target region that starts at
+    /// 0-based position `target_offset` of reference `target_name`.
+    ///
+    /// Query ends left unaligned (leading or trailing insertions, as produced
+    /// by ends-free alignment, or bases past the end of the CIGAR) become soft
+    /// clips, and leading deletions move `POS`. The CIGAR uses `M` for aligned
+    /// columns; `AS:i` and `NM:i` carry the score and the edit distance. MAPQ
+    /// is not known for a single alignment and is written as 255. An alignment
+    /// that did not complete is written as an unmapped record.
+    pub fn to_sam_record(
+        &self,
+        query_name: &str,
+        query: &[u8],
+        target_name: &str,
+        target_offset: usize,
+    ) -> String {
+        let seq = String::from_utf8_lossy(query);
+        if !self.status.is_complete() {
+            return format!("{query_name}\t4\t*\t0\t0\t*\t*\t0\t0\t{seq}\t*");
+        }
+
+        let layout = self.sam_layout(query.len());
+        let mut cigar = String::new();
+        if layout.clip_start > 0 {
+            cigar.push_str(&format!("{}S", layout.clip_start));
+        }
+        cigar.push_str(&sam_cigar(layout.body, true));
+        if layout.clip_end > 0 {
+            cigar.push_str(&format!("{}S", layout.clip_end));
+        }
+        let stats = CigarStats::from_cigar(layout.body);
+        format!(
+            "{query_name}\t0\t{target_name}\t{}\t255\t{cigar}\t*\t0\t0\t{seq}\t*\tAS:i:{}\tNM:i:{}",
+            target_offset + layout.skipped + 1,
+            self.score,
+            stats.mismatches + stats.insertions + stats.deletions
+        )
+    }
+}
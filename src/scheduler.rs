@@ -0,0 +1,145 @@
+use crate::affine_wavefront::MemoryMode;
+use std::sync::{Condvar, Mutex};
+
+// Offsets are stored as 32-bit integers in up to five wavefront components
+// (M, I1, D1, I2, D2).
+const BYTES_PER_DIAGONAL: u64 = 5 * 4;
+// Score steps per difference for typical penalties (e.g. mismatch 4).
+const SCORE_STEPS_PER_DIFFERENCE: u64 = 4;
+// Wavefronts kept alive by bi-WFA at any time.
+const ULTRALOW_RESIDENT_WAVEFRONTS: u64 = 16;
+
+/// Rough upper estimate of the memory needed to align sequences of the given
+/// lengths, assuming up to `divergence` (0.0 - 1.0) differences per base.
+///
+/// The model counts wavefront offsets: `d` differences give wavefronts about
+/// `2d + 1` diagonals wide over roughly `4d` score steps. High memory mode keeps
+/// every wavefront, medium and low compact the backtrace, and ultralow (bi-WFA)
+/// only keeps a handful of wavefronts alive. Sequence buffers are included.
+pub fn estimate_alignment_memory(
+    pattern_len: usize,
+    text_len: usize,
+    memory_mode: &MemoryMode,
+    divergence: f64,
+) -> u64 {
+    let (n, m) = (pattern_len as u64, text_len as u64);
+    let longest = n.max(m);
+    let differences = (longest as f64 * divergence.clamp(0.0, 1.0)).ceil() as u64 + n.abs_diff(m);
+    let width = 2 * differences + 1;
+    let steps = SCORE_STEPS_PER_DIFFERENCE * differences + 1;
+    let all_wavefronts = steps
+        .saturating_mul(width)
+        .saturating_mul(BYTES_PER_DIAGONAL);
+
+    let wavefronts = match memory_mode {
+        MemoryMode::High | MemoryMode::Undefined => all_wavefronts,
+        MemoryMode::Medium => all_wavefronts / 4,
+        MemoryMode::Low => all_wavefronts / 16,
+        MemoryMode::Ultralow => ULTRALOW_RESIDENT_WAVEFRONTS * width * BYTES_PER_DIAGONAL,
+    };
+    wavefronts.saturating_add(n + m)
+}
+
+/// Global memory budget shared by concurrent alignments, e.g. through
+/// [`BatchOptions`](crate::batch::BatchOptions).
+///
+/// Workers reserve the estimated memory of an alignment before running it and
+/// block while the reservation would exceed the budget, so several large
+/// alignments coinciding cannot push the process past its memory limit.
+/// A single reservation larger than the whole budget is admitted once nothing
+/// else is in flight, so oversized work is serialized rather than deadlocked.
+#[derive(Debug)]
+pub struct MemoryBudget {
+    limit: u64,
+    in_use: Mutex<u64>,
+    released: Condvar,
+}
+
+impl MemoryBudget {
+    pub fn new(limit_bytes: u64) -> Self {
+        Self {
+            limit: limit_bytes,
+            in_use: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Bytes currently reserved by in-flight alignments.
+    pub fn in_use(&self) -> u64 {
+        *self.in_use.lock().unwrap()
+    }
+
+    fn fits(&self, in_use: u64, bytes: u64) -> bool {
+        in_use == 0 || in_use.saturating_add(bytes) <= self.limit
+    }
+
+    /// Reserve `bytes`, waiting until enough of the budget has been released.
+    pub fn acquire(&self, bytes: u64) -> MemoryReservation<'_> {
+        let mut in_use = self.in_use.lock().unwrap();
+        while !self.fits(*in_use, bytes) {
+            in_use = self.released.wait(in_use).unwrap();
+        }
+        *in_use += bytes;
+        MemoryReservation {
+            budget: self,
+            bytes,
+        }
+    }
+
+    /// Reserve `bytes` only if that is possible without waiting.
+    pub fn try_acquire(&self, bytes: u64) -> Option<MemoryReservation<'_>> {
+        let mut in_use = self.in_use.lock().unwrap();
+        if !self.fits(*in_use, bytes) {
+            return None;
+        }
+        *in_use += bytes;
+        Some(MemoryReservation {
+            budget: self,
+            bytes,
+        })
+    }
+
+    /// Reserve the estimated memory of aligning the given lengths.
+    pub fn acquire_for(
+        &self,
+        pattern_len: usize,
+        text_len: usize,
+        memory_mode: &MemoryMode,
+        divergence: f64,
+    ) -> MemoryReservation<'_> {
+        self.acquire(estimate_alignment_memory(
+            pattern_len,
+            text_len,
+            memory_mode,
+            divergence,
+        ))
+    }
+
+    fn release(&self, bytes: u64) {
+        let mut in_use = self.in_use.lock().unwrap();
+        *in_use -= bytes;
+        self.released.notify_all();
+    }
+}
+
+/// Memory reserved from a `MemoryBudget`; released when dropped.
+pub struct MemoryReservation<'a> {
+    budget: &'a MemoryBudget,
+    bytes: u64,
+}
+
+impl MemoryReservation<'_> {
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl Drop for MemoryReservation<'_> {
+    fn drop(&mut self) {
+        self.budget.release(self.bytes);
+    }
+}
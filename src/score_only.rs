@@ -0,0 +1,103 @@
+//! Score-only alignment for filtering workloads such as all-vs-all distance
+//! estimation, where the CIGAR is never looked at.
+
+use crate::affine_wavefront::{
+    AffineWavefronts, AffineWavefrontsBuilder, AlignError, AlignmentScope, BuildError,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Aligner fixed to WFA2's score-only scope at construction. WFA2 then skips
+/// the traceback and keeps no backtrace data, which saves time and memory
+/// compared to a full alignment.
+pub struct ScoreAligner {
+    aligner: AffineWavefronts,
+}
+
+impl ScoreAligner {
+    /// Score-only aligner with the penalties, memory mode, heuristic and span
+    /// of `config`; its alignment scope is overridden. Fails if `config` is
+    /// invalid, see [`AffineWavefrontsBuilder::build`].
+    pub fn new(config: AffineWavefrontsBuilder) -> Result<Self, BuildError> {
+        Ok(Self {
+            aligner: config
+                .alignment_scope(AlignmentScope::ComputeScore)
+                .build()?,
+        })
+    }
+
+    /// Score of the optimal alignment of `a` against `b`, in the convention
+    /// of [`AffineWavefronts::score`].
+    pub fn align_score(&self, a: &[u8], b: &[u8]) -> Result<i32, AlignError> {
+        self.aligner.align(a, b).ok()?;
+        Ok(self.aligner.score())
+    }
+
+    /// Scores of many pairs in input order, reusing this aligner.
+    pub fn scores_for_pairs<'a, I>(&self, pairs: I) -> Vec<Result<i32, AlignError>>
+    where
+        I: IntoIterator<Item = (&'a [u8], &'a [u8])>,
+    {
+        pairs
+            .into_iter()
+            .map(|(a, b)| self.align_score(a, b))
+            .collect()
+    }
+
+    /// Give up on pairs whose score would exceed `max_steps`, which makes
+    /// filtering out distant pairs cheap. See
+    /// [`AffineWavefronts::set_max_alignment_steps`].
+    pub fn set_max_alignment_steps(&mut self, max_steps: i32) {
+        self.aligner.set_max_alignment_steps(max_steps);
+    }
+
+    /// The underlying aligner, e.g. to inspect its configuration.
+    pub fn aligner(&self) -> &AffineWavefronts {
+        &self.aligner
+    }
+}
+
+impl Default for ScoreAligner {
+    fn default() -> Self {
+        Self::new(AffineWavefrontsBuilder::new()).expect("the default configuration is valid")
+    }
+}
+
+/// Scores of `pairs` in input order, computed on `threads` worker threads
+/// with one score-only aligner each (built from `config`, see
+/// [`ScoreAligner::new`]). Pairs are handed out one at a time, so a few
+/// expensive pairs do not hold up a whole share of the input. The aligners
+/// are built before any work starts, so an invalid `config` fails here
+/// rather than on a worker.
+pub fn scores_for_pairs(
+    config: &AffineWavefrontsBuilder,
+    pairs: &[(&[u8], &[u8])],
+    threads: usize,
+) -> Result<Vec<Result<i32, AlignError>>, BuildError> {
+    let threads = threads.clamp(1, pairs.len().max(1));
+    let scorers = (0..threads)
+        .map(|_| ScoreAligner::new(config.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if threads == 1 {
+        return Ok(scorers[0].scores_for_pairs(pairs.iter().copied()));
+    }
+    let next = AtomicUsize::new(0);
+    let scores = Mutex::new(vec![Err(AlignError::Undefined); pairs.len()]);
+    thread::scope(|scope| {
+        for scorer in scorers {
+            let (next, scores) = (&next, &scores);
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&(a, b)) = pairs.get(i) else {
+                    break;
+                };
+                let score = scorer.align_score(a, b);
+                if let Ok(mut scores) = scores.lock() {
+                    scores[i] = score;
+                }
+            });
+        }
+    });
+    Ok(scores.into_inner().unwrap_or_else(|e| e.into_inner()))
+}
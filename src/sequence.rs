@@ -0,0 +1,177 @@
+//! Sequence helpers shared by the higher-level alignment utilities.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+
+/// Reverse complement of a nucleotide sequence. Case is preserved and
+/// non-ACGT symbols (e.g. `N`) are kept as they are.
+pub fn reverse_complement(sequence: &[u8]) -> Vec<u8> {
+    sequence
+        .iter()
+        .rev()
+        .map(|&base| match base {
+            b'A' => b'T',
+            b'C' => b'G',
+            b'G' => b'C',
+            b'T' => b'A',
+            b'a' => b't',
+            b'c' => b'g',
+            b'g' => b'c',
+            b't' => b'a',
+            other => other,
+        })
+        .collect()
+}
+
+/// 2-bit encoded k-mers (k <= 32) with their start positions, skipping any
+/// k-mer that contains a non-ACGT symbol.
+pub fn kmers(sequence: &[u8], k: usize) -> impl Iterator<Item = (usize, u64)> + '_ {
+    assert!((1..=32).contains(&k), "k must be between 1 and 32");
+    let mask = if k == 32 {
+        u64::MAX
+    } else {
+        (1u64 << (2 * k)) - 1
+    };
+    let mut kmer = 0u64;
+    let mut valid = 0usize;
+    sequence.iter().enumerate().filter_map(move |(i, &base)| {
+        let code = match base.to_ascii_uppercase() {
+            b'A' => 0,
+            b'C' => 1,
+            b'G' => 2,
+            b'T' => 3,
+            _ => {
+                valid = 0;
+                return None;
+            }
+        };
+        kmer = ((kmer << 2) | code) & mask;
+        valid += 1;
+        (valid >= k).then(|| (i + 1 - k, kmer))
+    })
+}
+
+/// Read all records of a FASTA file into memory, keyed by the first word of
+/// each header line. Sequence lines are concatenated as they are.
+pub fn read_fasta<R: BufRead>(reader: R) -> io::Result<HashMap<String, Vec<u8>>> {
+    let mut records = HashMap::new();
+    let mut current: Option<(String, Vec<u8>)> = None;
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim_end();
+        if let Some(header) = line.strip_prefix('>') {
+            if let Some((name, sequence)) = current.take() {
+                records.insert(name, sequence);
+            }
+            let name = header.split_whitespace().next().unwrap_or_default();
+            current = Some((name.to_string(), Vec::new()));
+        } else if let Some((_, sequence)) = current.as_mut() {
+            sequence.extend_from_slice(line.as_bytes());
+        } else if !line.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "FASTA sequence line before the first header",
+            ));
+        }
+    }
+    if let Some((name, sequence)) = current {
+        records.insert(name, sequence);
+    }
+    Ok(records)
+}
+
+/// One FASTA or FASTQ record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastxRecord {
+    /// First word of the header line.
+    pub name: String,
+    pub sequence: Vec<u8>,
+    /// Base qualities, for FASTQ records.
+    pub quality: Option<Vec<u8>>,
+}
+
+/// Streaming reader of FASTA and FASTQ records, one record in memory at a
+/// time. The format is taken from each header (`>` or `@`); FASTA sequences
+/// may span several lines, FASTQ records are the usual four lines.
+pub struct FastxReader<R> {
+    lines: io::Lines<R>,
+    // Header of the next FASTA record, read while collecting the last one
+    header: Option<String>,
+}
+
+impl<R: BufRead> FastxReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            header: None,
+        }
+    }
+
+    fn next_line(&mut self) -> io::Result<Option<String>> {
+        self.lines
+            .next()
+            .transpose()
+            .map(|line| line.map(|line| line.trim_end().to_string()))
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<FastxRecord>> {
+        let header = match self.header.take() {
+            Some(header) => header,
+            None => loop {
+                match self.next_line()? {
+                    Some(line) if line.is_empty() => continue,
+                    Some(line) => break line,
+                    None => return Ok(None),
+                }
+            },
+        };
+        let name = |header: &str| {
+            let name = header[1..].split_whitespace().next().unwrap_or_default();
+            name.to_string()
+        };
+        if header.starts_with('>') {
+            let mut sequence = Vec::new();
+            while let Some(line) = self.next_line()? {
+                if line.starts_with('>') {
+                    self.header = Some(line);
+                    break;
+                }
+                sequence.extend_from_slice(line.as_bytes());
+            }
+            Ok(Some(FastxRecord {
+                name: name(&header),
+                sequence,
+                quality: None,
+            }))
+        } else if header.starts_with('@') {
+            let truncated = || invalid_data(format!("Truncated FASTQ record '{header}'"));
+            let sequence = self.next_line()?.ok_or_else(truncated)?;
+            let separator = self.next_line()?.ok_or_else(truncated)?;
+            let quality = self.next_line()?.ok_or_else(truncated)?;
+            if !separator.starts_with('+') || quality.len() != sequence.len() {
+                return Err(invalid_data(format!("Malformed FASTQ record '{header}'")));
+            }
+            Ok(Some(FastxRecord {
+                name: name(&header),
+                sequence: sequence.into_bytes(),
+                quality: Some(quality.into_bytes()),
+            }))
+        } else {
+            Err(invalid_data(format!(
+                "Expected a FASTA or FASTQ header, found '{header}'"
+            )))
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for FastxReader<R> {
+    type Item = io::Result<FastxRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,265 @@
+//! Trimming of low-quality alignment ends.
+
+use crate::affine_wavefront::Distance;
+
+/// Alignment left after trimming. Coordinates are offsets into the aligned
+/// query (pattern) and target (text), relative to where the original CIGAR
+/// started.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimmedCigar {
+    pub cigar: Vec<u8>,
+    pub query_start: usize,
+    pub query_end: usize,
+    pub target_start: usize,
+    pub target_end: usize,
+    /// Query bases covered by the original CIGAR.
+    pub query_len: usize,
+}
+
+impl TrimmedCigar {
+    pub fn is_empty(&self) -> bool {
+        self.cigar.is_empty()
+    }
+
+    /// Query bases clipped from the start.
+    pub fn clipped_start(&self) -> usize {
+        self.query_start
+    }
+
+    /// Query bases clipped from the end.
+    pub fn clipped_end(&self) -> usize {
+        self.query_len - self.query_end
+    }
+
+    /// Compact CIGAR with the trimmed query bases represented as soft-clips
+    /// (`S`), so the CIGAR still spans the whole query.
+    pub fn with_soft_clips(&self) -> Vec<u8> {
+        let mut out = vec![b'S'; self.query_start];
+        out.extend_from_slice(&self.cigar);
+        out.resize(out.len() + (self.query_len - self.query_end), b'S');
+        out
+    }
+}
+
+fn consumes_query(op: u8) -> bool {
+    matches!(op, b'M' | b'=' | b'X' | b'I')
+}
+
+fn consumes_target(op: u8) -> bool {
+    matches!(op, b'M' | b'=' | b'X' | b'D')
+}
+
+fn is_match(op: u8) -> bool {
+    matches!(op, b'M' | b'=')
+}
+
+/// Criteria for [`soft_trim`]. Each end is trimmed back to the first window
+/// of `window` operations that meets every criterion that is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoftTrimOptions {
+    pub window: usize,
+    /// Minimum fraction of matches (0.0 - 1.0).
+    pub min_identity: Option<f64>,
+    /// Minimum mean score per operation under `penalties` (scores are
+    /// negated penalties, so this is `<= 0`; e.g. `-1.0`). Gap costs are
+    /// spread evenly over the bases of the gap.
+    pub min_score_density: Option<(Distance, f64)>,
+    /// Minimum mean Phred base quality of the query bases in the window.
+    /// Requires qualities to be passed to [`soft_trim`].
+    pub min_base_quality: Option<f64>,
+}
+
+impl Default for SoftTrimOptions {
+    fn default() -> Self {
+        Self {
+            window: 20,
+            min_identity: Some(0.8),
+            min_score_density: None,
+            min_base_quality: None,
+        }
+    }
+}
+
+// Per-operation values whose mean over a window must reach `min`
+struct Track {
+    values: Vec<f64>,
+    min: f64,
+}
+
+// First operation from which a window of `window` operations meets every
+// track, skipping to the first match in that window.
+fn trim_point(ops: &[u8], tracks: &[Track], window: usize) -> usize {
+    let len = ops.len();
+    if len == 0 {
+        return 0;
+    }
+    let window = window.clamp(1, len);
+    let sums: Vec<Vec<f64>> = tracks
+        .iter()
+        .map(|track| {
+            let mut sum = vec![0.0];
+            for value in &track.values {
+                sum.push(sum[sum.len() - 1] + value);
+            }
+            sum
+        })
+        .collect();
+    let passes = |start: usize| {
+        tracks
+            .iter()
+            .zip(&sums)
+            .all(|(track, sum)| (sum[start + window] - sum[start]) / window as f64 >= track.min)
+    };
+    for start in 0..=len - window {
+        if passes(start) {
+            return ops[start..]
+                .iter()
+                .position(|&op| is_match(op))
+                .map_or(len, |offset| start + offset);
+        }
+    }
+    len
+}
+
+// Cost of each operation under `penalties`, gaps spread over their bases
+fn operation_scores(cigar: &[u8], penalties: &Distance) -> Vec<f64> {
+    let (mismatch, gap_cost): (f64, Box<dyn Fn(f64) -> f64>) = match *penalties {
+        Distance::Edit => (1.0, Box::new(|len| len)),
+        Distance::GapAffine {
+            mismatch,
+            gap_opening,
+            gap_extension,
+        } => (
+            f64::from(mismatch),
+            Box::new(move |len| f64::from(gap_opening) + f64::from(gap_extension) * len),
+        ),
+        Distance::GapAffine2p {
+            mismatch,
+            gap_opening1,
+            gap_extension1,
+            gap_opening2,
+            gap_extension2,
+        } => (
+            f64::from(mismatch),
+            Box::new(move |len| {
+                let first = f64::from(gap_opening1) + f64::from(gap_extension1) * len;
+                let second = f64::from(gap_opening2) + f64::from(gap_extension2) * len;
+                first.min(second)
+            }),
+        ),
+    };
+    let mut scores = Vec::with_capacity(cigar.len());
+    let mut i = 0;
+    while i < cigar.len() {
+        let op = cigar[i];
+        let run = cigar[i..].iter().take_while(|&&c| c == op).count();
+        let score = match op {
+            b'X' => -mismatch,
+            b'I' | b'D' => -gap_cost(run as f64) / run as f64,
+            _ => 0.0,
+        };
+        scores.resize(scores.len() + run, score);
+        i += run;
+    }
+    scores
+}
+
+fn trimmed(cigar: &[u8], begin: usize, end: usize) -> TrimmedCigar {
+    let query_len = cigar.iter().filter(|&&op| consumes_query(op)).count();
+    let (head, kept) = (&cigar[..begin], &cigar[begin..end.max(begin)]);
+    let query_start = head.iter().filter(|&&op| consumes_query(op)).count();
+    let target_start = head.iter().filter(|&&op| consumes_target(op)).count();
+    TrimmedCigar {
+        cigar: kept.to_vec(),
+        query_start,
+        query_end: query_start + kept.iter().filter(|&&op| consumes_query(op)).count(),
+        target_start,
+        target_end: target_start + kept.iter().filter(|&&op| consumes_target(op)).count(),
+        query_len,
+    }
+}
+
+fn trim_ends(cigar: &[u8], tracks: Vec<Track>, window: usize) -> TrimmedCigar {
+    let begin = trim_point(cigar, &tracks, window);
+    let end = if begin == cigar.len() {
+        begin
+    } else {
+        let reversed: Vec<u8> = cigar.iter().rev().copied().collect();
+        let tracks: Vec<Track> = tracks
+            .into_iter()
+            .map(|track| Track {
+                values: track.values.into_iter().rev().collect(),
+                min: track.min,
+            })
+            .collect();
+        cigar.len() - trim_point(&reversed, &tracks, window)
+    };
+    trimmed(cigar, begin, end)
+}
+
+fn identity_track(cigar: &[u8], min_identity: f64) -> Track {
+    Track {
+        values: cigar
+            .iter()
+            .map(|&op| f64::from(u8::from(is_match(op))))
+            .collect(),
+        min: min_identity,
+    }
+}
+
+/// Trim alignment ends whose identity over a sliding window of `window`
+/// operations falls below `min_identity` (0.0 - 1.0). Each end is cut back
+/// to the first window that reaches the threshold and then to its first
+/// match. If no window qualifies the result is empty.
+pub fn trim_low_identity_ends(cigar: &[u8], min_identity: f64, window: usize) -> TrimmedCigar {
+    trim_ends(cigar, vec![identity_track(cigar, min_identity)], window)
+}
+
+/// Turn poorly supported alignment ends into soft-clips: each end is cut back
+/// to the first window meeting all criteria of `options`, then to its first
+/// match. `qualities` are Phred values (not ASCII-encoded) of the aligned
+/// query bases; a deletion is judged by the quality of the query base that
+/// follows it. Use [`TrimmedCigar::with_soft_clips`] for the clipped CIGAR and
+/// [`TrimmedCigar::clipped_start`]/[`TrimmedCigar::clipped_end`] for the
+/// clipped lengths.
+pub fn soft_trim(
+    cigar: &[u8],
+    qualities: Option<&[u8]>,
+    options: &SoftTrimOptions,
+) -> Result<TrimmedCigar, String> {
+    let mut tracks = Vec::new();
+    if let Some(min_identity) = options.min_identity {
+        tracks.push(identity_track(cigar, min_identity));
+    }
+    if let Some((penalties, min)) = &options.min_score_density {
+        tracks.push(Track {
+            values: operation_scores(cigar, penalties),
+            min: *min,
+        });
+    }
+    if let Some(min_quality) = options.min_base_quality {
+        let qualities =
+            qualities.ok_or_else(|| "Base quality trimming requires qualities".to_string())?;
+        let query_len = cigar.iter().filter(|&&op| consumes_query(op)).count();
+        if qualities.len() != query_len {
+            return Err(format!(
+                "Got {} qualities for {query_len} aligned query bases",
+                qualities.len()
+            ));
+        }
+        let mut query_pos = 0;
+        let values = cigar
+            .iter()
+            .map(|&op| {
+                let at = query_pos.min(query_len.saturating_sub(1));
+                query_pos += usize::from(consumes_query(op));
+                qualities.get(at).map_or(0.0, |&q| f64::from(q))
+            })
+            .collect();
+        tracks.push(Track {
+            values,
+            min: min_quality,
+        });
+    }
+    Ok(trim_ends(cigar, tracks, options.window))
+}
@@ -0,0 +1,226 @@
+//! Alignment results and penalty schemes shared by the WFA2 bindings and the
+//! pure-Rust aligner. Nothing here needs the native library, so these types
+//! are available without the `native` feature; with it they are also
+//! re-exported from [`crate::affine_wavefront`].
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+#[must_use = "the alignment may not have completed"]
+pub enum AlignmentStatus {
+    Completed,
+    Partial,
+    MaxStepsReached,
+    OOM,
+    Unattainable,
+    Undefined,
+}
+
+impl From<std::os::raw::c_int> for AlignmentStatus {
+    fn from(value: std::os::raw::c_int) -> Self {
+        match value {
+            0 => AlignmentStatus::Completed,
+            1 => AlignmentStatus::Partial,
+            -100 => AlignmentStatus::MaxStepsReached,
+            -200 => AlignmentStatus::OOM,
+            -300 => AlignmentStatus::Unattainable,
+            _ => AlignmentStatus::Undefined,
+        }
+    }
+}
+
+impl AlignmentStatus {
+    pub fn is_complete(&self) -> bool {
+        *self == AlignmentStatus::Completed
+    }
+
+    /// `Ok(())` for a completed alignment, the failure as an error otherwise,
+    /// so that checking a status is a one-liner: `aligner.align(a, b).ok()?`.
+    pub fn ok(self) -> Result<(), AlignError> {
+        match self {
+            AlignmentStatus::Completed => Ok(()),
+            AlignmentStatus::Partial => Err(AlignError::Partial),
+            AlignmentStatus::MaxStepsReached => Err(AlignError::MaxStepsReached),
+            AlignmentStatus::OOM => Err(AlignError::OOM),
+            AlignmentStatus::Unattainable => Err(AlignError::Unattainable),
+            AlignmentStatus::Undefined => Err(AlignError::Undefined),
+        }
+    }
+}
+
+/// Failure side of [`AlignmentStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignError {
+    Partial,
+    MaxStepsReached,
+    OOM,
+    Unattainable,
+    Undefined,
+}
+
+impl std::fmt::Display for AlignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            AlignError::Partial => "alignment is only partial",
+            AlignError::MaxStepsReached => "maximum number of alignment steps reached",
+            AlignError::OOM => "alignment ran out of memory",
+            AlignError::Unattainable => "alignment is unattainable",
+            AlignError::Undefined => "alignment ended with an undefined status",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for AlignError {}
+
+impl From<AlignError> for AlignmentStatus {
+    fn from(error: AlignError) -> Self {
+        match error {
+            AlignError::Partial => AlignmentStatus::Partial,
+            AlignError::MaxStepsReached => AlignmentStatus::MaxStepsReached,
+            AlignError::OOM => AlignmentStatus::OOM,
+            AlignError::Unattainable => AlignmentStatus::Unattainable,
+            AlignError::Undefined => AlignmentStatus::Undefined,
+        }
+    }
+}
+
+/// Result of an alignment, copied out of the aligner so that it outlives the
+/// next `align()` call and can be stored or sent between threads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    pub status: AlignmentStatus,
+    pub score: i32,
+    pub cigar: Vec<u8>,
+    /// Where the alignment ends in the pattern (query), exclusive. Equal to the
+    /// pattern length for end-to-end alignments.
+    pub pattern_end: usize,
+    /// Where the alignment ends in the text (target), exclusive.
+    pub text_end: usize,
+    /// Wall-clock duration of the alignment, if the aligner records timing
+    /// (see
+    /// [`AffineWavefronts::set_record_timing`](crate::affine_wavefront::AffineWavefronts::set_record_timing)).
+    pub elapsed: Option<Duration>,
+}
+
+/// Gap-affine penalties, see
+/// [`AffineWavefronts::get_penalties`](crate::affine_wavefront::AffineWavefronts::get_penalties).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AffinePenalties {
+    pub match_score: i32,
+    pub mismatch: i32,
+    pub gap_opening: i32,
+    pub gap_extension: i32,
+}
+
+/// Dual-affine (two-piece) penalties, see
+/// [`AffineWavefronts::get_penalties`](crate::affine_wavefront::AffineWavefronts::get_penalties).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Affine2pPenalties {
+    pub match_score: i32,
+    pub mismatch: i32,
+    pub gap_opening1: i32,
+    pub gap_extension1: i32,
+    pub gap_opening2: i32,
+    pub gap_extension2: i32,
+}
+
+impl AffinePenalties {
+    /// Check the scheme against WFA2's constraints, see [`PenaltyError`].
+    pub fn validate(&self) -> Result<(), PenaltyError> {
+        validate_penalties(
+            self.match_score,
+            self.mismatch,
+            &[(self.gap_opening, self.gap_extension)],
+        )
+    }
+}
+
+impl Affine2pPenalties {
+    /// Check the scheme against WFA2's constraints, see [`PenaltyError`].
+    pub fn validate(&self) -> Result<(), PenaltyError> {
+        validate_penalties(
+            self.match_score,
+            self.mismatch,
+            &[
+                (self.gap_opening1, self.gap_extension1),
+                (self.gap_opening2, self.gap_extension2),
+            ],
+        )
+    }
+}
+
+/// Penalties that WFA2 cannot align with. WFA2 itself aborts the process on
+/// most of these, so they are checked before an aligner is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PenaltyError {
+    /// The match score must be 0 or negative (a bonus).
+    PositiveMatch(i32),
+    /// The mismatch penalty must be positive.
+    NonPositiveMismatch(i32),
+    /// Gap opening penalties must be 0 or positive.
+    NegativeGapOpening(i32),
+    /// Gap extension penalties must be positive.
+    NonPositiveGapExtension(i32),
+    /// A penalty does not fit in an `i32` once the scheme is rewritten with a
+    /// zero match score (see
+    /// [`MatchNormalization`](crate::affine_wavefront::MatchNormalization)).
+    Overflow,
+}
+
+impl std::fmt::Display for PenaltyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PenaltyError::PositiveMatch(m) => {
+                write!(f, "match score must be 0 or negative (a bonus), got {m}")
+            }
+            PenaltyError::NonPositiveMismatch(x) => {
+                write!(f, "mismatch penalty must be positive, got {x}")
+            }
+            PenaltyError::NegativeGapOpening(o) => {
+                write!(f, "gap opening penalty must be 0 or positive, got {o}")
+            }
+            PenaltyError::NonPositiveGapExtension(e) => {
+                write!(f, "gap extension penalty must be positive, got {e}")
+            }
+            PenaltyError::Overflow => f.write_str(
+                "penalties overflow when rewritten with a zero match score; use smaller values",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PenaltyError {}
+
+fn validate_penalties(
+    match_score: i32,
+    mismatch: i32,
+    gaps: &[(i32, i32)],
+) -> Result<(), PenaltyError> {
+    if match_score > 0 {
+        return Err(PenaltyError::PositiveMatch(match_score));
+    }
+    if mismatch <= 0 {
+        return Err(PenaltyError::NonPositiveMismatch(mismatch));
+    }
+    for &(opening, extension) in gaps {
+        if opening < 0 {
+            return Err(PenaltyError::NegativeGapOpening(opening));
+        }
+        if extension <= 0 {
+            return Err(PenaltyError::NonPositiveGapExtension(extension));
+        }
+    }
+    // Largest values after normalization, see MatchNormalization
+    let bonus = i64::from(match_score).abs();
+    let mut normalized = vec![2 * (bonus + i64::from(mismatch))];
+    for &(opening, extension) in gaps {
+        normalized.extend([2 * i64::from(opening), 2 * i64::from(extension) + bonus]);
+    }
+    if match_score != 0 && normalized.iter().any(|&p| p > i64::from(i32::MAX)) {
+        return Err(PenaltyError::Overflow);
+    }
+    Ok(())
+}
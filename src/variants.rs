@@ -0,0 +1,110 @@
+//! Variants implied by an alignment of a query against a reference (the
+//! target), and their output as minimal VCF records.
+
+use crate::cigar::resolve_matches;
+use std::io::{self, Write};
+
+/// One difference between the query and the reference. `pos` is the 0-based
+/// reference position of the first `reference` base. Indels carry the
+/// preceding reference base as anchor, as in VCF (the following base for an
+/// indel at the very start).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub pos: usize,
+    pub reference: Vec<u8>,
+    pub alternate: Vec<u8>,
+}
+
+/// Variants of the alignment of `query` against `reference` given by
+/// `cigar`: one SNV per mismatching base and one record per insertion or
+/// deletion run. Run [`crate::cigar::left_align_indels`] first for normalized
+/// indel positions.
+pub fn extract_variants(
+    query: &[u8],
+    reference: &[u8],
+    cigar: &[u8],
+) -> Result<Vec<Variant>, String> {
+    let resolved = resolve_matches(query, reference, cigar)?;
+    let mut variants = Vec::new();
+    let (mut q, mut t, mut i) = (0, 0, 0);
+    while i < resolved.len() {
+        let op = resolved[i];
+        let run = resolved[i..].iter().take_while(|&&c| c == op).count();
+        match op {
+            b'X' => variants.extend((0..run).map(|k| Variant {
+                pos: t + k,
+                reference: vec![reference[t + k]],
+                alternate: vec![query[q + k]],
+            })),
+            b'I' | b'D' => {
+                let (inserted, deleted) = if op == b'I' {
+                    (&query[q..q + run], &[][..])
+                } else {
+                    (&[][..], &reference[t..t + run])
+                };
+                // Anchor on the base before the indel, or after it at the start
+                let variant = if t > 0 {
+                    Variant {
+                        pos: t - 1,
+                        reference: [&reference[t - 1..t], deleted].concat(),
+                        alternate: [&reference[t - 1..t], inserted].concat(),
+                    }
+                } else {
+                    let after = &reference[deleted.len()..(deleted.len() + 1).min(reference.len())];
+                    Variant {
+                        pos: 0,
+                        reference: [deleted, after].concat(),
+                        alternate: [inserted, after].concat(),
+                    }
+                };
+                variants.push(variant);
+            }
+            _ => {}
+        }
+        if op != b'D' {
+            q += run;
+        }
+        if op != b'I' {
+            t += run;
+        }
+        i += run;
+    }
+    Ok(variants)
+}
+
+/// VCF record for `variant` on reference sequence `chrom`, whose aligned
+/// region starts at `offset` (0-based) in that sequence. With `score`, an
+/// `AS` INFO field carries the alignment score.
+pub fn vcf_record(chrom: &str, offset: usize, variant: &Variant, score: Option<i32>) -> String {
+    let info = score.map_or_else(|| ".".to_string(), |score| format!("AS={score}"));
+    format!(
+        "{chrom}\t{}\t.\t{}\t{}\t.\t.\t{info}",
+        offset + variant.pos + 1,
+        String::from_utf8_lossy(&variant.reference),
+        String::from_utf8_lossy(&variant.alternate)
+    )
+}
+
+/// Write a minimal VCF 4.2 file (header and one record per variant) for
+/// variants on `chrom`. See [`vcf_record`] for `offset` and `score`.
+pub fn write_vcf<W: Write>(
+    mut output: W,
+    chrom: &str,
+    offset: usize,
+    variants: &[Variant],
+    score: Option<i32>,
+) -> io::Result<()> {
+    writeln!(output, "##fileformat=VCFv4.2")?;
+    writeln!(output, "##contig=<ID={chrom}>")?;
+    if score.is_some() {
+        writeln!(
+            output,
+            "##INFO=<ID=AS,Number=1,Type=Integer,Description=\"Alignment score\">"
+        )?;
+    }
+    writeln!(output, "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO")?;
+    for variant in variants {
+        writeln!(output, "{}", vcf_record(chrom, offset, variant, score))?;
+    }
+    Ok(())
+}
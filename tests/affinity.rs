@@ -0,0 +1,21 @@
+use lib_wfa2::affinity::CorePinning;
+
+#[test]
+fn test_no_pinning_is_a_no_op() {
+    assert_eq!(CorePinning::None.apply(3), Ok(()));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_pin_worker_to_core() {
+    // Runs on its own thread, so the test harness threads are unaffected
+    std::thread::spawn(|| CorePinning::Cores(vec![0]).apply(5))
+        .join()
+        .unwrap()
+        .expect("pinning to core 0 should succeed");
+}
+
+#[test]
+fn test_empty_core_list_is_rejected() {
+    assert!(CorePinning::Cores(vec![]).apply(0).is_err());
+}
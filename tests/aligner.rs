@@ -0,0 +1,819 @@
+use lib_wfa2::affine_wavefront::{
+    Affine2pPenalties, AffinePenalties, AffineWavefronts, AffineWavefrontsBuilder, AlignError,
+    Alignment, AlignmentCoordinates, AlignmentScope, AlignmentSpan, AlignmentStatus, BiwfaFallback,
+    BuildError, Distance, DistanceMetric, HeuristicConfig, HeuristicKind, HeuristicStrategy,
+    MemoryMode, Penalties, PenaltyError, PenaltyPreset,
+};
+
+const QUERY: &[u8] = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+#[test]
+fn test_alignment_timing() {
+    let mut aligner = AffineWavefronts::default();
+    let _ = aligner.align(QUERY, REFERENCE);
+    assert_eq!(aligner.last_alignment_duration(), None);
+    assert_eq!(aligner.align_owned(QUERY, REFERENCE).elapsed, None);
+
+    aligner.set_record_timing(true);
+    let _ = aligner.align(QUERY, REFERENCE);
+    assert!(aligner.last_alignment_duration().is_some());
+    let alignment = aligner.align_owned(QUERY, REFERENCE);
+    assert_eq!(alignment.elapsed, aligner.last_alignment_duration());
+    assert!(alignment.elapsed.is_some());
+
+    aligner.set_record_timing(false);
+    assert_eq!(aligner.last_alignment_duration(), None);
+}
+
+#[test]
+fn test_status_ok() {
+    let aligner = AffineWavefronts::default();
+    let status = aligner.align(QUERY, REFERENCE);
+    assert!(status.is_complete());
+    assert_eq!(status.ok(), Ok(()));
+
+    let error = AlignmentStatus::MaxStepsReached.ok().unwrap_err();
+    assert_eq!(error, AlignError::MaxStepsReached);
+    assert_eq!(
+        AlignmentStatus::from(error),
+        AlignmentStatus::MaxStepsReached
+    );
+    let boxed: Box<dyn std::error::Error> = error.into();
+    assert!(boxed.to_string().contains("steps"));
+}
+
+#[test]
+fn test_align_checked() {
+    let mut aligner = AffineWavefronts::default();
+    let alignment = aligner.align_checked(QUERY, REFERENCE).unwrap();
+    assert_eq!(alignment.status, AlignmentStatus::Completed);
+    assert_eq!(alignment.score, aligner.score());
+    assert_eq!(alignment.cigar, aligner.cigar());
+
+    aligner.set_max_alignment_steps(1);
+    assert_eq!(
+        aligner.align_checked(QUERY, REFERENCE),
+        Err(AlignError::MaxStepsReached)
+    );
+}
+
+#[test]
+fn test_owned_alignment_outlives_aligner() {
+    let aligner = AffineWavefronts::default();
+    let first = aligner.align_owned(QUERY, REFERENCE);
+    let second = aligner.align_owned(QUERY, QUERY);
+    drop(aligner);
+
+    assert_eq!(first.status, AlignmentStatus::Completed);
+    assert!(first.score < 0);
+    assert_eq!(
+        (first.pattern_end, first.text_end),
+        (QUERY.len(), REFERENCE.len())
+    );
+    assert_eq!(second.score, 0);
+    assert!(second.cigar.iter().all(|&op| op == b'M'));
+    assert_ne!(first.cigar, second.cigar);
+
+    let handle = std::thread::spawn(move || first.cigar.len());
+    assert!(handle.join().unwrap() >= QUERY.len());
+}
+
+#[test]
+fn test_clone_is_independent() {
+    let mut original = AffineWavefrontsBuilder::new()
+        .penalties(0, 5, 8, 1)
+        .memory_mode(MemoryMode::Medium)
+        .alignment_scope(AlignmentScope::ComputeScore)
+        .build()
+        .unwrap();
+    original.set_max_alignment_steps(1000);
+    let heuristics = original.get_heuristics();
+    let mut copy = original.clone();
+    assert_ne!(copy.aligner(), original.aligner());
+    assert_eq!(copy.get_distance(), original.get_distance());
+    assert_eq!(copy.get_memory_mode(), MemoryMode::Medium);
+    assert_eq!(copy.get_alignment_scope(), AlignmentScope::ComputeScore);
+    assert_eq!(copy.get_max_alignment_steps(), 1000);
+
+    copy.set_heuristic(&HeuristicStrategy::XDrop {
+        xdrop: 50,
+        score_steps: 1,
+    });
+    copy.set_alignment_scope(AlignmentScope::Alignment);
+    assert_eq!(original.get_heuristics(), heuristics);
+    assert_eq!(original.get_alignment_scope(), AlignmentScope::ComputeScore);
+
+    let score = original.align_owned(QUERY, REFERENCE).score;
+    drop(original);
+    assert_eq!(copy.align_owned(QUERY, REFERENCE).score, score);
+}
+
+#[test]
+fn test_to_builder_round_trip() {
+    let span = AlignmentSpan::EndsFree {
+        pattern_begin_free: 2,
+        pattern_end_free: 2,
+        text_begin_free: 3,
+        text_end_free: 3,
+    };
+    let heuristic = HeuristicStrategy::BandedStatic {
+        band_min_k: -10,
+        band_max_k: 10,
+    };
+    let aligner = AffineWavefrontsBuilder::new()
+        .penalties(0, 4, 6, 2)
+        .dual_affine_penalties(24, 1)
+        .memory_mode(MemoryMode::Medium)
+        .heuristic(heuristic.clone())
+        .alignment_span(span.clone())
+        .build()
+        .unwrap();
+
+    let copy = aligner.to_builder().build().unwrap();
+    assert_eq!(copy.get_distance(), aligner.get_distance());
+    assert_eq!(copy.match_normalization(), aligner.match_normalization());
+    assert_eq!(copy.get_memory_mode(), MemoryMode::Medium);
+    assert_eq!(copy.get_heuristics(), vec![heuristic]);
+    assert_eq!(copy.get_alignment_span(), span);
+
+    // Derived variant: same scoring, different memory mode
+    let ultralow = aligner
+        .to_builder()
+        .memory_mode(MemoryMode::Ultralow)
+        .build()
+        .unwrap();
+    assert_eq!(ultralow.get_distance(), aligner.get_distance());
+    assert_eq!(ultralow.get_memory_mode(), MemoryMode::Ultralow);
+
+    // A match bonus survives as well
+    let bonus = AffineWavefrontsBuilder::new()
+        .penalties(-1, 4, 6, 2)
+        .build()
+        .unwrap();
+    assert!(bonus.match_normalization().is_some());
+    assert_eq!(
+        bonus.to_builder().build().unwrap().match_normalization(),
+        bonus.match_normalization()
+    );
+}
+
+#[test]
+fn test_edit_distance() {
+    let aligner = AffineWavefronts::new_edit();
+    assert_eq!(aligner.get_distance_metric(), DistanceMetric::Edit);
+    assert_eq!(aligner.get_distance(), Distance::Edit);
+
+    // One mismatch and one deletion, each costing 1
+    aligner
+        .align(b"ACGTACGTAC", b"ACGAACGTTAC")
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(aligner.score().abs(), 2);
+    assert_eq!(aligner.cigar().iter().filter(|&&op| op != b'M').count(), 2);
+
+    let built = AffineWavefrontsBuilder::new()
+        .distance_metric(DistanceMetric::Edit)
+        .memory_mode(MemoryMode::Medium)
+        .build()
+        .unwrap();
+    assert_eq!(built.get_distance_metric(), DistanceMetric::Edit);
+    assert_eq!(built.get_memory_mode(), MemoryMode::Medium);
+    let copy = built.to_builder().build().unwrap();
+    assert_eq!(copy.get_distance_metric(), DistanceMetric::Edit);
+}
+
+#[test]
+fn test_get_penalties() {
+    // Reported as supplied, although WFA2 runs a normalized zero-match scheme
+    let affine = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    assert_eq!(
+        affine.get_penalties(),
+        Penalties::GapAffine(AffinePenalties {
+            match_score: -1,
+            mismatch: 4,
+            gap_opening: 6,
+            gap_extension: 2,
+        })
+    );
+
+    let dual = AffineWavefronts::new_ultralow();
+    assert_eq!(
+        dual.get_penalties(),
+        Penalties::GapAffine2p(Affine2pPenalties {
+            match_score: 0,
+            mismatch: 4,
+            gap_opening1: 6,
+            gap_extension1: 2,
+            gap_opening2: 12,
+            gap_extension2: 1,
+        })
+    );
+    assert_eq!(dual.clone().get_penalties(), dual.get_penalties());
+
+    assert_eq!(
+        AffineWavefronts::new_edit().get_penalties(),
+        Penalties::Edit
+    );
+}
+
+#[test]
+fn test_penalty_presets() {
+    for preset in PenaltyPreset::ALL {
+        assert_eq!(preset.name().parse::<PenaltyPreset>(), Ok(preset));
+        let aligner = AffineWavefronts::from_preset(preset, MemoryMode::Medium);
+        assert_eq!(
+            aligner.get_penalties(),
+            Penalties::GapAffine2p(preset.penalties())
+        );
+        assert_eq!(aligner.get_memory_mode(), MemoryMode::Medium);
+        aligner
+            .align(QUERY, REFERENCE)
+            .ok()
+            .expect("alignment failed");
+    }
+
+    let ont = PenaltyPreset::MapOnt.penalties();
+    assert_eq!((ont.match_score, ont.mismatch), (-2, 4));
+    assert_eq!((ont.gap_opening2, ont.gap_extension2), (24, 1));
+    assert_eq!(PenaltyPreset::Asm5.to_string(), "asm5");
+    assert!("map-illumina".parse::<PenaltyPreset>().is_err());
+}
+
+#[test]
+fn test_penalty_validation() {
+    let invalid = AffineWavefrontsBuilder::new().penalties(1, 4, 6, 2);
+    assert_eq!(invalid.validate(), Err(PenaltyError::PositiveMatch(1)));
+    assert!(invalid.build().is_err());
+    assert_eq!(
+        AffineWavefrontsBuilder::new()
+            .penalties(0, 0, 6, 2)
+            .build()
+            .err(),
+        Some(BuildError::Penalties(PenaltyError::NonPositiveMismatch(0)))
+    );
+    assert_eq!(
+        AffineWavefrontsBuilder::new()
+            .penalties(0, 4, 6, 2)
+            .dual_affine_penalties(-1, 1)
+            .validate(),
+        Err(PenaltyError::NegativeGapOpening(-1))
+    );
+    assert_eq!(
+        AffinePenalties {
+            match_score: 0,
+            mismatch: 4,
+            gap_opening: 0,
+            gap_extension: 0,
+        }
+        .validate(),
+        Err(PenaltyError::NonPositiveGapExtension(0))
+    );
+    assert_eq!(
+        AffinePenalties {
+            match_score: -1,
+            mismatch: i32::MAX / 2,
+            gap_opening: 6,
+            gap_extension: 2,
+        }
+        .validate(),
+        Err(PenaltyError::Overflow)
+    );
+
+    // Edit distance ignores the penalties
+    assert!(AffineWavefrontsBuilder::new()
+        .penalties(1, 0, -1, 0)
+        .distance_metric(DistanceMetric::Edit)
+        .build()
+        .is_ok());
+    assert!(AffineWavefrontsBuilder::new()
+        .penalties(-1, 4, 0, 2)
+        .build()
+        .is_ok());
+}
+
+#[test]
+#[should_panic(expected = "gap extension penalty must be positive")]
+fn test_invalid_penalties_panic() {
+    AffineWavefronts::with_penalties(0, 4, 6, 0);
+}
+
+#[test]
+fn test_builder_ends_free() {
+    let aligner = AffineWavefrontsBuilder::new()
+        .penalties(0, 4, 6, 2)
+        .memory_mode(MemoryMode::Medium)
+        .ends_free(0, 0, 10, 10)
+        .build()
+        .unwrap();
+    assert_eq!(
+        aligner.get_alignment_span(),
+        AlignmentSpan::EndsFree {
+            pattern_begin_free: 0,
+            pattern_end_free: 0,
+            text_begin_free: 10,
+            text_end_free: 10,
+        }
+    );
+
+    // The pattern aligns inside the text without paying for the flanks
+    aligner
+        .align(b"ACGTTGCA", b"TTTTTACGTTGCATTTTT")
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(aligner.score(), 0);
+    assert_eq!(
+        aligner.to_builder().build().unwrap().get_alignment_span(),
+        aligner.get_alignment_span()
+    );
+}
+
+#[test]
+fn test_align_ends_free_restores_span() {
+    let mut aligner = AffineWavefronts::with_penalties(0, 4, 6, 2);
+    let (pattern, text) = (b"ACGTTGCA", b"TTTTTACGTTGCATTTTT");
+
+    let status = aligner.align_ends_free(pattern, text, 0, 0, 10, 10);
+    assert_eq!(status, AlignmentStatus::Completed);
+    assert_eq!(aligner.score(), 0);
+    assert_eq!(aligner.get_alignment_span(), AlignmentSpan::End2End);
+
+    // Global alignment pays for the flanks again
+    aligner.align(pattern, text).ok().expect("alignment failed");
+    assert!(aligner.score() < 0);
+}
+
+#[test]
+fn test_extend_reports_end_coordinates() {
+    // Shared 40 bp seed extension, then unrelated tails
+    let seed = b"ACGTTGCATGCAAGTCCGATAGGCTTACGATCGGATCCAT";
+    let pattern = [&seed[..], b"CCCCCCCCCCCCCCCCCCCCCCCCCCCCCC"].concat();
+    let text = [&seed[..], b"GGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGG"].concat();
+
+    let mut aligner = AffineWavefronts::with_penalties(0, 4, 6, 2);
+    let extension = aligner.extend(&pattern, &text, 20);
+    assert!(matches!(
+        extension.status,
+        AlignmentStatus::Completed | AlignmentStatus::Partial
+    ));
+    assert!(extension.pattern_end >= seed.len() && extension.pattern_end <= pattern.len());
+    assert!(extension.text_end >= seed.len() && extension.text_end <= text.len());
+    assert!(extension.cigar[..seed.len()].iter().all(|&op| op == b'M'));
+
+    // Span and heuristic are back to global alignment without heuristics
+    assert_eq!(aligner.get_alignment_span(), AlignmentSpan::End2End);
+    assert!(aligner
+        .get_heuristics()
+        .iter()
+        .all(|h| *h == HeuristicStrategy::None));
+}
+
+#[test]
+fn test_alignment_coordinates() {
+    let expected = AlignmentCoordinates {
+        pattern_start: 2,
+        pattern_end: 8,
+        text_start: 0,
+        text_end: 6,
+    };
+    // Free end gaps included in the CIGAR or not
+    let with_gaps = Alignment {
+        status: AlignmentStatus::Completed,
+        score: -4,
+        cigar: b"IIMMMXMMDD".to_vec(),
+        pattern_end: 8,
+        text_end: 8,
+        elapsed: None,
+    };
+    assert_eq!(with_gaps.coordinates(), expected);
+    let without_gaps = Alignment {
+        cigar: b"MMMXMM".to_vec(),
+        text_end: 6,
+        elapsed: None,
+        ..with_gaps
+    };
+    assert_eq!(without_gaps.coordinates(), expected);
+
+    let mut aligner = AffineWavefronts::with_penalties(0, 4, 6, 2);
+    aligner
+        .align_ends_free(b"ACGTTGCA", b"TTTTTACGTTGCATTTTT", 0, 0, 10, 10)
+        .ok()
+        .expect("alignment failed");
+    let coordinates = aligner.alignment_coordinates();
+    assert_eq!((coordinates.pattern_start, coordinates.pattern_end), (0, 8));
+    assert_eq!((coordinates.text_start, coordinates.text_end), (5, 13));
+}
+
+#[test]
+#[should_panic(expected = "ends-free alignment needs a zero match score")]
+fn test_ends_free_with_match_bonus_panics() {
+    // The zero-match rewrite would favour shorter aligned regions
+    let mut aligner = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    let _ = aligner.align_ends_free(b"ACGTTGCA", b"TTTTTACGTTGCATTTTT", 0, 0, 10, 10);
+}
+
+#[test]
+#[should_panic(expected = "ends-free alignment needs a zero match score")]
+fn test_new_semiglobal_with_match_bonus_panics() {
+    AffineWavefronts::new_semiglobal(-1, 4, 6, 2);
+}
+
+#[test]
+fn test_new_semiglobal() {
+    let aligner = AffineWavefronts::new_semiglobal(0, 4, 6, 2);
+    assert!(matches!(
+        aligner.get_alignment_span(),
+        AlignmentSpan::EndsFree {
+            pattern_begin_free: 0,
+            pattern_end_free: 0,
+            ..
+        }
+    ));
+
+    // Read inside a longer reference, with one mismatch
+    let read = b"ACGTTGCAAGTC";
+    let reference = b"GGGGGGGGGGACGTTGCTAGTCTTTTTTTTTTTTTTT";
+    aligner
+        .align(read, reference)
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(aligner.score(), -4);
+    let coordinates = aligner.alignment_coordinates();
+    assert_eq!(
+        (coordinates.pattern_start, coordinates.pattern_end),
+        (0, 12)
+    );
+    assert_eq!((coordinates.text_start, coordinates.text_end), (10, 22));
+}
+
+#[test]
+fn test_max_memory() {
+    // Unrelated pseudo-random sequences: a long, expensive alignment
+    let mut state = 7u64;
+    let mut sequence = |len: usize| -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                b"ACGT"[(state >> 62) as usize]
+            })
+            .collect()
+    };
+    let (pattern, text) = (sequence(5_000), sequence(5_000));
+
+    let unlimited = AffineWavefronts::default();
+    assert_eq!(unlimited.get_max_memory(), u64::MAX);
+
+    let limited = AffineWavefrontsBuilder::new()
+        .max_memory(1 << 10)
+        .build()
+        .unwrap();
+    assert_eq!(limited.get_max_memory(), 1 << 10);
+    assert_eq!(limited.align(&pattern, &text), AlignmentStatus::OOM);
+    assert_eq!(limited.clone().get_max_memory(), 1 << 10);
+}
+
+#[test]
+fn test_reset_releases_buffers() {
+    let pattern = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT".repeat(100);
+    let text = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT".repeat(100);
+    let mut aligner = AffineWavefrontsBuilder::new()
+        .max_memory(1 << 30)
+        .build()
+        .unwrap();
+    let expected = aligner.align_owned(&pattern, &text);
+    let used = aligner.memory_usage();
+    assert!(used > 0);
+
+    aligner.reset();
+    assert!(aligner.memory_usage() <= used);
+    assert_eq!(aligner.get_max_memory(), 1 << 30);
+    assert_eq!(aligner.align_owned(&pattern, &text), expected);
+}
+
+#[test]
+fn test_attributes_snapshot() {
+    let heuristic = HeuristicStrategy::XDrop {
+        xdrop: 50,
+        score_steps: 1,
+    };
+    let aligner = AffineWavefrontsBuilder::new()
+        .penalties(-1, 4, 6, 2)
+        .memory_mode(MemoryMode::Medium)
+        .heuristic(heuristic.clone())
+        .max_alignment_steps(1000)
+        .max_memory(1 << 30)
+        .build()
+        .unwrap();
+    let attributes = aligner.attributes();
+    assert_eq!(attributes.distance_metric, DistanceMetric::GapAffine);
+    assert_eq!(attributes.penalties, aligner.get_penalties());
+    assert_eq!(attributes.distance, aligner.get_distance());
+    assert!(attributes.match_normalization.is_some());
+    assert_eq!(attributes.memory_mode, MemoryMode::Medium);
+    assert_eq!(attributes.heuristics, vec![heuristic]);
+    assert_eq!(attributes.alignment_span, aligner.get_alignment_span());
+    assert_eq!(attributes.system.max_alignment_steps, 1000);
+    assert_eq!(attributes.system.max_memory_abort, 1 << 30);
+    assert!(attributes.system.max_memory_resident <= 1 << 30);
+    assert_eq!(attributes.system.max_num_threads, 1);
+    assert_eq!(attributes.band_divergence, None);
+    assert_eq!(aligner.clone().attributes(), attributes);
+}
+
+#[test]
+fn test_aligner_debug() {
+    let aligner = AffineWavefrontsBuilder::new()
+        .penalties(0, 4, 6, 2)
+        .memory_mode(MemoryMode::Low)
+        .build()
+        .unwrap();
+    let debug = format!("{aligner:?}");
+    assert!(debug.starts_with("AffineWavefronts { attributes: WavefrontAttributes {"));
+    assert!(debug.contains(&format!("{:?}", aligner.attributes())));
+    assert_eq!(aligner.get_penalties().to_string(), "affine(0,4,6,2)");
+    assert_eq!(aligner.get_memory_mode().to_string(), "low");
+}
+
+#[test]
+fn test_builder_max_alignment_steps() {
+    let aligner = AffineWavefrontsBuilder::new()
+        .max_alignment_steps(1)
+        .build()
+        .unwrap();
+    assert_eq!(aligner.get_max_alignment_steps(), 1);
+    assert_eq!(
+        aligner.align(b"ACGTACGTACGT", b"TTTTGGGGCCCC"),
+        AlignmentStatus::MaxStepsReached
+    );
+    assert_eq!(
+        aligner
+            .to_builder()
+            .build()
+            .unwrap()
+            .get_max_alignment_steps(),
+        1
+    );
+}
+
+#[test]
+#[should_panic(expected = "max_alignment_steps must be positive")]
+fn test_builder_max_alignment_steps_positive() {
+    AffineWavefrontsBuilder::new()
+        .max_alignment_steps(0)
+        .build_unchecked();
+}
+
+#[test]
+fn test_build_reports_invalid_settings() {
+    let error = |builder: AffineWavefrontsBuilder| builder.build().err();
+    assert_eq!(
+        error(AffineWavefrontsBuilder::new().penalties(1, 4, 6, 2)),
+        Some(BuildError::Penalties(PenaltyError::PositiveMatch(1)))
+    );
+    assert_eq!(
+        error(AffineWavefrontsBuilder::new().distance_metric(DistanceMetric::Indel)),
+        Some(BuildError::UnsupportedMetric(DistanceMetric::Indel))
+    );
+    assert_eq!(
+        error(
+            AffineWavefrontsBuilder::new()
+                .dual_affine_penalties(12, 1)
+                .distance_metric(DistanceMetric::GapAffine)
+        ),
+        Some(BuildError::DualAffineWithoutAffine2p(
+            DistanceMetric::GapAffine
+        ))
+    );
+    assert_eq!(
+        error(AffineWavefrontsBuilder::new().max_alignment_steps(0)),
+        Some(BuildError::NonPositiveMaxSteps(0))
+    );
+    assert_eq!(
+        error(AffineWavefrontsBuilder::new().banded_for(1.5)),
+        Some(BuildError::InvalidBandDivergence(1.5))
+    );
+    let fallback = BiwfaFallback {
+        max_memory: 1 << 20,
+        memory_mode: MemoryMode::High,
+        divergence: 0.1,
+    };
+    assert_eq!(
+        error(AffineWavefrontsBuilder::new().biwfa_fallback(fallback)),
+        Some(BuildError::FallbackWithoutUltralow(MemoryMode::High))
+    );
+    assert_eq!(
+        error(AffineWavefrontsBuilder::new().memory_mode(MemoryMode::Undefined)),
+        Some(BuildError::UndefinedMemoryMode)
+    );
+    let fallback = BiwfaFallback {
+        max_memory: 1 << 20,
+        memory_mode: MemoryMode::Undefined,
+        divergence: 0.1,
+    };
+    assert_eq!(
+        error(
+            AffineWavefrontsBuilder::new()
+                .memory_mode(MemoryMode::Ultralow)
+                .biwfa_fallback(fallback)
+        ),
+        Some(BuildError::UndefinedMemoryMode)
+    );
+    assert_eq!(
+        error(AffineWavefrontsBuilder::new().alignment_scope(AlignmentScope::Undefined)),
+        Some(BuildError::UndefinedScope)
+    );
+    assert_eq!(
+        error(
+            AffineWavefrontsBuilder::new()
+                .penalties(-1, 4, 6, 2)
+                .ends_free(0, 0, 10, 10)
+        ),
+        Some(BuildError::EndsFreeWithMatchBonus(-1))
+    );
+    if !lib_wfa2::capabilities().openmp {
+        assert!(matches!(
+            error(AffineWavefrontsBuilder::new().num_threads(2)),
+            Some(BuildError::Threads { threads: 2, .. })
+        ));
+    }
+
+    // The unchecked build ignores settings that do not apply
+    let aligner = AffineWavefrontsBuilder::new()
+        .dual_affine_penalties(12, 1)
+        .distance_metric(DistanceMetric::GapAffine)
+        .build_unchecked();
+    assert_eq!(aligner.get_distance_metric(), DistanceMetric::GapAffine);
+}
+
+#[test]
+fn test_builder_heuristics() {
+    let heuristics = [
+        HeuristicStrategy::BandedStatic {
+            band_min_k: -10,
+            band_max_k: 10,
+        },
+        HeuristicStrategy::XDrop {
+            xdrop: 50,
+            score_steps: 1,
+        },
+    ];
+    let mut aligner = AffineWavefrontsBuilder::new()
+        .heuristics(&heuristics)
+        .build()
+        .unwrap();
+    let applied = aligner.get_heuristics();
+    assert_eq!(applied.len(), 2);
+    assert!(heuristics.iter().all(|h| applied.contains(h)));
+    assert_eq!(
+        aligner.to_builder().build().unwrap().get_heuristics(),
+        applied
+    );
+    assert!(aligner.align(QUERY, REFERENCE).is_complete());
+
+    // A single heuristic replaces all previous ones
+    aligner.set_heuristic(&heuristics[1]);
+    assert_eq!(aligner.get_heuristics(), vec![heuristics[1].clone()]);
+    aligner.set_heuristics(&[]);
+    assert!(aligner.get_heuristics().is_empty());
+}
+
+#[test]
+fn test_heuristic_config() {
+    let mut aligner = AffineWavefronts::default();
+    assert!(aligner.get_heuristic_config().enabled.is_empty());
+
+    aligner.set_heuristics(&[
+        HeuristicStrategy::XDrop {
+            xdrop: 50,
+            score_steps: 2,
+        },
+        HeuristicStrategy::BandedStatic {
+            band_min_k: -8,
+            band_max_k: 12,
+        },
+    ]);
+    let config: HeuristicConfig = aligner.get_heuristic_config();
+    assert!(config.is_enabled(HeuristicKind::XDrop));
+    assert!(config.is_enabled(HeuristicKind::BandedStatic));
+    assert!(!config.is_enabled(HeuristicKind::ZDrop));
+    assert_eq!(config.xdrop, 50);
+    assert_eq!(config.score_steps, 2);
+    assert_eq!((config.band_min_k, config.band_max_k), (-8, 12));
+    assert_eq!(aligner.get_heuristics(), config.strategies());
+    assert!(aligner
+        .get_heuristics()
+        .contains(&HeuristicStrategy::XDrop {
+            xdrop: 50,
+            score_steps: 2,
+        }));
+}
+
+#[test]
+fn test_clear_heuristic() {
+    let xdrop = HeuristicStrategy::XDrop {
+        xdrop: 50,
+        score_steps: 1,
+    };
+    let mut aligner = AffineWavefrontsBuilder::new()
+        .heuristics(&[
+            HeuristicStrategy::BandedStatic {
+                band_min_k: -10,
+                band_max_k: 10,
+            },
+            xdrop.clone(),
+        ])
+        .build()
+        .unwrap();
+    aligner.clear_heuristic(HeuristicKind::BandedStatic);
+    assert_eq!(aligner.get_heuristics(), vec![xdrop]);
+
+    // Clearing a disabled strategy changes nothing
+    aligner.clear_heuristic(HeuristicKind::ZDrop);
+    assert_eq!(
+        aligner.get_heuristic_config().enabled,
+        vec![HeuristicKind::XDrop]
+    );
+    aligner.clear_heuristic(HeuristicKind::XDrop);
+    assert!(aligner.get_heuristics().is_empty());
+    assert!(aligner.align(QUERY, REFERENCE).is_complete());
+}
+
+#[test]
+fn test_heuristic_auto_for() {
+    assert_eq!(
+        HeuristicStrategy::auto_for(0.1, 500),
+        HeuristicStrategy::None
+    );
+    assert_eq!(
+        HeuristicStrategy::auto_for(0.0, 100_000),
+        HeuristicStrategy::None
+    );
+    assert_eq!(
+        HeuristicStrategy::auto_for(0.01, 10_000),
+        HeuristicStrategy::WFAdaptive {
+            min_wavefront_length: 10,
+            max_distance_threshold: 50,
+            score_steps: 1,
+        }
+    );
+    assert_eq!(
+        HeuristicStrategy::auto_for(0.2, 1_000_000),
+        HeuristicStrategy::WFAdaptive {
+            min_wavefront_length: 256,
+            max_distance_threshold: 4096,
+            score_steps: 1,
+        }
+    );
+}
+
+#[test]
+fn test_banded_for() {
+    assert_eq!(
+        HeuristicStrategy::banded_for(100, 120, 0.05),
+        HeuristicStrategy::BandedStatic {
+            band_min_k: -6,
+            band_max_k: 26,
+        }
+    );
+    assert_eq!(
+        HeuristicStrategy::banded_for(120, 100, 0.0),
+        HeuristicStrategy::BandedStatic {
+            band_min_k: -20,
+            band_max_k: 0,
+        }
+    );
+
+    let aligner = AffineWavefrontsBuilder::new()
+        .banded_for(0.1)
+        .build()
+        .unwrap();
+    assert_eq!(aligner.get_banded_for(), Some(0.1));
+    let exact = AffineWavefronts::default().align_owned(QUERY, REFERENCE);
+    let banded = aligner.align_owned(QUERY, REFERENCE);
+    assert_eq!(banded.status, AlignmentStatus::Completed);
+    assert_eq!(banded.score, exact.score);
+    // The per-pair band does not stick to the aligner
+    assert!(aligner.get_heuristics().is_empty());
+    assert_eq!(aligner.clone().get_banded_for(), Some(0.1));
+}
+
+#[test]
+fn test_num_threads() {
+    let mut aligner = AffineWavefronts::default();
+    assert_eq!(aligner.get_num_threads(), 1);
+    assert!(aligner.set_num_threads(0).is_err());
+    if lib_wfa2::capabilities().openmp {
+        aligner.set_num_threads(4).unwrap();
+        assert_eq!(aligner.get_num_threads(), 4);
+        assert_eq!(aligner.clone().get_num_threads(), 4);
+        let aligner = AffineWavefrontsBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        assert_eq!(aligner.get_num_threads(), 2);
+        assert!(aligner.align(QUERY, REFERENCE).is_complete());
+    } else {
+        assert!(aligner.set_num_threads(4).is_err());
+        assert_eq!(aligner.get_num_threads(), 1);
+    }
+}
@@ -0,0 +1,82 @@
+use lib_wfa2::affine_wavefront::{
+    AffineWavefronts, AffineWavefrontsBuilder, BiwfaFallback, MemoryMode,
+};
+use lib_wfa2::alphabet::{EncodedSequence, PackedDna};
+
+const QUERY: &[u8] = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+#[test]
+fn test_packed_dna() {
+    let packed = PackedDna::from_ascii(b"ACGTt").unwrap();
+    assert_eq!(packed.len(), 5);
+    assert_eq!(
+        (0..5).map(|i| packed.code(i)).collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 3]
+    );
+    assert!(PackedDna::from_ascii(b"ACNT").is_err());
+}
+
+#[test]
+fn test_align_encoded_matches_ascii() {
+    let aligner = AffineWavefronts::default();
+    assert!(aligner.align(QUERY, REFERENCE).is_complete());
+    let (score, cigar) = (aligner.score(), aligner.cigar().to_vec());
+
+    let query = PackedDna::from_ascii(QUERY).unwrap();
+    let reference = PackedDna::from_ascii(REFERENCE).unwrap();
+    assert!(aligner.align_encoded(&query, &reference).is_complete());
+    assert_eq!(aligner.score(), score);
+    assert_eq!(aligner.cigar(), &cigar[..]);
+
+    // Plain bytes go through the same path
+    assert!(aligner.align_encoded(QUERY, REFERENCE).is_complete());
+    assert_eq!(aligner.score(), score);
+}
+
+#[test]
+fn test_align_encoded_uses_fallback_and_band() {
+    let aligner = AffineWavefrontsBuilder::new()
+        .penalties(0, 4, 6, 2)
+        .memory_mode(MemoryMode::Ultralow)
+        .biwfa_fallback(BiwfaFallback {
+            max_memory: 1 << 20,
+            memory_mode: MemoryMode::High,
+            divergence: 0.1,
+        })
+        .banded_for(0.2)
+        .build()
+        .unwrap();
+    assert!(aligner.align(QUERY, REFERENCE).is_complete());
+    assert!(aligner.last_used_fallback());
+    let (score, cigar) = (aligner.score(), aligner.cigar().to_vec());
+
+    let query = PackedDna::from_ascii(QUERY).unwrap();
+    let reference = PackedDna::from_ascii(REFERENCE).unwrap();
+    assert!(aligner.align_encoded(&query, &reference).is_complete());
+    assert!(aligner.last_used_fallback());
+    assert_eq!(aligner.score(), score);
+    assert_eq!(aligner.cigar(), &cigar[..]);
+}
+
+#[cfg(feature = "bio-seq")]
+#[test]
+fn test_align_bio_seq_matches_ascii() {
+    use bio_seq::prelude::{Dna, Seq};
+
+    let aligner = AffineWavefronts::default();
+    assert!(aligner.align(QUERY, REFERENCE).is_complete());
+    let (score, cigar) = (aligner.score(), aligner.cigar().to_vec());
+
+    let query: Seq<Dna> = std::str::from_utf8(QUERY).unwrap().try_into().unwrap();
+    let reference: Seq<Dna> = std::str::from_utf8(REFERENCE).unwrap().try_into().unwrap();
+    assert!(aligner.align_encoded(&query, &reference).is_complete());
+    assert_eq!(aligner.score(), score);
+    assert_eq!(aligner.cigar(), &cigar[..]);
+
+    // Slices go through the same impl
+    assert!(aligner
+        .align_encoded(&query[..], &reference[..])
+        .is_complete());
+    assert_eq!(aligner.score(), score);
+}
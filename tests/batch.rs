@@ -0,0 +1,212 @@
+use lib_wfa2::affine_wavefront::AffineWavefronts;
+use lib_wfa2::batch::{
+    stream_batch, stream_batch_with_options, BatchOptions, BatchPair, Checkpoint, ResumableBatch,
+};
+use lib_wfa2::scheduler::MemoryBudget;
+use std::fs;
+use std::sync::Arc;
+
+fn pairs() -> Vec<BatchPair> {
+    (0..5)
+        .map(|i| BatchPair {
+            id: format!("pair{i}"),
+            query: b"ACGTACGTACGT".to_vec(),
+            target: b"ACGTTCGTACGT".to_vec(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_resumable_batch_skips_completed_pairs() {
+    let dir = std::env::temp_dir().join(format!("lib_wfa2_batch_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let results = dir.join("results.tsv");
+    let checkpoint = dir.join("checkpoint");
+    let _ = fs::remove_file(&results);
+    let _ = fs::remove_file(&checkpoint);
+
+    let aligner = AffineWavefronts::default();
+    let batch = ResumableBatch::new(&results, &checkpoint).checkpoint_every(2);
+
+    // Simulate a run interrupted after the first three pairs
+    let summary = batch.run(&aligner, pairs().into_iter().take(3)).unwrap();
+    assert_eq!(summary.aligned, 3);
+    assert_eq!(Checkpoint::load(&checkpoint).unwrap().completed.len(), 3);
+
+    let summary = batch.run(&aligner, pairs()).unwrap();
+    assert_eq!(summary.skipped, 3);
+    assert_eq!(summary.aligned, 2);
+    assert_eq!(fs::read_to_string(&results).unwrap().lines().count(), 5);
+
+    // With timing, each line ends in the alignment's duration
+    let mut timed = AffineWavefronts::default();
+    timed.set_record_timing(true);
+    let timed_results = dir.join("timed.tsv");
+    ResumableBatch::new(&timed_results, dir.join("timed_checkpoint"))
+        .run(&timed, pairs())
+        .unwrap();
+    for line in fs::read_to_string(&timed_results).unwrap().lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        assert_eq!(fields.len(), 5);
+        assert!(fields[4].parse::<f64>().is_ok());
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_stream_batch_yields_every_pair() {
+    let mut indices: Vec<usize> = stream_batch(pairs(), 3, AffineWavefronts::default)
+        .map(|(index, result)| {
+            assert!(result.status.is_complete());
+            assert_eq!(result.cigar, b"MMMMXMMMMMMM".to_vec());
+            index
+        })
+        .collect();
+    indices.sort_unstable();
+    assert_eq!(indices, (0..5).collect::<Vec<_>>());
+
+    // Dropping a partially consumed stream stops the workers
+    let mut stream = stream_batch(pairs(), 2, AffineWavefronts::default);
+    assert!(stream.next().is_some());
+    drop(stream);
+}
+
+#[test]
+fn test_stream_batch_records_timing() {
+    let options = BatchOptions {
+        record_timing: true,
+        ..BatchOptions::default()
+    };
+    for (_, result) in stream_batch_with_options(pairs(), 2, options, AffineWavefronts::default) {
+        assert!(result.elapsed.is_some());
+    }
+    for (_, result) in stream_batch(pairs(), 2, AffineWavefronts::default) {
+        assert_eq!(result.elapsed, None);
+    }
+}
+
+#[test]
+fn test_stream_batch_waits_for_memory_budget() {
+    let budget = Arc::new(MemoryBudget::new(1 << 20));
+    let options = BatchOptions {
+        memory_budget: Some(Arc::clone(&budget)),
+        ..BatchOptions::default()
+    };
+    let held = budget.acquire(budget.limit());
+    let mut stream = stream_batch_with_options(pairs(), 2, options, AffineWavefronts::default);
+
+    // Nothing is aligned while the budget is taken
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    assert_eq!(budget.in_use(), budget.limit());
+    drop(held);
+    assert!(stream.next().is_some());
+    assert_eq!(stream.count(), 4);
+    assert_eq!(budget.in_use(), 0);
+}
+
+// Cores the calling thread may run on, as listed by the kernel
+#[cfg(target_os = "linux")]
+fn allowed_cores() -> String {
+    let status = fs::read_to_string("/proc/thread-self/status").unwrap();
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Cpus_allowed_list:"))
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_stream_batch_pins_workers_before_building_aligners() {
+    use lib_wfa2::affinity::CorePinning;
+
+    let options = BatchOptions {
+        pinning: CorePinning::Cores(vec![0]),
+        ..BatchOptions::default()
+    };
+    let stream = stream_batch_with_options(pairs(), 2, options, || {
+        assert_eq!(allowed_cores(), "0");
+        AffineWavefronts::default()
+    });
+    assert_eq!(stream.count(), 5);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_align_pairs_parallel_keeps_input_order() {
+    use lib_wfa2::affine_wavefront::AlignerConfig;
+    use lib_wfa2::batch::{align_pairs_parallel, align_pairs_parallel_with_options};
+
+    let pairs: Vec<(Vec<u8>, Vec<u8>)> = (0..50)
+        .map(|i| {
+            let query = b"ACGTACGTACGT".repeat(i % 5 + 1);
+            let mut target = query.clone();
+            target.truncate(target.len() - i % 3);
+            (query, target)
+        })
+        .collect();
+    let alignments = align_pairs_parallel(&AlignerConfig::default(), &pairs).unwrap();
+    assert_eq!(alignments.len(), pairs.len());
+    let aligner = AffineWavefronts::default();
+    for ((query, target), alignment) in pairs.iter().zip(&alignments) {
+        let expected = aligner.align_owned(query, target);
+        assert_eq!(alignment.score, expected.score);
+        assert_eq!(alignment.cigar, expected.cigar);
+    }
+
+    let invalid = AlignerConfig {
+        mismatch: -1,
+        ..AlignerConfig::default()
+    };
+    assert!(align_pairs_parallel(&invalid, &pairs).is_err());
+
+    // Pinned workers give the same alignments
+    let options = BatchOptions {
+        pinning: lib_wfa2::affinity::CorePinning::Cores(vec![0]),
+        ..BatchOptions::default()
+    };
+    let pinned =
+        align_pairs_parallel_with_options(&AlignerConfig::default(), &pairs, &options).unwrap();
+    assert_eq!(pinned, alignments);
+}
+
+#[test]
+fn test_checkpoint_journal_appends_new_ids() {
+    let dir = std::env::temp_dir().join(format!("lib_wfa2_journal_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("checkpoint");
+    let journal = dir.join("checkpoint.journal");
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(&journal);
+
+    let mut checkpoint = Checkpoint::load(&path).unwrap();
+    let ids = |range: std::ops::Range<usize>| range.map(|i| format!("pair{i}"));
+    checkpoint.commit(&path, ids(0..3), 30).unwrap();
+    checkpoint.commit(&path, ids(3..5), 50).unwrap();
+    // Each id is written once, however many commits follow
+    assert_eq!(
+        fs::read_to_string(&journal).unwrap(),
+        "pair0\npair1\npair2\npair3\npair4\n"
+    );
+    assert_eq!(Checkpoint::load(&path).unwrap(), checkpoint);
+
+    // Ids appended by a commit that crashed before recording them are ignored
+    let mut file = fs::OpenOptions::new().append(true).open(&journal).unwrap();
+    std::io::Write::write_all(&mut file, b"pair5\n").unwrap();
+    let mut loaded = Checkpoint::load(&path).unwrap();
+    assert_eq!(loaded.completed.len(), 5);
+    assert_eq!(loaded.results_offset, 50);
+    loaded.commit(&path, ids(6..7), 60).unwrap();
+    assert!(!Checkpoint::load(&path).unwrap().completed.contains("pair5"));
+
+    // Line breaks would split an id in two
+    let error = loaded
+        .commit(&path, ["bad\nid".to_string()], 70)
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(Checkpoint::load(&path).unwrap().results_offset, 60);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,60 @@
+use lib_wfa2::{build_info, capabilities, wfa2_version};
+
+#[test]
+fn test_build_info() {
+    let info = build_info();
+    assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.wfa2_version, wfa2_version());
+    for field in [
+        info.wfa2_version,
+        info.wfa2_commit,
+        info.bindgen_version,
+        info.target,
+        info.compiler,
+        info.cc_flags,
+    ] {
+        assert!(!field.is_empty());
+    }
+
+    let rendered = info.to_string();
+    assert!(rendered.starts_with(&format!("lib_wfa2 {}\n", info.crate_version)));
+    for field in [
+        info.wfa2_version,
+        info.wfa2_commit,
+        info.bindgen_version,
+        info.target,
+        info.compiler,
+    ] {
+        assert!(rendered.contains(field), "{field} missing from {rendered}");
+    }
+    let openmp = if info.openmp {
+        "openmp yes"
+    } else {
+        "openmp no"
+    };
+    assert!(rendered.contains(openmp));
+    assert!(rendered.ends_with(&format!("march {}", info.march.unwrap_or("portable"))));
+}
+
+#[test]
+fn test_capabilities_match_build_info() {
+    let info = build_info();
+    let capabilities = capabilities();
+    assert_eq!(capabilities.openmp, info.openmp);
+    assert_eq!(capabilities.march, info.march);
+    if !capabilities.openmp {
+        assert_eq!(capabilities.max_threads, 1);
+    }
+    assert!(capabilities.max_threads >= 1);
+    if let Some(march) = info.march {
+        assert!(info.cc_flags.contains(&format!("-march={march}")));
+    }
+
+    // Known for the vendored sources, which include both components
+    if cfg!(feature = "system-wfa2") {
+        assert_eq!((capabilities.biwfa, capabilities.plot), (None, None));
+    } else {
+        assert_eq!(capabilities.biwfa, Some(true));
+        assert_eq!(capabilities.plot, Some(true));
+    }
+}
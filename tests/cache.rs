@@ -0,0 +1,65 @@
+use lib_wfa2::affine_wavefront::{AffineWavefronts, AlignmentStatus, MemoryMode};
+use lib_wfa2::cache::{config_fingerprint, AlignmentCache};
+
+const QUERY: &[u8] = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+#[test]
+fn test_cache_round_trip() {
+    let dir = std::env::temp_dir().join(format!("lib_wfa2_cache_{}", std::process::id()));
+    let cache = AlignmentCache::open(&dir).unwrap();
+    cache.clear().unwrap();
+
+    let aligner = AffineWavefronts::default();
+    assert_eq!(cache.get(&aligner, QUERY, REFERENCE).unwrap(), None);
+
+    let computed = cache.align(&aligner, QUERY, REFERENCE).unwrap();
+    let cached = cache.get(&aligner, QUERY, REFERENCE).unwrap();
+    assert_eq!(cached, Some(computed));
+
+    // A different configuration does not hit the same entry
+    let other = AffineWavefronts::with_penalties(0, 2, 3, 1);
+    assert_ne!(config_fingerprint(&aligner), config_fingerprint(&other));
+    assert_eq!(cache.get(&other, QUERY, REFERENCE).unwrap(), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cache_limits_are_part_of_the_key() {
+    let dir = std::env::temp_dir().join(format!("lib_wfa2_cache_limits_{}", std::process::id()));
+    let cache = AlignmentCache::open(&dir).unwrap();
+    cache.clear().unwrap();
+
+    // A result cut short by the step limit is returned but not stored
+    let mut aligner = AffineWavefronts::default();
+    aligner.set_max_alignment_steps(1);
+    let limited = cache.align(&aligner, QUERY, REFERENCE).unwrap();
+    assert_eq!(limited.status, AlignmentStatus::MaxStepsReached);
+    assert_eq!(cache.get(&aligner, QUERY, REFERENCE).unwrap(), None);
+
+    // Raising the limit changes the key, so it misses the cache
+    let fingerprint = config_fingerprint(&aligner);
+    aligner.set_max_alignment_steps(10_000);
+    assert_ne!(config_fingerprint(&aligner), fingerprint);
+    assert_eq!(cache.get(&aligner, QUERY, REFERENCE).unwrap(), None);
+    let completed = cache.align(&aligner, QUERY, REFERENCE).unwrap();
+    assert_eq!(completed.status, AlignmentStatus::Completed);
+    assert_eq!(
+        cache.get(&aligner, QUERY, REFERENCE).unwrap(),
+        Some(completed)
+    );
+
+    // So do the memory budget and memory mode
+    let mut other = aligner.clone();
+    other.set_max_memory(1 << 30);
+    assert_ne!(config_fingerprint(&other), config_fingerprint(&aligner));
+    let other = aligner
+        .to_builder()
+        .memory_mode(MemoryMode::Low)
+        .build()
+        .unwrap();
+    assert_ne!(config_fingerprint(&other), config_fingerprint(&aligner));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "capi")]
+
+use lib_wfa2::affine_wavefront::{AffineWavefronts, AlignmentStatus};
+use lib_wfa2::capi::*;
+use std::ptr;
+
+const QUERY: &[u8] = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+#[test]
+fn test_capi_align() {
+    let config = wfa2_config_default();
+    unsafe {
+        let aligner = wfa2_aligner_new(&config);
+        assert!(!aligner.is_null());
+        let status = wfa2_align(
+            aligner,
+            QUERY.as_ptr(),
+            QUERY.len(),
+            REFERENCE.as_ptr(),
+            REFERENCE.len(),
+        );
+        assert_eq!(status, WFA2_STATUS_COMPLETED);
+
+        let expected = AffineWavefronts::default();
+        assert_eq!(expected.align(QUERY, REFERENCE), AlignmentStatus::Completed);
+        assert_eq!(wfa2_score(aligner), expected.score());
+
+        let mut len = 0;
+        let cigar = wfa2_cigar(aligner, &mut len);
+        assert_eq!(std::slice::from_raw_parts(cigar, len), expected.cigar());
+
+        // Empty sequences may be passed as null
+        let status = wfa2_align(aligner, ptr::null(), 0, ptr::null(), 0);
+        assert_eq!(status, WFA2_STATUS_COMPLETED);
+        assert_eq!(wfa2_score(aligner), 0);
+        wfa2_aligner_free(aligner);
+    }
+}
+
+#[test]
+fn test_capi_invalid() {
+    unsafe {
+        assert!(wfa2_aligner_new(ptr::null()).is_null());
+        let mut config = wfa2_config_default();
+        config.memory_mode = 7;
+        assert!(wfa2_aligner_new(&config).is_null());
+        let mut config = wfa2_config_default();
+        config.mismatch = -1;
+        assert!(wfa2_aligner_new(&config).is_null());
+
+        let status = wfa2_align(ptr::null_mut(), QUERY.as_ptr(), QUERY.len(), ptr::null(), 0);
+        assert_eq!(status, WFA2_STATUS_UNDEFINED);
+        let mut len = 1;
+        assert!(wfa2_cigar(ptr::null(), &mut len).is_null());
+        assert_eq!(len, 0);
+        wfa2_aligner_free(ptr::null_mut());
+    }
+}
@@ -0,0 +1,393 @@
+use lib_wfa2::affine_wavefront::{
+    Affine2pPenalties, AffinePenalties, AffineWavefronts, Distance, Penalties,
+};
+use lib_wfa2::cigar::{
+    alignments_equivalent, btop, cigar_runs, cigar_string, diff_cigars, expand_cigar,
+    format_alignment, format_alignment_with, left_align_indels, sam_cigar, score_cigar, Cigar,
+    CigarOp, CigarStats, FormatOptions,
+};
+
+#[test]
+fn test_expand_cigar() {
+    assert_eq!(expand_cigar("3M1I2D").unwrap(), b"MMMIDD".to_vec());
+    assert!(expand_cigar("3M2S").is_err());
+    assert!(expand_cigar("M").is_err());
+    assert!(expand_cigar("3M2").is_err());
+}
+
+#[test]
+fn test_cigar_runs() {
+    let runs: Vec<_> = cigar_runs(b"MMM=XIIDD").collect();
+    assert_eq!(
+        runs,
+        vec![
+            (CigarOp::Match, 3),
+            (CigarOp::Equal, 1),
+            (CigarOp::Mismatch, 1),
+            (CigarOp::Insertion, 2),
+            (CigarOp::Deletion, 2),
+        ]
+    );
+    assert!(CigarOp::Insertion.consumes_query() && !CigarOp::Insertion.consumes_target());
+
+    let aligner = AffineWavefronts::default();
+    aligner
+        .align(b"ACGTTACGT", b"ACGTACGA")
+        .ok()
+        .expect("alignment failed");
+    let total: u32 = aligner.cigar_ops().map(|(_, len)| len).sum();
+    assert_eq!(total as usize, aligner.cigar().len());
+    let rebuilt: Vec<u8> = aligner
+        .cigar_ops()
+        .flat_map(|(op, len)| std::iter::repeat(op.to_byte()).take(len as usize))
+        .collect();
+    assert_eq!(rebuilt, aligner.cigar());
+}
+
+#[test]
+fn test_cigar_string() {
+    assert_eq!(cigar_string(b"MMMMXIIIMM"), "4=1X3I2=");
+    assert_eq!(cigar_string(b""), "");
+
+    let aligner = AffineWavefronts::default();
+    aligner
+        .align(b"ACGTACGT", b"ACGTACGT")
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(aligner.cigar_string(), "8=");
+}
+
+#[test]
+fn test_sam_cigar() {
+    assert_eq!(sam_cigar(b"MMMMXIIIMM", true), "5M3I2M");
+    assert_eq!(sam_cigar(b"MMMMXIIIMM", false), "4=1X3I2=");
+    assert_eq!(sam_cigar(b"==XDD", true), "3M2D");
+
+    let aligner = AffineWavefronts::default();
+    aligner
+        .align(b"ACGTACGT", b"ACGAACGT")
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(aligner.cigar_sam(true), "8M");
+    assert_eq!(aligner.cigar_sam(false), "3=1X4=");
+}
+
+#[test]
+fn test_cigar_concat_and_merge() {
+    let parts = ["5M2I", "1I3M", "4M1D"].map(|part| Cigar::parse(part).unwrap());
+    let joined = Cigar::concat(parts);
+    assert_eq!(joined.to_string(), "5M3I7M1D");
+    assert_eq!((joined.query_len(), joined.target_len()), (15, 13));
+    assert_eq!(Cigar::from_compact(&joined.to_compact()), joined);
+
+    // Next chunk starts 2 bases before the end of the previous one
+    let mut stitched = Cigar::parse("10M").unwrap();
+    stitched.merge(&Cigar::parse("5M").unwrap(), 2, 2).unwrap();
+    assert_eq!(stitched.to_string(), "13M");
+
+    // Overlaps ending inside a gap are restored with gaps
+    let mut stitched = Cigar::parse("8M2I").unwrap();
+    stitched.merge(&Cigar::parse("3M").unwrap(), 2, 1).unwrap();
+    assert_eq!(stitched.to_string(), "7M1I3M");
+    assert_eq!((stitched.query_len(), stitched.target_len()), (11, 10));
+
+    assert!(Cigar::parse("3M")
+        .unwrap()
+        .merge(&Cigar::new(), 4, 0)
+        .is_err());
+}
+
+#[test]
+fn test_cigar_slicing() {
+    // Query 0-4 / target 0-4 aligned, query 5-6 inserted, query 7-11 / target
+    // 5-9 aligned, target 10-12 deleted, query 12-14 / target 13-15 aligned
+    let cigar = Cigar::parse("5M2I5M3D3M").unwrap();
+
+    let slice = cigar.slice_ref(3..8).unwrap();
+    assert_eq!(slice.cigar.to_string(), "2M2I3M");
+    assert_eq!((slice.query, slice.target), (3..10, 3..8));
+
+    // Insertions at the interval boundary are left out
+    let slice = cigar.slice_ref(5..8).unwrap();
+    assert_eq!(slice.cigar.to_string(), "3M");
+    assert_eq!((slice.query, slice.target), (7..10, 5..8));
+
+    let slice = cigar.slice_ref(9..15).unwrap();
+    assert_eq!(slice.cigar.to_string(), "1M3D2M");
+    assert_eq!((slice.query, slice.target), (11..14, 9..15));
+
+    let slice = cigar.slice_query(4..13).unwrap();
+    assert_eq!(slice.cigar.to_string(), "1M2I5M3D1M");
+    assert_eq!((slice.query, slice.target), (4..13, 4..14));
+
+    let slice = cigar.slice_ref(4..4).unwrap();
+    assert!(slice.cigar.is_empty());
+    assert_eq!((slice.query, slice.target), (4..4, 4..4));
+
+    assert!(cigar.slice_ref(0..17).is_err());
+}
+
+#[test]
+fn test_left_align_indels() {
+    // Extra T in a homopolymer, placed at its right end
+    let query = b"ACGTTTTGC";
+    let target = b"ACGTTTGC";
+    let aligned = left_align_indels(query, target, b"MMMMMMIMM").unwrap();
+    assert_eq!(aligned, b"===I=====".to_vec());
+
+    // Deleted AC unit of a dinucleotide repeat moves to the start
+    let aligned = left_align_indels(b"ACACGT", b"ACACACGT", b"MMMMDDMM").unwrap();
+    assert_eq!(aligned, b"DD======".to_vec());
+
+    // Gaps do not move across mismatches
+    let aligned = left_align_indels(b"ACGATTGC", b"ACGTTTTGC", b"MMMMMDMMM").unwrap();
+    assert_eq!(aligned, b"===XD====".to_vec());
+
+    let equivalence = alignments_equivalent(
+        query,
+        target,
+        b"MMMMMMIMM",
+        &left_align_indels(query, target, b"MMMMMMIMM").unwrap(),
+        &Distance::GapAffine {
+            mismatch: 4,
+            gap_opening: 6,
+            gap_extension: 2,
+        }
+        .into(),
+    )
+    .unwrap();
+    assert!(equivalence.is_equivalent());
+    assert!(left_align_indels(query, target, b"MMM").is_err());
+}
+
+#[test]
+fn test_btop() {
+    // Mismatch, insertion of C in the query and deletion of TT from it
+    let query = b"ACGTACCGTAAC";
+    let target = b"ACGAACGTAATTC";
+    let cigar = expand_cigar("3M1X2M1I4M2D1M").unwrap();
+    assert_eq!(btop(query, target, &cigar).unwrap(), "3TA2C-4-T-T1");
+    assert!(btop(query, target, b"MMM").is_err());
+
+    let aligner = AffineWavefronts::default();
+    aligner
+        .align(b"ACGTACGT", b"ACGAACGT")
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(aligner.btop(b"ACGTACGT", b"ACGAACGT").unwrap(), "3TA4");
+}
+
+#[test]
+fn test_format_alignment() {
+    let query = b"ACGTACCGTAAC";
+    let target = b"ACGAACGTAATTC";
+    let cigar = expand_cigar("3M1X2M1I4M2D1M").unwrap();
+    assert_eq!(
+        format_alignment(query, target, &cigar, 0).unwrap(),
+        "ACGTACCGTAA--C\n\
+         |||.|| ||||  |\n\
+         ACGAAC-GTAATTC\n"
+    );
+    assert_eq!(
+        format_alignment(query, target, &cigar, 8).unwrap(),
+        "ACGTACCG\n|||.|| |\nACGAAC-G\n\nTAA--C\n|||  |\nTAATTC\n"
+    );
+
+    let aligner = AffineWavefronts::default();
+    aligner
+        .align(b"ACGTACGT", b"ACGAACGT")
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(
+        aligner
+            .format_alignment(b"ACGTACGT", b"ACGAACGT", 80)
+            .unwrap(),
+        "ACGTACGT\n|||.||||\nACGAACGT\n"
+    );
+}
+
+#[test]
+fn test_format_alignment_color() {
+    let query = b"ACGTACCGTAAC";
+    let target = b"ACGAACGTAATTC";
+    let cigar = expand_cigar("3M1X2M1I4M2D1M").unwrap();
+    let plain = FormatOptions {
+        width: 8,
+        color: false,
+    };
+    assert_eq!(
+        format_alignment_with(query, target, &cigar, &plain).unwrap(),
+        format_alignment(query, target, &cigar, 8).unwrap()
+    );
+
+    let colored = FormatOptions {
+        width: 0,
+        color: true,
+    };
+    assert_eq!(
+        format_alignment_with(query, target, &cigar, &colored).unwrap(),
+        "ACG\x1b[31mT\x1b[0mAC\x1b[33mC\x1b[0mGTAA\x1b[33m--\x1b[0mC\n\
+         |||.|| ||||  |\n\
+         ACG\x1b[31mA\x1b[0mAC\x1b[33m-\x1b[0mGTAA\x1b[33mTT\x1b[0mC\n"
+    );
+}
+
+#[test]
+fn test_cigar_stats() {
+    let stats = CigarStats::from_cigar(b"MMM=XIIDMM");
+    assert_eq!(
+        stats,
+        CigarStats {
+            matches: 6,
+            mismatches: 1,
+            insertions: 2,
+            deletions: 1,
+            insertion_events: 1,
+            deletion_events: 1,
+        }
+    );
+    assert_eq!(stats.aligned_length(), 10);
+    assert!((stats.identity() - 0.6).abs() < 1e-9);
+    assert_eq!(CigarStats::default().identity(), 0.0);
+
+    let aligner = AffineWavefronts::default();
+    aligner
+        .align(b"ACGTACGT", b"ACGAACGT")
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(aligner.stats().mismatches, 1);
+    assert_eq!(aligner.stats().matches, 7);
+}
+
+#[test]
+fn test_gap_compressed_and_block_identity() {
+    // 15 matches, 1 mismatch, a 2 bp insertion and a 3 bp deletion
+    let stats = CigarStats::from_cigar(&expand_cigar("10=1X2I5=3D").unwrap());
+    assert_eq!((stats.insertion_events, stats.deletion_events), (1, 1));
+    assert!((stats.gap_compressed_identity() - 15.0 / 18.0).abs() < 1e-9);
+    assert!((stats.block_identity() - 15.0 / 21.0).abs() < 1e-9);
+
+    // Split gaps count once per run
+    let stats = CigarStats::from_cigar(b"MMIMMIIMMDM");
+    assert_eq!((stats.insertion_events, stats.deletion_events), (2, 1));
+    assert!((stats.gap_compressed_identity() - 7.0 / 10.0).abs() < 1e-9);
+    assert!((stats.block_identity() - 7.0 / 11.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_diff_cigars() {
+    let penalties: Penalties = Distance::GapAffine {
+        mismatch: 4,
+        gap_opening: 6,
+        gap_extension: 2,
+    }
+    .into();
+    // The same insertion placed one base apart, then a deletion/insertion pair
+    // in `a` where `b` has a single match
+    let a = b"MMMMIMMMDIMMM";
+    let b = b"MMMMMIMMMMMM";
+
+    let regions = diff_cigars(&penalties, a, b).unwrap();
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions[0].query, 4..6);
+    assert_eq!(regions[0].target, 4..5);
+    assert_eq!(regions[0].cigar_a, b"IM".to_vec());
+    assert_eq!(regions[0].cigar_b, b"MI".to_vec());
+    assert_eq!(regions[0].score_delta(), 0);
+    assert_eq!(regions[1].query, 8..9);
+    assert_eq!(regions[1].target, 7..8);
+    assert_eq!(regions[1].cigar_a, b"DI".to_vec());
+    assert_eq!(regions[1].cigar_b, b"M".to_vec());
+    assert!(regions[1].score_delta() > 0);
+
+    // Identical paths have no divergent regions
+    assert!(diff_cigars(&penalties, a, a).unwrap().is_empty());
+    // Different sequence lengths are rejected
+    assert!(diff_cigars(&penalties, a, b"MMMM").is_err());
+}
+
+#[test]
+fn test_alignments_equivalent() {
+    let penalties: Penalties = Distance::GapAffine {
+        mismatch: 4,
+        gap_opening: 6,
+        gap_extension: 2,
+    }
+    .into();
+    let query = b"GGACACTT";
+    let target = b"GGACACACTT";
+
+    // Deleting either copy of the "AC" repeat is equivalent
+    let a = b"MMDDMMMMMM";
+    let b = b"MMMMDDMMMM";
+    let result = alignments_equivalent(query, target, a, b, &penalties).unwrap();
+    assert!(result.equal_score());
+    assert!(result.is_equivalent());
+
+    // Splitting the deletion costs a second gap opening
+    let c = b"MMDMDMMMMM";
+    let result = alignments_equivalent(query, target, a, c, &penalties).unwrap();
+    assert!(!result.equal_score());
+    assert!(!result.gap_shifts_only);
+
+    // CIGARs that do not span the sequences are rejected
+    assert!(alignments_equivalent(query, target, a, b"MMMM", &penalties).is_err());
+}
+
+#[test]
+fn test_score_cigar() {
+    let cigar = expand_cigar("3M1X2I4M3D").unwrap();
+    let affine = Penalties::GapAffine(AffinePenalties {
+        match_score: 0,
+        mismatch: 4,
+        gap_opening: 6,
+        gap_extension: 2,
+    });
+    // 4 + (6 + 2 * 2) + (6 + 3 * 2)
+    assert_eq!(score_cigar(&cigar, &affine), Some(-26));
+
+    // A match bonus of 1 per aligned base pair
+    let bonus = Penalties::GapAffine(AffinePenalties {
+        match_score: -1,
+        mismatch: 4,
+        gap_opening: 6,
+        gap_extension: 2,
+    });
+    assert_eq!(score_cigar(&cigar, &bonus), Some(7 - 26));
+
+    // The long 3D gap is cheaper under the second piece: 12 + 3 * 1
+    let dual = Penalties::GapAffine2p(Affine2pPenalties {
+        match_score: 0,
+        mismatch: 4,
+        gap_opening1: 6,
+        gap_extension1: 4,
+        gap_opening2: 12,
+        gap_extension2: 1,
+    });
+    assert_eq!(score_cigar(&cigar, &dual), Some(-(4 + 14 + 15)));
+
+    assert_eq!(score_cigar(&cigar, &Penalties::Edit), Some(-6));
+    assert_eq!(
+        score_cigar(
+            &cigar,
+            &Distance::GapAffine {
+                mismatch: 4,
+                gap_opening: 6,
+                gap_extension: 2,
+            }
+            .into()
+        ),
+        Some(-26)
+    );
+
+    // Agrees with WFA2 on its own alignments
+    let aligner = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    aligner
+        .align(b"ACGTACGTTTACGT", b"ACGAACGTACGT")
+        .ok()
+        .expect("alignment failed");
+    assert_eq!(
+        score_cigar(aligner.cigar(), &aligner.get_penalties()),
+        Some(i64::from(aligner.score()))
+    );
+}
@@ -0,0 +1,251 @@
+#![cfg(feature = "cli")]
+
+use lib_wfa2::affine_wavefront::{AffineWavefronts, AffineWavefrontsBuilder, AlignmentStatus};
+use std::process::{Command, Output};
+
+const QUERY: &str = "TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &str = "TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+fn wfa2(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_wfa2"))
+        .args(args)
+        .output()
+        .expect("failed to run wfa2")
+}
+
+fn field(stdout: &str, name: &str) -> String {
+    stdout
+        .lines()
+        .find_map(|line| line.strip_prefix(&format!("{name}\t")))
+        .unwrap_or_else(|| panic!("no {name} in output:\n{stdout}"))
+        .to_string()
+}
+
+#[test]
+fn test_cli_align() {
+    let output = wfa2(&["align", QUERY, REFERENCE]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let aligner = AffineWavefronts::default();
+    assert_eq!(
+        aligner.align(QUERY.as_bytes(), REFERENCE.as_bytes()),
+        AlignmentStatus::Completed
+    );
+    assert_eq!(field(&stdout, "score"), aligner.score().to_string());
+    assert_eq!(field(&stdout, "cigar"), aligner.cigar_string());
+    let identity: f64 = field(&stdout, "identity").parse().unwrap();
+    assert!((identity - aligner.stats().identity()).abs() < 1e-6);
+}
+
+#[test]
+fn test_cli_align_options() {
+    let output = wfa2(&[
+        "align", "-x", "3", "-o", "5", "-e", "1", "-m", "low", "--pretty", QUERY, REFERENCE,
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let aligner = AffineWavefrontsBuilder::new()
+        .penalties(0, 3, 5, 1)
+        .build()
+        .unwrap();
+    assert_eq!(
+        aligner.align(QUERY.as_bytes(), REFERENCE.as_bytes()),
+        AlignmentStatus::Completed
+    );
+    assert_eq!(field(&stdout, "score"), aligner.score().to_string());
+    assert!(stdout.contains(QUERY), "no pretty alignment in:\n{stdout}");
+}
+
+#[test]
+fn test_cli_align_fasta() {
+    let dir = std::env::temp_dir().join(format!("wfa2_cli_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let query = dir.join("query.fa");
+    let target = dir.join("target.fa");
+    std::fs::write(&query, format!(">q\n{}\n{}\n", &QUERY[..16], &QUERY[16..])).unwrap();
+    std::fs::write(&target, format!(">t desc\n{REFERENCE}\n>other\nACGT\n")).unwrap();
+
+    let from_files = wfa2(&["align", query.to_str().unwrap(), target.to_str().unwrap()]);
+    let from_args = wfa2(&["align", QUERY, REFERENCE]);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(from_files.status.success());
+    assert_eq!(from_files.stdout, from_args.stdout);
+}
+
+#[test]
+fn test_cli_errors() {
+    for args in [
+        &["align", QUERY][..],
+        &["align", "--heuristic", "xdrop:100", QUERY, REFERENCE],
+        &["align", "--memory", "tiny", QUERY, REFERENCE],
+        &["align", "-O", "24", QUERY, REFERENCE],
+        &["align", "--bogus", QUERY, REFERENCE],
+        &["frobnicate"],
+    ] {
+        let output = wfa2(args);
+        assert!(!output.status.success(), "{args:?} should fail");
+        assert!(String::from_utf8_lossy(&output.stderr).starts_with("wfa2: "));
+    }
+}
+
+#[test]
+fn test_cli_batch() {
+    let dir = std::env::temp_dir().join(format!("wfa2_batch_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let queries = dir.join("queries.fq");
+    let targets = dir.join("targets.fa");
+    let pairs = dir.join("pairs.tsv");
+    let mut fastq = String::new();
+    let mut fasta = String::new();
+    let mut tsv = String::new();
+    for i in 0..20 {
+        let target = &REFERENCE[i % 5..];
+        fastq.push_str(&format!("@q{i}\n{QUERY}\n+\n{}\n", "I".repeat(QUERY.len())));
+        fasta.push_str(&format!(">t{i}\n{target}\n"));
+        tsv.push_str(&format!("q{i}\t{QUERY}\t{target}\n"));
+    }
+    std::fs::write(&queries, fastq).unwrap();
+    std::fs::write(&targets, fasta).unwrap();
+    std::fs::write(&pairs, tsv).unwrap();
+
+    let from_files = wfa2(&[
+        "batch",
+        "-t",
+        "4",
+        queries.to_str().unwrap(),
+        targets.to_str().unwrap(),
+    ]);
+    let from_tsv = wfa2(&["batch", "--pairs", pairs.to_str().unwrap()]);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(from_files.status.success());
+    assert!(from_tsv.status.success());
+
+    let aligner = AffineWavefronts::default();
+    let from_files = String::from_utf8(from_files.stdout).unwrap();
+    let from_tsv = String::from_utf8(from_tsv.stdout).unwrap();
+    assert_eq!(from_files.lines().count(), 20);
+    for (i, (line, tsv_line)) in from_files.lines().zip(from_tsv.lines()).enumerate() {
+        let target = &REFERENCE[i % 5..];
+        assert_eq!(
+            aligner.align(QUERY.as_bytes(), target.as_bytes()),
+            AlignmentStatus::Completed
+        );
+        let fields: Vec<&str> = line.split('\t').collect();
+        assert_eq!(fields[..2], [format!("q{i}"), format!("t{i}")]);
+        assert_eq!(fields[3], target.len().to_string());
+        assert_eq!(fields[4], "Completed");
+        assert_eq!(fields[5], aligner.score().to_string());
+        assert_eq!(fields[7], aligner.cigar_string());
+
+        let tsv_fields: Vec<&str> = tsv_line.split('\t').collect();
+        assert_eq!(tsv_fields[2..], fields[2..]);
+    }
+}
+
+#[test]
+fn test_cli_batch_errors() {
+    let dir = std::env::temp_dir().join(format!("wfa2_batch_errors_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let queries = dir.join("queries.fa");
+    let targets = dir.join("targets.fa");
+    std::fs::write(&queries, format!(">q1\n{QUERY}\n>q2\n{QUERY}\n")).unwrap();
+    std::fs::write(&targets, format!(">t1\n{REFERENCE}\n")).unwrap();
+    let output = wfa2(&[
+        "batch",
+        queries.to_str().unwrap(),
+        targets.to_str().unwrap(),
+    ]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // The first pair is written before the mismatch is found
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 1);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("different numbers of records"));
+}
+
+#[test]
+fn test_cli_output_formats() {
+    let aligner = AffineWavefronts::default();
+    assert_eq!(
+        aligner.align(QUERY.as_bytes(), REFERENCE.as_bytes()),
+        AlignmentStatus::Completed
+    );
+    let stdout = |format: &str| {
+        let output = wfa2(&["align", "-f", format, QUERY, REFERENCE]);
+        assert!(output.status.success(), "{format} failed");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let tsv = stdout("tsv");
+    let fields: Vec<&str> = tsv.trim_end().split('\t').collect();
+    assert_eq!(fields[..5], ["query", "target", "32", "32", "Completed"]);
+    assert_eq!(fields[7], aligner.cigar_string());
+
+    let paf = stdout("paf");
+    let fields: Vec<&str> = paf.trim_end().split('\t').collect();
+    assert_eq!(
+        fields[..9],
+        ["query", "32", "0", "32", "+", "target", "32", "0", "32"]
+    );
+    assert!(fields.contains(&format!("cg:Z:{}", aligner.cigar_string()).as_str()));
+    assert!(fields.contains(&format!("AS:i:{}", aligner.score()).as_str()));
+
+    let sam = stdout("sam");
+    let lines: Vec<&str> = sam.lines().collect();
+    assert!(lines[0].starts_with("@HD\t"));
+    assert_eq!(lines[1], "@SQ\tSN:target\tLN:32");
+    assert!(lines[2].starts_with("@PG\tID:wfa2"));
+    let fields: Vec<&str> = lines[3].split('\t').collect();
+    assert_eq!(fields[..4], ["query", "0", "target", "1"]);
+    assert_eq!(fields[9], QUERY);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout("json")).unwrap();
+    assert_eq!(json["query"], "query");
+    assert_eq!(json["status"], "Completed");
+    assert_eq!(json["score"], aligner.score());
+    assert_eq!(json["cigar"], aligner.cigar_string());
+
+    let output = wfa2(&["align", "-f", "bam", QUERY, REFERENCE]);
+    assert!(!output.status.success());
+    let output = wfa2(&["align", "-f", "sam", "--pretty", QUERY, REFERENCE]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_batch_output_formats() {
+    let dir = std::env::temp_dir().join(format!("wfa2_batch_formats_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let queries = dir.join("queries.fa");
+    let targets = dir.join("targets.fa");
+    std::fs::write(&queries, format!(">q1\n{QUERY}\n>q2\n{QUERY}\n")).unwrap();
+    std::fs::write(&targets, format!(">t1\n{REFERENCE}\n>t2\n{QUERY}\n")).unwrap();
+    let run = |format: &str| {
+        let output = wfa2(&[
+            "batch",
+            "-f",
+            format,
+            queries.to_str().unwrap(),
+            targets.to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{format} failed");
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let sam = run("sam");
+    let json = run("json");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let header: Vec<&str> = sam.lines().filter(|line| line.starts_with('@')).collect();
+    assert!(header.contains(&"@SQ\tSN:t1\tLN:32"));
+    assert!(header.contains(&"@SQ\tSN:t2\tLN:32"));
+    assert_eq!(sam.lines().count(), header.len() + 2);
+
+    let records: Vec<serde_json::Value> = json
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1]["target"], "t2");
+    assert_eq!(records[1]["score"], 0);
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "clustering")]
+
+use lib_wfa2::clustering::{neighbor_joining, upgma, GuideTree};
+use lib_wfa2::distance_matrix::DistanceMatrix;
+
+fn matrix(names: &[&str], rows: &[&[f64]]) -> DistanceMatrix {
+    let names = names.iter().map(|name| name.to_string()).collect();
+    DistanceMatrix::new(names, rows.concat()).unwrap()
+}
+
+// Path lengths between the leaves below `tree`, and from `tree` to each leaf
+fn tree_distances(tree: &GuideTree, distances: &mut [Vec<f64>]) -> Vec<(usize, f64)> {
+    match tree {
+        GuideTree::Leaf(index) => vec![(*index, 0.0)],
+        GuideTree::Node {
+            left,
+            right,
+            left_length,
+            right_length,
+        } => {
+            let left: Vec<_> = tree_distances(left, distances)
+                .into_iter()
+                .map(|(leaf, depth)| (leaf, depth + left_length))
+                .collect();
+            let right: Vec<_> = tree_distances(right, distances)
+                .into_iter()
+                .map(|(leaf, depth)| (leaf, depth + right_length))
+                .collect();
+            for &(a, depth_a) in &left {
+                for &(b, depth_b) in &right {
+                    distances[a][b] = depth_a + depth_b;
+                    distances[b][a] = depth_a + depth_b;
+                }
+            }
+            left.into_iter().chain(right).collect()
+        }
+    }
+}
+
+fn assert_tree_fits(tree: &GuideTree, matrix: &DistanceMatrix) {
+    let n = matrix.len();
+    let mut distances = vec![vec![0.0; n]; n];
+    tree_distances(tree, &mut distances);
+    for (i, row) in distances.iter().enumerate() {
+        for (j, &distance) in row.iter().enumerate() {
+            assert!((distance - matrix.get(i, j)).abs() < 1e-9, "{i} {j}");
+        }
+    }
+}
+
+#[test]
+fn test_neighbor_joining_recovers_additive_tree() {
+    let matrix = matrix(
+        &["a", "b", "c", "d", "e"],
+        &[
+            &[0.0, 5.0, 9.0, 9.0, 8.0],
+            &[5.0, 0.0, 10.0, 10.0, 9.0],
+            &[9.0, 10.0, 0.0, 8.0, 7.0],
+            &[9.0, 10.0, 8.0, 0.0, 3.0],
+            &[8.0, 9.0, 7.0, 3.0, 0.0],
+        ],
+    );
+    let tree = neighbor_joining(&matrix).unwrap();
+    assert_tree_fits(&tree, &matrix);
+    let mut leaves = tree.leaves();
+    leaves.sort_unstable();
+    assert_eq!(leaves, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_upgma_recovers_ultrametric_tree() {
+    let matrix = matrix(
+        &["a", "b", "c", "d"],
+        &[
+            &[0.0, 2.0, 6.0, 10.0],
+            &[2.0, 0.0, 6.0, 10.0],
+            &[6.0, 6.0, 0.0, 10.0],
+            &[10.0, 10.0, 10.0, 0.0],
+        ],
+    );
+    let tree = upgma(&matrix).unwrap();
+    assert_tree_fits(&tree, &matrix);
+    assert_eq!(tree.to_newick(matrix.names()), "(((a:1,b:1):2,c:3):2,d:5);");
+}
+
+#[test]
+fn test_small_matrices() {
+    let empty = DistanceMatrix::new(Vec::new(), Vec::new()).unwrap();
+    assert!(upgma(&empty).is_none());
+    assert!(neighbor_joining(&empty).is_none());
+
+    let single = matrix(&["a"], &[&[0.0]]);
+    assert_eq!(neighbor_joining(&single), Some(GuideTree::Leaf(0)));
+    assert_eq!(upgma(&single).unwrap().to_newick(single.names()), "a;");
+
+    let pair = matrix(&["a", "b"], &[&[0.0, 4.0], &[4.0, 0.0]]);
+    assert_eq!(
+        neighbor_joining(&pair).unwrap().to_newick(pair.names()),
+        "(a:2,b:2);"
+    );
+}
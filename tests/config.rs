@@ -0,0 +1,149 @@
+use lib_wfa2::affine_wavefront::{
+    AffineWavefronts, AlignerConfig, AlignmentScope, AlignmentSpan, DistanceMetric,
+    HeuristicStrategy, MemoryMode,
+};
+
+fn config() -> AlignerConfig {
+    AlignerConfig {
+        distance_metric: DistanceMetric::GapAffine2p,
+        match_score: 0,
+        mismatch: 5,
+        gap_opening1: 8,
+        gap_extension1: 2,
+        gap_opening2: Some(24),
+        gap_extension2: Some(1),
+        memory_mode: MemoryMode::Medium,
+        heuristics: vec![HeuristicStrategy::XDrop {
+            xdrop: 100,
+            score_steps: 1,
+        }],
+        alignment_scope: AlignmentScope::Alignment,
+        alignment_span: AlignmentSpan::EndsFree {
+            pattern_begin_free: 1,
+            pattern_end_free: 2,
+            text_begin_free: 3,
+            text_end_free: 4,
+        },
+        max_alignment_steps: Some(5000),
+        biwfa_fallback: None,
+        max_memory: Some(1 << 30),
+        band_divergence: Some(0.05),
+        num_threads: None,
+    }
+}
+
+#[test]
+fn test_config_round_trip() {
+    let aligner = config().build();
+    assert_eq!(aligner.config(), config());
+    assert_eq!(aligner.get_max_alignment_steps(), 5000);
+    assert_eq!(aligner.get_max_memory(), 1 << 30);
+}
+
+#[test]
+fn test_config_display() {
+    assert_eq!(
+        config().to_string(),
+        "affine2p(0,5,8,2,24,1) memory=med scope=alignment span=ends-free(1,2,3,4) \
+         heuristic=xdrop:100,1 max-steps=5000 max-memory=1073741824 band-divergence=0.05"
+    );
+    let config = AlignerConfig {
+        distance_metric: DistanceMetric::Edit,
+        heuristics: Vec::new(),
+        alignment_span: AlignmentSpan::End2End,
+        max_alignment_steps: None,
+        max_memory: None,
+        band_divergence: None,
+        ..config()
+    };
+    assert_eq!(
+        config.to_string(),
+        "edit memory=med scope=alignment span=end-to-end"
+    );
+    assert_eq!(
+        HeuristicStrategy::WFAdaptive {
+            min_wavefront_length: 10,
+            max_distance_threshold: 50,
+            score_steps: 1,
+        }
+        .to_string(),
+        "wf-adaptive:10,50,1"
+    );
+}
+
+#[test]
+fn test_config_from_aligner_and_to_builder() {
+    let aligner = AffineWavefronts::with_penalties(0, 3, 5, 1);
+    let config = AlignerConfig::from(&aligner);
+    assert_eq!(config, aligner.config());
+    assert_eq!((config.mismatch, config.gap_opening1), (3, 5));
+
+    // Configurations are plain data: compared, cloned and sent across threads
+    let sent = std::thread::spawn({
+        let config = config.clone();
+        move || config
+    })
+    .join()
+    .unwrap();
+    assert_eq!(sent, config);
+
+    let ultralow = config
+        .to_builder()
+        .memory_mode(MemoryMode::Ultralow)
+        .build()
+        .unwrap();
+    assert_eq!(ultralow.get_memory_mode(), MemoryMode::Ultralow);
+    assert_eq!(ultralow.config().mismatch, 3);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_config_serde() {
+    let json = serde_json::to_string(&config()).unwrap();
+    assert!(json.contains("\"mismatch\":5"));
+    let restored: AlignerConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, config());
+    assert_eq!(restored.build().config(), config());
+}
+
+#[cfg(feature = "config")]
+#[test]
+fn test_config_toml() {
+    use lib_wfa2::affine_wavefront::{AffineWavefrontsBuilder, BuildError};
+
+    let toml = config().to_toml();
+    assert!(toml.contains("mismatch = 5"));
+    assert!(!toml.contains("num_threads"));
+    assert_eq!(AlignerConfig::from_toml(&toml), Ok(config()));
+
+    // Missing fields keep their defaults
+    let builder = AffineWavefrontsBuilder::from_toml(
+        "distance_metric = \"GapAffine2p\"\n\
+         gap_opening2 = 24\n\
+         gap_extension2 = 1\n\
+         memory_mode = \"Ultralow\"\n\
+         heuristics = [{ XDrop = { xdrop = 100, score_steps = 1 } }]\n",
+    )
+    .unwrap();
+    let parsed = AlignerConfig::from(builder);
+    assert_eq!(parsed.memory_mode, MemoryMode::Ultralow);
+    assert_eq!(parsed.gap_opening2, Some(24));
+    assert_eq!(parsed.mismatch, AlignerConfig::default().mismatch);
+
+    let error = AffineWavefrontsBuilder::from_toml("mismatch_penalty = 4").unwrap_err();
+    assert!(error.contains("mismatch_penalty"), "{error}");
+
+    // Values that parse but cannot be built are errors, not panics
+    let undefined = AlignerConfig::from_toml("memory_mode = \"Undefined\"").unwrap();
+    assert_eq!(
+        undefined.try_build().err(),
+        Some(BuildError::UndefinedMemoryMode)
+    );
+
+    let path = std::env::temp_dir().join(format!("lib_wfa2_config_{}.toml", std::process::id()));
+    std::fs::write(&path, &toml).unwrap();
+    let builder = AffineWavefrontsBuilder::from_path(&path).unwrap();
+    assert_eq!(AlignerConfig::from(builder), config());
+    std::fs::remove_file(&path).unwrap();
+    assert!(AffineWavefrontsBuilder::from_path(&path).is_err());
+}
@@ -0,0 +1,46 @@
+use lib_wfa2::affine_wavefront::AffineWavefronts;
+use lib_wfa2::batch::BatchPair;
+use lib_wfa2::deadline::{BudgetedAlignment, DeadlineBatch};
+use std::time::Duration;
+
+fn pairs() -> Vec<BatchPair> {
+    (0..4)
+        .map(|i| BatchPair {
+            id: format!("pair{i}"),
+            query: b"ACGTACGTACGT".to_vec(),
+            target: b"ACGTTCGTACGT".to_vec(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_deadline_batch_within_budget() {
+    let mut aligner = AffineWavefronts::default();
+    let steps = aligner.get_max_alignment_steps();
+    let outcomes = DeadlineBatch::new(Duration::from_secs(10)).run(&mut aligner, &pairs());
+    assert_eq!(outcomes.len(), 4);
+    for outcome in &outcomes {
+        match outcome {
+            BudgetedAlignment::Aligned {
+                result, retried, ..
+            } => {
+                assert!(result.status.is_complete());
+                assert_eq!(result.cigar, b"MMMMXMMMMMMM".to_vec());
+                assert!(!retried);
+            }
+            BudgetedAlignment::TimedOut { .. } => panic!("pair timed out"),
+        }
+    }
+    assert_eq!(aligner.get_max_alignment_steps(), steps);
+}
+
+#[test]
+fn test_deadline_batch_past_deadline() {
+    let mut aligner = AffineWavefronts::default();
+    let outcomes = DeadlineBatch::new(Duration::ZERO)
+        .retry_heuristic(None)
+        .run(&mut aligner, &pairs());
+    assert!(outcomes
+        .iter()
+        .all(|outcome| matches!(outcome, BudgetedAlignment::TimedOut { min_penalty: 0, .. })));
+}
@@ -0,0 +1,63 @@
+use lib_wfa2::affine_wavefront::{AffineWavefronts, AffineWavefrontsBuilder};
+use lib_wfa2::distance_matrix::DistanceMatrix;
+
+fn matrix() -> DistanceMatrix {
+    let names = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let distances = vec![0.0, 4.0, 10.0, 4.0, 0.0, 8.5, 10.0, 8.5, 0.0];
+    DistanceMatrix::new(names, distances).unwrap()
+}
+
+#[test]
+fn test_new_checks_dimensions() {
+    assert!(DistanceMatrix::new(vec!["a".to_string()], vec![0.0, 1.0]).is_err());
+    let matrix = matrix();
+    assert_eq!(matrix.len(), 3);
+    assert_eq!(matrix.get(2, 1), 8.5);
+}
+
+#[test]
+fn test_write_phylip() {
+    let mut out = Vec::new();
+    matrix().write_phylip(&mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "3\na 0 4 10\nb 4 0 8.5\nc 10 8.5 0\n"
+    );
+
+    let names = vec!["two words".to_string()];
+    let matrix = DistanceMatrix::new(names, vec![0.0]).unwrap();
+    assert!(matrix.write_phylip(Vec::new()).is_err());
+}
+
+#[test]
+fn test_write_tsv() {
+    let mut out = Vec::new();
+    matrix().write_tsv(&mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\ta\tb\tc\na\t0\t4\t10\nb\t4\t0\t8.5\nc\t10\t8.5\t0\n"
+    );
+}
+
+#[test]
+fn test_all_vs_all_is_symmetric() {
+    let sequences: Vec<(&str, &[u8])> = vec![
+        ("s1", b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT"),
+        ("s2", b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT"),
+        ("s3", b"TCTTTACTCGCGCGTTGGAGAAATACAATAG"),
+    ];
+    let config = AffineWavefrontsBuilder::new().penalties(0, 4, 6, 2);
+    let matrix = DistanceMatrix::all_vs_all(&config, &sequences, 2).unwrap();
+    let aligner = AffineWavefronts::with_penalties(0, 4, 6, 2);
+    for i in 0..3 {
+        assert_eq!(matrix.get(i, i), 0.0);
+        for j in 0..3 {
+            assert_eq!(matrix.get(i, j), matrix.get(j, i));
+            if i != j {
+                let alignment = aligner.align_owned(sequences[i].1, sequences[j].1);
+                assert_eq!(matrix.get(i, j), -f64::from(alignment.score));
+            }
+        }
+    }
+    assert_eq!(matrix.names(), ["s1", "s2", "s3"]);
+}
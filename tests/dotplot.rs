@@ -0,0 +1,41 @@
+use lib_wfa2::cigar::expand_cigar;
+use lib_wfa2::dotplot::{alignment_path, write_path_tsv};
+
+#[test]
+fn test_alignment_path() {
+    let cigar = expand_cigar("3M1X2I4M3D").unwrap();
+    assert_eq!(
+        alignment_path(&cigar),
+        vec![(0, 0), (3, 3), (4, 4), (6, 4), (10, 8), (10, 11)]
+    );
+    assert_eq!(alignment_path(b""), vec![(0, 0)]);
+}
+
+#[test]
+fn test_write_path_tsv() {
+    let exact = expand_cigar("2M1D2M").unwrap();
+    let heuristic = expand_cigar("4M1D").unwrap();
+    let mut tsv = Vec::new();
+    write_path_tsv(&mut tsv, &[("exact", &exact), ("heuristic", &heuristic)]).unwrap();
+    assert_eq!(
+        String::from_utf8(tsv).unwrap(),
+        "path\tquery\ttarget\n\
+         exact\t0\t0\nexact\t2\t2\nexact\t2\t3\nexact\t4\t5\n\
+         heuristic\t0\t0\nheuristic\t4\t4\nheuristic\t4\t5\n"
+    );
+}
+
+#[cfg(feature = "dotplot-png")]
+#[test]
+fn test_write_path_png() {
+    use lib_wfa2::dotplot::write_path_png;
+
+    let cigar = expand_cigar("10M5D10M").unwrap();
+    let mut png = Vec::new();
+    write_path_png(&mut png, 20, 25, &[&cigar], 50).unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    // IHDR: 50 x 40 pixels, 8-bit RGB
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(&png[16..24], &[0, 0, 0, 50, 0, 0, 0, 40]);
+    assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+}
@@ -0,0 +1,52 @@
+use lib_wfa2::affine_wavefront::Distance;
+use lib_wfa2::fit::{fit_penalties, fit_penalties_from_pairs};
+
+fn cigar(pattern: &str, repeats: usize) -> Vec<u8> {
+    pattern.repeat(repeats).into_bytes()
+}
+
+#[test]
+fn test_fit_penalties_follows_error_profile() {
+    // Mismatch-rich data: mismatches should be cheap relative to gaps
+    let substitutions = cigar("MMMMMMMMMXMMMMMMMMMXMMMMMMMMMMMMMMMMMMMDMMMMMMMMMM", 20);
+    // Indel-rich data (e.g. noisy long reads): gaps should be cheap
+    let indels = cigar("MMMMMMMMMIMMMMMMMMMDMMMMMMMMMIMMMMMMMMMXMMMMMMMMMM", 20);
+
+    let subst = fit_penalties([substitutions.as_slice()], None).unwrap();
+    let indel = fit_penalties([indels.as_slice()], None).unwrap();
+    assert!(subst.mismatch < indel.mismatch);
+    assert!(subst.gap_opening > indel.gap_opening);
+    assert!(subst.long_gap.is_none());
+
+    match indel.to_distance(2) {
+        Distance::GapAffine {
+            mismatch,
+            gap_opening,
+            gap_extension,
+        } => assert!(mismatch > 0 && gap_opening > 0 && gap_extension > 0),
+        other => panic!("expected gap-affine penalties, got {other:?}"),
+    }
+
+    let dual = fit_penalties([indels.as_slice()], Some(10)).unwrap();
+    assert!(matches!(dual.to_distance(2), Distance::GapAffine2p { .. }));
+
+    assert!(fit_penalties(std::iter::empty(), None).is_err());
+}
+
+#[test]
+fn test_fit_penalties_from_pairs() {
+    let target = b"ACGTTGCATGCATGCAAGTCGATCGATGCTAGCTAGCTGATCGTAGCTAGCTAGCATCGATCGA".to_vec();
+    let mut query = target.clone();
+    query[10] = b'A';
+    query[40] = b'C';
+    query.remove(25);
+    let pairs = vec![(query, target)];
+
+    let initial = Distance::GapAffine {
+        mismatch: 4,
+        gap_opening: 6,
+        gap_extension: 2,
+    };
+    let fitted = fit_penalties_from_pairs(&pairs, &initial, 3, None, 2).unwrap();
+    assert!(matches!(fitted, Distance::GapAffine { .. }));
+}
@@ -0,0 +1,36 @@
+use lib_wfa2::affine_wavefront::Distance;
+use lib_wfa2::introns::annotate_introns;
+
+#[test]
+fn test_annotate_long_gaps_as_introns() {
+    // 4 exonic bases, a 10-base gap, a short 2-base deletion, 4 exonic bases
+    let mut cigar = b"MMMM".to_vec();
+    cigar.extend_from_slice(&[b'D'; 10]);
+    cigar.extend_from_slice(b"MMDDMMMM");
+    let target = b"ACGTGTAAAAAAAGACCTTACGT";
+
+    let introns = annotate_introns(&cigar, 5, Some(target));
+    assert_eq!(introns.len(), 1);
+    assert_eq!(introns[0].query_pos, 4);
+    assert_eq!(introns[0].target_start, 4);
+    assert_eq!(introns[0].target_end, 14);
+    assert_eq!(introns[0].len(), 10);
+    assert_eq!(introns[0].canonical, Some(true));
+
+    assert_eq!(annotate_introns(&cigar, 5, None)[0].canonical, None);
+}
+
+#[test]
+fn test_intron_preset_aligns_across_long_gap() {
+    let exon1 = b"ACGTTGCATGCATCGATCGTAGCTAGCTAGGCTA";
+    let exon2 = b"TTGACCGATGCATGCTAGCTAGCATCGATGCAAT";
+    let intron = [b"GT".as_slice(), &[b'C'; 300], b"AG"].concat();
+    let genome = [exon1.as_slice(), &intron, exon2].concat();
+    let cdna = [exon1.as_slice(), exon2].concat();
+
+    let aligner = Distance::long_gap_intron().create_aligner(None, None);
+    let _ = aligner.align(&cdna, &genome);
+    let introns = annotate_introns(aligner.cigar(), 50, Some(&genome));
+    assert_eq!(introns.len(), 1);
+    assert_eq!(introns[0].len(), intron.len());
+}
@@ -0,0 +1,153 @@
+use std::io::Read;
+
+const FASTA: &[u8] = b">q1\nACGTACGT\n>q2\nTTGCA\n";
+
+#[test]
+fn test_reader_plain() {
+    let mut text = String::new();
+    lib_wfa2::io::reader(FASTA)
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    assert_eq!(text.as_bytes(), FASTA);
+
+    // Empty input is not mistaken for anything else
+    let mut reader = lib_wfa2::io::reader(&b""[..]).unwrap();
+    assert_eq!(reader.read(&mut [0; 8]).unwrap(), 0);
+
+    // Plain input read one byte at a time keeps its first bytes
+    let mut slow = Vec::new();
+    lib_wfa2::io::reader(OneByteReader(std::io::Cursor::new(FASTA.to_vec())))
+        .unwrap()
+        .read_to_end(&mut slow)
+        .unwrap();
+    assert_eq!(slow, FASTA);
+}
+
+// Reader handing out one byte per read, like a slow pipe
+struct OneByteReader(std::io::Cursor<Vec<u8>>);
+
+impl Read for OneByteReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(1);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_reader_gzip() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use lib_wfa2::sequence::FastxReader;
+    use std::io::Write;
+
+    let gzip = |data: &[u8]| {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    };
+    let records = |data: Vec<u8>| -> Vec<String> {
+        FastxReader::new(lib_wfa2::io::reader(std::io::Cursor::new(data)).unwrap())
+            .map(|record| record.unwrap().name)
+            .collect()
+    };
+    assert_eq!(records(gzip(FASTA)), ["q1", "q2"]);
+
+    // bgzip files are a series of gzip members
+    let (first, second) = FASTA.split_at(12);
+    let mut bgzip = gzip(first);
+    bgzip.extend(gzip(second));
+    assert_eq!(records(bgzip), ["q1", "q2"]);
+
+    // The magic number is recognized when it arrives over several reads
+    let input = OneByteReader(std::io::Cursor::new(gzip(FASTA)));
+    let slow: Vec<String> = FastxReader::new(lib_wfa2::io::reader(input).unwrap())
+        .map(|record| record.unwrap().name)
+        .collect();
+    assert_eq!(slow, ["q1", "q2"]);
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn test_reader_gzip_needs_feature() {
+    let gzip = [0x1f, 0x8b, 8, 0, 0, 0, 0, 0];
+    let error = lib_wfa2::io::reader(std::io::Cursor::new(gzip))
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_open_missing_file() {
+    let error = lib_wfa2::io::open("/nonexistent/reads.fa.gz")
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    assert!(error.to_string().contains("reads.fa.gz"));
+}
+
+#[test]
+fn test_record_pairs() {
+    use lib_wfa2::batch::BatchPair;
+    use lib_wfa2::io::RecordPairs;
+
+    let targets = b"@t1\nACGA\n+\nIIII\n@t2\nTTGA\n+\nIIII\n";
+    let pairs: Vec<_> = RecordPairs::new(FASTA, &targets[..])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(pairs.len(), 2);
+    assert_eq!(pairs[1].query.name, "q2");
+    assert_eq!(pairs[1].target.name, "t2");
+    assert_eq!(pairs[1].target.quality, Some(b"IIII".to_vec()));
+
+    let batch_pair = BatchPair::from(pairs[0].clone());
+    assert_eq!(batch_pair.id, "q1");
+    assert_eq!(batch_pair.query, b"ACGTACGT".to_vec());
+    assert_eq!(batch_pair.target, b"ACGA".to_vec());
+
+    // The second query has no target
+    let mut pairs = RecordPairs::new(FASTA, &b">t1\nACGA\n"[..]);
+    assert!(pairs.next().unwrap().is_ok());
+    assert!(pairs.next().unwrap().is_err());
+}
+
+#[test]
+fn test_pair_table() {
+    use lib_wfa2::io::PairTable;
+
+    let table = b"p1\tACGT\tACGA\n\nTTGA\tTTCA\n";
+    let pairs: Vec<_> = PairTable::new(&table[..])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(pairs.len(), 2);
+    assert_eq!(pairs[0].query.name, "p1");
+    assert_eq!(pairs[0].target.name, "p1");
+    assert_eq!(pairs[0].target.sequence, b"ACGA".to_vec());
+    // Named after its line number
+    assert_eq!(pairs[1].query.name, "3");
+    assert_eq!(pairs[1].query.sequence, b"TTGA".to_vec());
+
+    let error = PairTable::new(&b"ACGT\n"[..]).next().unwrap().unwrap_err();
+    assert!(error.to_string().contains("Line 1"));
+}
+
+#[test]
+fn test_read_pairs_files() {
+    let dir = std::env::temp_dir().join(format!("lib_wfa2_io_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let queries = dir.join("queries.fa");
+    let targets = dir.join("targets.fa");
+    let table = dir.join("pairs.tsv");
+    std::fs::write(&queries, FASTA).unwrap();
+    std::fs::write(&targets, ">t1\nACGA\n>t2\nTTGA\n").unwrap();
+    std::fs::write(&table, "ACGT\tACGA\n").unwrap();
+
+    let pairs = lib_wfa2::io::read_pairs(&queries, &targets)
+        .unwrap()
+        .count();
+    let table_pairs = lib_wfa2::io::read_pair_table(&table).unwrap().count();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(pairs, 2);
+    assert_eq!(table_pairs, 1);
+}
@@ -0,0 +1,64 @@
+use lib_wfa2::cigar::expand_cigar;
+use lib_wfa2::liftover::CoordinateMap;
+
+#[test]
+fn test_point_projection() {
+    // Alignment starting at query 10 and target 100
+    let cigar = expand_cigar("5M2I3M4D2X").unwrap();
+    let map = CoordinateMap::new(&cigar, 10, 100);
+
+    assert_eq!(map.query_to_ref(10), Some(100));
+    assert_eq!(map.query_to_ref(14), Some(104));
+    assert_eq!(map.query_to_ref(15), None);
+    assert_eq!(map.query_to_ref(16), None);
+    assert_eq!(map.query_to_ref(17), Some(105));
+    assert_eq!(map.query_to_ref(20), Some(112));
+    assert_eq!(map.query_to_ref(21), Some(113));
+    assert_eq!(map.query_to_ref(22), None);
+    assert_eq!(map.query_to_ref(9), None);
+
+    assert_eq!(map.ref_to_query(105), Some(17));
+    assert_eq!(map.ref_to_query(108), None);
+    assert_eq!(map.ref_to_query(111), None);
+    assert_eq!(map.ref_to_query(112), Some(20));
+    assert_eq!(map.ref_to_query(99), None);
+    assert_eq!(map.ref_to_query(114), None);
+
+    for pos in 10..22 {
+        if let Some(target) = map.query_to_ref(pos) {
+            assert_eq!(map.ref_to_query(target), Some(pos));
+        }
+    }
+}
+
+#[test]
+fn test_interval_liftover() {
+    let cigar = expand_cigar("5M2I3M4D2X").unwrap();
+    let map = CoordinateMap::new(&cigar, 10, 100);
+
+    // Within one ungapped block
+    let lifted = map.ref_interval_to_query(101..104);
+    assert_eq!(lifted.blocks, vec![(101..104, 11..14)]);
+    assert!(lifted.is_complete() && !lifted.is_split());
+
+    // Across the insertion: contiguous in the target, split in the query
+    let lifted = map.ref_interval_to_query(103..107);
+    assert_eq!(lifted.blocks, vec![(103..105, 13..15), (105..107, 17..19)]);
+    assert!(lifted.is_complete() && lifted.is_split());
+    assert_eq!(lifted.span(), Some(13..19));
+
+    // Across the deletion and past the end of the alignment
+    let lifted = map.ref_interval_to_query(106..116);
+    assert_eq!(lifted.blocks, vec![(106..108, 18..20), (112..114, 20..22)]);
+    assert_eq!(lifted.unmapped, 6);
+    assert!(!lifted.is_complete() && !lifted.is_split());
+
+    // Query to target: the inserted bases are unmapped
+    let lifted = map.query_interval_to_ref(13..18);
+    assert_eq!(lifted.blocks, vec![(13..15, 103..105), (17..18, 105..106)]);
+    assert_eq!(lifted.unmapped, 2);
+
+    let lifted = map.ref_interval_to_query(0..50);
+    assert!(lifted.blocks.is_empty());
+    assert_eq!((lifted.unmapped, lifted.span()), (50, None));
+}
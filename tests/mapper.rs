@@ -0,0 +1,59 @@
+#![cfg(feature = "mapper")]
+
+use lib_wfa2::mapper::{Mapper, MapperOptions, ReferenceIndex};
+
+fn reference() -> Vec<u8> {
+    let mut state = 7u64;
+    (0..4000)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            b"ACGT"[(state >> 62) as usize]
+        })
+        .collect()
+}
+
+fn reverse_complement(seq: &[u8]) -> Vec<u8> {
+    seq.iter()
+        .rev()
+        .map(|&b| match b {
+            b'A' => b'T',
+            b'C' => b'G',
+            b'G' => b'C',
+            _ => b'A',
+        })
+        .collect()
+}
+
+#[test]
+fn test_map_forward_and_reverse_reads() {
+    let reference = reference();
+    let options = MapperOptions::default();
+    let mut index = ReferenceIndex::new(options.k);
+    index.add("chr1", &reference);
+    let mut mapper = Mapper::new(index, options);
+
+    let read = reference[1000..1400].to_vec();
+    let mapping = mapper.map("fwd", &read).expect("forward read should map");
+    assert!(!mapping.reverse);
+    assert_eq!(mapping.target_start, 1000);
+    assert_eq!(mapping.target_end, 1400);
+    assert_eq!(mapping.matches, 400);
+    assert!(mapping
+        .to_paf()
+        .starts_with("fwd\t400\t0\t400\t+\tchr1\t4000\t1000\t1400"));
+
+    let mapping = mapper
+        .map("rev", &reverse_complement(&read))
+        .expect("reverse read should map");
+    assert!(mapping.reverse);
+    assert_eq!(mapping.target_start, 1000);
+    assert_eq!(mapping.target_end, 1400);
+}
+
+#[test]
+fn test_unrelated_read_is_unmapped() {
+    let mut index = ReferenceIndex::new(15);
+    index.add("chr1", &reference());
+    let mut mapper = Mapper::new(index, MapperOptions::default());
+    assert_eq!(mapper.map("polyA", &[b'A'; 300]), None);
+}
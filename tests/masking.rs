@@ -0,0 +1,96 @@
+use lib_wfa2::affine_wavefront::{AffineWavefronts, AlignmentStatus};
+use lib_wfa2::masking::{
+    align_excluding_target, align_split_on_n, n_runs, Block, StitchedAlignment,
+};
+
+#[test]
+fn test_n_runs() {
+    assert_eq!(n_runs(b"ACNNNGTNAC", 2), vec![2..5]);
+    assert_eq!(n_runs(b"ACNNNGTNAC", 1), vec![2..5, 7..8]);
+}
+
+#[test]
+fn test_split_on_n_gaps() {
+    let aligner = AffineWavefronts::default();
+    // Different N-gap sizes would otherwise cost a long gap
+    let query = b"ACGTACGTNNNNNNNNNNNNNNNNNNNNTTGACCAGT";
+    let target = b"ACGTACGTNNNNNTTGACCAGT";
+
+    let stitched = align_split_on_n(&aligner, query, target, 5).unwrap();
+    assert_eq!(stitched.blocks.len(), 3);
+    assert_eq!(stitched.score(), Some(0));
+    assert!(stitched.is_complete());
+    assert_eq!(
+        stitched.blocks[1],
+        Block::Masked {
+            query: 8..28,
+            target: 8..13,
+        }
+    );
+
+    // Gaps that do not correspond are rejected
+    assert!(align_split_on_n(&aligner, query, b"ACGTACGTTTGACCAGT", 5).is_err());
+}
+
+#[test]
+fn test_align_excluding_target() {
+    let aligner = AffineWavefronts::default();
+    let query = b"ACGTACGTTTGACCAGT";
+    let target = b"ACGTACGTGGGGGGTTGACCAGT";
+
+    let excluded = 8..14;
+    let stitched = align_excluding_target(&aligner, query, target, std::slice::from_ref(&excluded));
+    assert_eq!(stitched.score(), Some(0));
+    assert_eq!(
+        stitched.blocks,
+        vec![
+            Block::Aligned {
+                query: 0..8,
+                target: 0..8,
+                status: AlignmentStatus::Completed,
+                score: Some(0),
+                cigar: b"MMMMMMMM".to_vec(),
+            },
+            Block::Masked {
+                query: 8..8,
+                target: 8..14,
+            },
+            Block::Aligned {
+                query: 8..17,
+                target: 14..23,
+                status: AlignmentStatus::Completed,
+                score: Some(0),
+                cigar: b"MMMMMMMMM".to_vec(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_stitched_score_without_sentinel() {
+    let block = |score| Block::Aligned {
+        query: 0..0,
+        target: 0..0,
+        status: AlignmentStatus::Completed,
+        score,
+        cigar: Vec::new(),
+    };
+    let masked = Block::Masked {
+        query: 0..0,
+        target: 0..4,
+    };
+    let stitched = StitchedAlignment {
+        blocks: vec![block(Some(-4)), masked, block(Some(-6))],
+    };
+    assert_eq!(stitched.score(), Some(-10));
+
+    // A block whose score overflowed makes the sum unknown instead of wrong
+    let stitched = StitchedAlignment {
+        blocks: vec![block(Some(-4)), block(None)],
+    };
+    assert_eq!(stitched.score(), None);
+    let stitched = StitchedAlignment {
+        blocks: vec![block(Some(i64::MIN)), block(Some(-1))],
+    };
+    assert_eq!(stitched.score(), None);
+}
@@ -1,6 +1,6 @@
 use lib_wfa2::affine_wavefront::{
     AffineWavefronts, AffineWavefrontsBuilder, MemoryMode, 
-    HeuristicStrategy, DistanceMetric, AlignmentStatus
+    HeuristicStrategy, DistanceMetric, AlignmentStatus, BiwfaFallback
 };
 
 // Test sequences
@@ -91,7 +91,8 @@ fn test_builder_all_combinations() {
     let aligner1 = AffineWavefrontsBuilder::new()
         .penalties(0, 4, 6, 2)
         .memory_mode(MemoryMode::High)
-        .build();
+        .build()
+        .unwrap();
     
     assert_eq!(aligner1.get_memory_mode(), MemoryMode::High);
     assert_eq!(aligner1.get_distance_metric(), DistanceMetric::GapAffine);
@@ -101,7 +102,8 @@ fn test_builder_all_combinations() {
         .penalties(0, 4, 6, 2)
         .dual_affine_penalties(12, 1)
         .memory_mode(MemoryMode::Ultralow)
-        .build();
+        .build()
+        .unwrap();
     
     assert_eq!(aligner2.get_memory_mode(), MemoryMode::Ultralow);
     assert_eq!(aligner2.get_distance_metric(), DistanceMetric::GapAffine2p);
@@ -111,7 +113,8 @@ fn test_builder_all_combinations() {
         .penalties(0, 4, 6, 2)
         .memory_mode(MemoryMode::Low)
         .heuristic(HeuristicStrategy::BandedStatic { band_min_k: -10, band_max_k: 10 })
-        .build();
+        .build()
+        .unwrap();
     
     assert_eq!(aligner3.get_memory_mode(), MemoryMode::Low);
     let heuristics = aligner3.get_heuristics();
@@ -246,3 +249,39 @@ fn test_score_validity() {
     assert!(score > -100, "Score should be reasonable for one mismatch");
     println!("Score for one mismatch: {}", score);
 }
+
+#[test]
+fn test_biwfa_fallback_for_small_pairs() {
+    let fallback = BiwfaFallback {
+        max_memory: 1 << 20,
+        memory_mode: MemoryMode::High,
+        divergence: 0.1,
+    };
+    let aligner = AffineWavefrontsBuilder::new()
+        .penalties(0, 4, 6, 2)
+        .memory_mode(MemoryMode::Ultralow)
+        .biwfa_fallback(fallback.clone())
+        .build()
+        .unwrap();
+    assert_eq!(aligner.biwfa_fallback(), Some(&fallback));
+
+    let reference = AffineWavefronts::with_penalties_and_memory_mode(0, 4, 6, 2, MemoryMode::High);
+    let _ = reference.align(MED_QUERY, MED_REF);
+
+    // Small pair: aligned by the standard-WFA fallback, same result
+    assert_eq!(aligner.align(MED_QUERY, MED_REF), AlignmentStatus::Completed);
+    assert!(aligner.last_used_fallback());
+    assert_eq!(aligner.score(), reference.score());
+    assert_eq!(aligner.cigar(), reference.cigar());
+
+    // Large pair: estimated beyond the limit, stays on bi-WFA
+    let long_query = generate_long_seq(20_000);
+    let mut long_ref = long_query.clone();
+    long_ref[10_000] = b'N';
+    assert_eq!(aligner.align(&long_query, &long_ref), AlignmentStatus::Completed);
+    assert!(!aligner.last_used_fallback());
+    assert_eq!(aligner.score(), -4);
+
+    // The fallback is kept when deriving variants
+    assert_eq!(aligner.to_builder().build().unwrap().biwfa_fallback(), Some(&fallback));
+}
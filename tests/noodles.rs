@@ -0,0 +1,64 @@
+#![cfg(feature = "noodles")]
+
+use lib_wfa2::affine_wavefront::{Alignment, AlignmentStatus};
+use lib_wfa2::cigar::expand_cigar;
+use lib_wfa2::noodles::to_noodles_cigar;
+use noodles_sam::alignment::record::cigar::{op::Kind, Op};
+use noodles_sam::alignment::RecordBuf;
+
+#[test]
+fn test_noodles_cigar() {
+    let cigar = expand_cigar("3M1X2I2M1D").unwrap();
+    let collapsed = to_noodles_cigar(&cigar, true);
+    assert_eq!(
+        collapsed.as_ref(),
+        [
+            Op::new(Kind::Match, 4),
+            Op::new(Kind::Insertion, 2),
+            Op::new(Kind::Match, 2),
+            Op::new(Kind::Deletion, 1),
+        ]
+    );
+    let extended = to_noodles_cigar(&cigar, false);
+    assert_eq!(extended.as_ref()[0], Op::new(Kind::SequenceMatch, 3));
+    assert_eq!(extended.as_ref()[1], Op::new(Kind::SequenceMismatch, 1));
+}
+
+#[test]
+fn test_populate_record_builder() {
+    let query = b"TTACGTACGG";
+    let alignment = Alignment {
+        status: AlignmentStatus::Completed,
+        score: -4,
+        cigar: expand_cigar("2I3D6M").unwrap(),
+        pattern_end: 8,
+        text_end: 9,
+        elapsed: None,
+    };
+    let record = alignment
+        .populate_record_builder(RecordBuf::builder(), query, 0)
+        .build();
+    assert_eq!(record.alignment_start().map(usize::from), Some(4));
+    assert_eq!(
+        record.cigar().as_ref(),
+        [
+            Op::new(Kind::SoftClip, 2),
+            Op::new(Kind::Match, 6),
+            Op::new(Kind::SoftClip, 2),
+        ]
+    );
+    assert_eq!(record.sequence().as_ref(), query);
+
+    let failed = Alignment {
+        status: AlignmentStatus::MaxStepsReached,
+        score: 0,
+        cigar: Vec::new(),
+        pattern_end: 0,
+        text_end: 0,
+        elapsed: None,
+    };
+    let record = failed
+        .populate_record_builder(RecordBuf::builder(), query, 0)
+        .build();
+    assert!(record.flags().is_unmapped());
+}
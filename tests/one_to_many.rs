@@ -0,0 +1,49 @@
+use lib_wfa2::affine_wavefront::AffineWavefronts;
+
+const PATTERN: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+fn texts() -> Vec<Vec<u8>> {
+    vec![
+        b"TCTATACTGCGCGTTTGGAGAAATAAAATAGTACGGTACCATG".to_vec(),
+        PATTERN.to_vec(),
+        b"TCTATACTGCGCGTTTCGAGAAATAAAATAGT".to_vec(),
+        b"GGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGG".to_vec(),
+    ]
+}
+
+#[test]
+fn test_align_one_to_many_matches_single_alignments() {
+    let aligner = AffineWavefronts::default();
+    let texts = texts();
+    let hits = aligner.align_one_to_many(PATTERN, &texts);
+    assert_eq!(hits.len(), texts.len());
+    for (i, hit) in hits.iter().enumerate() {
+        assert_eq!(hit.index, i);
+        assert_eq!(hit.alignment, aligner.align_owned(PATTERN, &texts[i]));
+    }
+    assert_eq!(hits[1].alignment.score, 0);
+}
+
+#[test]
+fn test_align_one_to_many_best_keeps_top_hits() {
+    let aligner = AffineWavefronts::default();
+    let texts = texts();
+    let best = aligner.align_one_to_many_best(PATTERN, &texts, 2);
+    let indices: Vec<usize> = best.iter().map(|hit| hit.index).collect();
+    assert_eq!(indices, vec![1, 2]);
+    assert_eq!(best[0].alignment.score, 0);
+    assert!(best[1].alignment.score < 0);
+    assert_eq!(
+        best[1].alignment.cigar,
+        aligner.align_owned(PATTERN, &texts[2]).cigar
+    );
+
+    let all = aligner.align_one_to_many_best(PATTERN, &texts, 10);
+    assert_eq!(all.len(), texts.len());
+    assert!(all
+        .windows(2)
+        .all(|w| w[0].alignment.score >= w[1].alignment.score));
+    assert!(aligner
+        .align_one_to_many_best(PATTERN, &texts, 0)
+        .is_empty());
+}
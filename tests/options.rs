@@ -0,0 +1,91 @@
+use lib_wfa2::affine_wavefront::{
+    AffineWavefronts, AlignmentScope, AlignmentSpan, AlignmentStatus,
+};
+use lib_wfa2::options::{AlignOptions, OutputDetail};
+use lib_wfa2::orient::Strand;
+
+const QUERY: &[u8] = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+#[test]
+fn test_align_with_options_is_per_call() {
+    let mut aligner = AffineWavefronts::default();
+    let status = aligner.align(QUERY, REFERENCE);
+    assert_eq!(status, AlignmentStatus::Completed);
+    let (score, cigar) = (aligner.score(), aligner.cigar().to_vec());
+    let steps = aligner.get_max_alignment_steps();
+
+    let options = AlignOptions {
+        span: Some(AlignmentSpan::EndsFree {
+            pattern_begin_free: 4,
+            pattern_end_free: 4,
+            text_begin_free: 4,
+            text_end_free: 4,
+        }),
+        max_steps: Some(1000),
+        detail: OutputDetail::ScoreOnly,
+        ..Default::default()
+    };
+    let result = aligner.align_with_options(QUERY, REFERENCE, &options);
+    assert_eq!(result.strand, Strand::Forward);
+    assert_eq!(result.status, AlignmentStatus::Completed);
+    assert!(result.cigar.is_empty());
+
+    // The aligner's own configuration is untouched
+    assert_eq!(aligner.get_alignment_span(), AlignmentSpan::End2End);
+    assert_eq!(aligner.get_alignment_scope(), AlignmentScope::Alignment);
+    assert_eq!(aligner.get_max_alignment_steps(), steps);
+
+    let result = aligner.align_with_options(QUERY, REFERENCE, &AlignOptions::default());
+    assert_eq!(result.score, score);
+    assert_eq!(result.cigar, cigar);
+}
+
+#[test]
+fn test_align_with_options_records_timing() {
+    let mut aligner = AffineWavefronts::default();
+    let result = aligner.align_with_options(QUERY, REFERENCE, &AlignOptions::default());
+    assert_eq!(result.elapsed, None);
+
+    aligner.set_record_timing(true);
+    let result = aligner.align_with_options(QUERY, REFERENCE, &AlignOptions::default());
+    assert!(result.elapsed.is_some());
+}
+
+#[test]
+fn test_score_only_with_match_bonus_after_full_alignment() {
+    let (query, reference) = (QUERY.repeat(2), REFERENCE.repeat(2));
+    let expected = {
+        let aligner = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+        let _ = aligner.align(&query, &reference);
+        aligner.score()
+    };
+
+    let mut aligner = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    let full = aligner.align_with_options(QUERY, REFERENCE, &AlignOptions::default());
+    assert!(!full.cigar.is_empty());
+    let options = AlignOptions {
+        detail: OutputDetail::ScoreOnly,
+        ..Default::default()
+    };
+    let result = aligner.align_with_options(&query, &reference, &options);
+    assert_eq!(result.status, AlignmentStatus::Completed);
+    assert_eq!(result.score, expected);
+    assert!(result.cigar.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "ends-free alignment needs a zero match score")]
+fn test_ends_free_option_with_match_bonus_panics() {
+    let mut aligner = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    let options = AlignOptions {
+        span: Some(AlignmentSpan::EndsFree {
+            pattern_begin_free: 0,
+            pattern_end_free: 0,
+            text_begin_free: 4,
+            text_end_free: 4,
+        }),
+        ..Default::default()
+    };
+    aligner.align_with_options(QUERY, REFERENCE, &options);
+}
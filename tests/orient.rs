@@ -0,0 +1,46 @@
+use lib_wfa2::affine_wavefront::AffineWavefronts;
+use lib_wfa2::orient::{OrientOptions, Strand};
+use lib_wfa2::sequence::reverse_complement;
+
+const TARGET: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGTACGGTACCATGACTAGGACT";
+
+#[test]
+fn test_auto_orient_picks_reverse_strand() {
+    let aligner = AffineWavefronts::default();
+    let query = reverse_complement(TARGET);
+
+    let alignment = aligner.align_auto_orient(&query, TARGET);
+    assert_eq!(alignment.strand, Strand::Reverse);
+    assert_eq!(alignment.score, 0);
+
+    // Without the k-mer prefilter both strands are tried
+    let options = OrientOptions {
+        prefilter_k: None,
+        ..Default::default()
+    };
+    let alignment = aligner.align_auto_orient_with(&query, TARGET, &options);
+    assert_eq!(alignment.strand, Strand::Reverse);
+}
+
+#[test]
+fn test_auto_orient_keeps_forward_strand() {
+    let aligner = AffineWavefronts::default();
+    let alignment = aligner.align_auto_orient(TARGET, TARGET);
+    assert_eq!(alignment.strand, Strand::Forward);
+    assert_eq!(alignment.score, 0);
+}
+
+#[test]
+fn test_auto_orient_clamps_prefilter_k() {
+    let aligner = AffineWavefronts::default();
+    let query = reverse_complement(TARGET);
+    for k in [0, 33, usize::MAX] {
+        let options = OrientOptions {
+            prefilter_k: Some(k),
+            ..Default::default()
+        };
+        let alignment = aligner.align_auto_orient_with(&query, TARGET, &options);
+        assert_eq!(alignment.strand, Strand::Reverse);
+        assert_eq!(alignment.score, 0);
+    }
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "paf")]
+
+use lib_wfa2::affine_wavefront::{AffineWavefronts, Alignment, AlignmentStatus};
+use lib_wfa2::cigar::expand_cigar;
+use lib_wfa2::paf::{realign_paf, PafRecord};
+use lib_wfa2::sequence::read_fasta;
+use std::collections::HashMap;
+
+#[test]
+fn test_parse_paf_record() {
+    let line = "q1\t100\t10\t90\t-\tt1\t500\t200\t280\t70\t80\t60\ttp:A:P";
+    let record = PafRecord::parse(line).unwrap();
+    assert!(record.reverse);
+    assert_eq!(record.target_start, 200);
+    assert_eq!(record.tags, vec!["tp:A:P".to_string()]);
+    assert_eq!(record.to_string(), line);
+
+    assert!(PafRecord::parse("q1\t100\t10").is_err());
+    assert!(PafRecord::parse(&line.replace("\t-\t", "\t*\t")).is_err());
+}
+
+#[test]
+fn test_realign_paf() {
+    let fasta = b">t1 description\nACGTACGTAC\nGTTTGACCAG\n>t2\nAAAA\n";
+    let targets = read_fasta(&fasta[..]).unwrap();
+    assert_eq!(targets["t1"], b"ACGTACGTACGTTTGACCAG".to_vec());
+
+    // The query carries one mismatch against t1[4..16]
+    let mut queries = HashMap::new();
+    queries.insert("q1".to_string(), b"GGACGTACATTTGA".to_vec());
+
+    let paf = "q1\t14\t2\t14\t+\tt1\t20\t4\t16\t0\t12\t60\tcg:Z:12M\n\
+               q2\t10\t0\t10\t+\tt1\t20\t0\t10\t0\t10\t60\n";
+    let aligner = AffineWavefronts::default();
+    let mut output = Vec::new();
+    let summary = realign_paf(&aligner, &queries, &targets, paf.as_bytes(), &mut output).unwrap();
+    assert_eq!(summary.realigned, 1);
+    assert_eq!(summary.failed, 1);
+
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    let record = PafRecord::parse(lines[0]).unwrap();
+    assert_eq!(record.matches, 11);
+    assert_eq!(record.block_len, 12);
+    assert!(record.tags.contains(&"cg:Z:6=1X5=".to_string()));
+    assert!(!record.tags.contains(&"cg:Z:12M".to_string()));
+    // Unknown query names are passed through
+    assert!(lines[1].starts_with("q2\t"));
+}
+
+#[test]
+fn test_alignment_to_paf() {
+    // Two free query bases, then 8 aligned columns with a mismatch and a
+    // deletion, then a free target base
+    let alignment = Alignment {
+        status: AlignmentStatus::Completed,
+        score: -12,
+        cigar: expand_cigar("2I4M1X1D2M1D").unwrap(),
+        pattern_end: 9,
+        text_end: 9,
+        elapsed: None,
+    };
+    let record = alignment.to_paf("q1", 12, "t1", 100, 40, false);
+    assert_eq!(
+        record.to_string(),
+        "q1\t12\t2\t9\t+\tt1\t100\t40\t48\t6\t8\t255\t\
+         gi:f:0.750000\tbi:f:0.750000\tAS:i:-12\tcg:Z:4=1X1D2="
+    );
+
+    let record = alignment.to_paf("q1", 12, "t1", 100, 40, true);
+    assert_eq!((record.query_start, record.query_end), (3, 10));
+    assert!(record.reverse);
+}
@@ -0,0 +1,146 @@
+use lib_wfa2::affine_wavefront::{
+    AffineWavefronts, AffineWavefrontsBuilder, AlignerConfig, BuildError, MemoryMode,
+};
+use lib_wfa2::batch::BatchOptions;
+use lib_wfa2::pool::{clear_thread_local_aligners, thread_local_aligner, AlignerPool};
+use lib_wfa2::scheduler::MemoryBudget;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const QUERY: &[u8] = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+#[test]
+fn test_aligner_is_send() {
+    let aligner = AffineWavefronts::default();
+    let score = thread::spawn(move || aligner.align_owned(QUERY, REFERENCE).score)
+        .join()
+        .unwrap();
+    assert!(score < 0);
+}
+
+#[test]
+fn test_pool_reuses_aligners_across_threads() {
+    let pool = AlignerPool::new(AffineWavefrontsBuilder::new().penalties(0, 4, 6, 2)).unwrap();
+    let expected = pool.get().align_owned(QUERY, REFERENCE).score;
+    assert_eq!(pool.idle(), 1);
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..10 {
+                    assert_eq!(pool.get().align_owned(QUERY, REFERENCE).score, expected);
+                }
+            });
+        }
+    });
+    assert!((1..=4).contains(&pool.idle()));
+}
+
+#[test]
+fn test_pool_rejects_invalid_config() {
+    let invalid = AffineWavefrontsBuilder::new().memory_mode(MemoryMode::Undefined);
+    assert_eq!(
+        AlignerPool::new(invalid).err(),
+        Some(BuildError::UndefinedMemoryMode)
+    );
+}
+
+#[test]
+fn test_pool_waits_for_memory_budget() {
+    let budget = Arc::new(MemoryBudget::new(1 << 20));
+    let options = BatchOptions {
+        memory_budget: Some(Arc::clone(&budget)),
+        ..BatchOptions::default()
+    };
+    let pool = AlignerPool::new(AffineWavefrontsBuilder::new())
+        .unwrap()
+        .with_options(options);
+    let query = QUERY.repeat(100);
+    let target = REFERENCE.repeat(100);
+
+    // Other work holds the whole budget, so the large pair has to wait
+    let held = budget.acquire(budget.limit());
+    let done = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let worker = scope.spawn(|| {
+            let alignment = pool.align(&query, &target);
+            done.store(true, Ordering::SeqCst);
+            alignment
+        });
+        thread::sleep(Duration::from_millis(200));
+        assert!(!done.load(Ordering::SeqCst));
+
+        drop(held);
+        let alignment = worker.join().unwrap();
+        assert!(alignment.status.is_complete());
+    });
+    assert_eq!(budget.in_use(), 0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_pool_pins_its_threads() {
+    use lib_wfa2::affinity::CorePinning;
+
+    let options = BatchOptions {
+        pinning: CorePinning::Cores(vec![0]),
+        ..BatchOptions::default()
+    };
+    let pool = AlignerPool::new(AffineWavefrontsBuilder::new())
+        .unwrap()
+        .with_options(options);
+    // On its own thread, so the test harness threads are unaffected
+    let allowed = thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                assert!(pool.align(QUERY, REFERENCE).status.is_complete());
+                let status = std::fs::read_to_string("/proc/thread-self/status").unwrap();
+                status
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Cpus_allowed_list:"))
+                    .map(|cores| cores.trim().to_string())
+            })
+            .join()
+            .unwrap()
+    });
+    assert_eq!(allowed.as_deref(), Some("0"));
+}
+
+#[test]
+fn test_thread_local_aligner_is_built_once_per_config() {
+    let config = AlignerConfig::default();
+    let other = AlignerConfig {
+        mismatch: 3,
+        ..AlignerConfig::default()
+    };
+    let first = thread_local_aligner(&config, |aligner| aligner.aligner());
+    assert_eq!(
+        thread_local_aligner(&config, |aligner| aligner.aligner()),
+        first
+    );
+    let score = thread_local_aligner(&other, |aligner| {
+        aligner.align_owned(QUERY, REFERENCE).score
+    });
+    assert_eq!(score, other.build().align_owned(QUERY, REFERENCE).score);
+
+    // Nested use of the same configuration gets a second aligner
+    let nested = thread_local_aligner(&config, |outer| {
+        thread_local_aligner(&config, |inner| inner.aligner() != outer.aligner())
+    });
+    assert!(nested);
+
+    // Other threads have their own aligners
+    let elsewhere =
+        thread::spawn(move || thread_local_aligner(&config, |aligner| aligner.aligner() as usize))
+            .join()
+            .unwrap();
+    assert_ne!(elsewhere, first as usize);
+
+    clear_thread_local_aligners();
+    let config = AlignerConfig::default();
+    let score = thread_local_aligner(&config, |aligner| aligner.align_owned(QUERY, QUERY).score);
+    assert_eq!(score, 0);
+}
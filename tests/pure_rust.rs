@@ -0,0 +1,90 @@
+#![cfg(feature = "pure-rust")]
+
+#[cfg(feature = "native")]
+use lib_wfa2::affine_wavefront::{AffineWavefronts, MemoryMode, Penalties};
+#[cfg(feature = "native")]
+use lib_wfa2::cigar::score_cigar;
+use lib_wfa2::pure_rust::PureAffineWavefronts;
+use lib_wfa2::types::AlignmentStatus;
+
+// Deterministic pairs: a random sequence and a copy with scattered edits,
+// plus some unrelated pairs
+#[cfg(feature = "native")]
+fn pairs() -> Vec<(Vec<u8>, Vec<u8>)> {
+    let mut state = 42u64;
+    let mut next = |bound: u64| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) % bound
+    };
+    let mut pairs = Vec::new();
+    for i in 0..200 {
+        let len = next(80) as usize;
+        let pattern: Vec<u8> = (0..len).map(|_| b"ACGT"[next(4) as usize]).collect();
+        let mut text = pattern.clone();
+        for _ in 0..next(10) {
+            let position = next(text.len() as u64 + 1) as usize;
+            match next(3) {
+                0 if position < text.len() => text[position] = b"ACGT"[next(4) as usize],
+                1 => text.insert(position, b"ACGT"[next(4) as usize]),
+                _ if position < text.len() => {
+                    text.remove(position);
+                }
+                _ => {}
+            }
+        }
+        if i % 5 == 0 {
+            text = (0..next(80)).map(|_| b"ACGT"[next(4) as usize]).collect();
+        }
+        pairs.push((pattern, text));
+    }
+    pairs
+}
+
+#[cfg(feature = "native")]
+#[test]
+fn test_parity_with_wfa2() {
+    for (mismatch, gap_opening, gap_extension) in [(4, 6, 2), (1, 0, 1), (3, 10, 1)] {
+        let wfa2 = AffineWavefronts::with_penalties_and_memory_mode(
+            0,
+            mismatch,
+            gap_opening,
+            gap_extension,
+            MemoryMode::High,
+        );
+        let mut pure =
+            PureAffineWavefronts::with_penalties(mismatch, gap_opening, gap_extension).unwrap();
+        let penalties = Penalties::GapAffine(pure.get_penalties());
+        for (pattern, text) in pairs() {
+            assert_eq!(wfa2.align(&pattern, &text), AlignmentStatus::Completed);
+            assert_eq!(pure.align(&pattern, &text), AlignmentStatus::Completed);
+            assert_eq!(pure.score(), wfa2.score());
+            // The CIGAR may differ between equally good alignments
+            assert_eq!(
+                score_cigar(pure.cigar(), &penalties),
+                Some(i64::from(pure.score()))
+            );
+        }
+    }
+}
+
+#[test]
+fn test_pure_rust_alignment() {
+    let mut aligner = PureAffineWavefronts::default();
+    let alignment = aligner.align_owned(b"ACGTACGT", b"ACGAACGT");
+    assert_eq!(alignment.status, AlignmentStatus::Completed);
+    assert_eq!(alignment.score, -4);
+    assert_eq!(alignment.cigar, b"MMMXMMMM");
+
+    assert_eq!(aligner.align(b"", b"ACG"), AlignmentStatus::Completed);
+    assert_eq!(aligner.cigar(), b"DDD");
+    assert_eq!(aligner.score(), -12);
+
+    aligner.set_max_alignment_steps(4);
+    assert_eq!(
+        aligner.align(b"AAAAAAAA", b"TTTTTTTT"),
+        AlignmentStatus::MaxStepsReached
+    );
+    assert!(PureAffineWavefronts::with_penalties(0, 6, 2).is_err());
+}
@@ -0,0 +1,41 @@
+use lib_wfa2::qv::{QvEstimator, QvOptions};
+
+#[test]
+fn test_qv_per_contig_and_overall() {
+    let mut estimator = QvEstimator::new(QvOptions::default());
+    // 10 assembly bases, one mismatch -> error rate 0.1 -> QV 10
+    estimator.add_alignment("ctg1", b"ACGTACGTAC", b"MMMMXMMMMM");
+    // 10 assembly bases, no errors
+    estimator.add_alignment("ctg2", b"ACGTACGTAC", b"MMMMMMMMMM");
+
+    let ctg1 = estimator.contig("ctg1").unwrap();
+    assert_eq!(ctg1.mismatches, 1);
+    assert!((ctg1.qv() - 10.0).abs() < 1e-9);
+
+    // Error-free contigs are capped by the evidence available
+    let ctg2 = estimator.contig("ctg2").unwrap();
+    assert!((ctg2.qv() - 10.0 * 11f64.log10()).abs() < 1e-9);
+
+    let overall = estimator.overall();
+    assert_eq!(overall.aligned_bases, 20);
+    assert!((overall.error_rate() - 0.05).abs() < 1e-9);
+}
+
+#[test]
+fn test_homopolymer_indels_can_be_excluded() {
+    let cigar = b"MMMDMMMM";
+    let target = b"ACGGGTAC";
+
+    let mut all = QvEstimator::new(QvOptions::default());
+    all.add_alignment("ctg", target, cigar);
+    assert_eq!(all.contig("ctg").unwrap().deletions, 1);
+
+    let mut filtered = QvEstimator::new(QvOptions {
+        exclude_homopolymer_indels: true,
+        ..Default::default()
+    });
+    filtered.add_alignment("ctg", target, cigar);
+    let counts = filtered.contig("ctg").unwrap();
+    assert_eq!(counts.deletions, 0);
+    assert_eq!(counts.excluded_indels, 1);
+}
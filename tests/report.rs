@@ -0,0 +1,41 @@
+#![cfg(feature = "html-report")]
+
+use lib_wfa2::affine_wavefront::{Alignment, AlignmentStatus};
+use lib_wfa2::cigar::expand_cigar;
+use lib_wfa2::report::{write_html_report, ReportEntry, ReportOptions};
+
+#[test]
+fn test_write_html_report() {
+    let alignment = Alignment {
+        status: AlignmentStatus::Completed,
+        score: -20,
+        cigar: expand_cigar("3M1X2M1I4M2D1M").unwrap(),
+        pattern_end: 12,
+        text_end: 13,
+        elapsed: None,
+    };
+    let entry = ReportEntry {
+        name: "read<1>",
+        query: b"ACGTACCGTAAC",
+        target: b"ACGAACGTAATTC",
+        alignment: &alignment,
+    };
+    let mut html = Vec::new();
+    write_html_report(&mut html, "QC & more", &[entry], &ReportOptions::default()).unwrap();
+    let html = String::from_utf8(html).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>QC &amp; more</title>"));
+    assert!(html.contains("<h2>read&lt;1&gt;</h2>"));
+    assert!(html.contains("1 insertions, 1 deletions"));
+    assert!(html.contains("<title>2 bp deletion at column 11</title>"));
+    assert!(html.contains("ACG<span class=\"x\">T</span>AC<span class=\"g\">C</span>GTAA"));
+    assert_eq!(html.matches("<section>").count(), 1);
+
+    let mismatched = ReportEntry {
+        target: b"ACGT",
+        ..entry
+    };
+    let error =
+        write_html_report(Vec::new(), "QC", &[mismatched], &ReportOptions::default()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
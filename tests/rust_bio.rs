@@ -0,0 +1,48 @@
+#![cfg(feature = "rust-bio")]
+
+use bio_types::alignment::{AlignmentMode, AlignmentOperation};
+use lib_wfa2::affine_wavefront::AffineWavefronts;
+use lib_wfa2::rust_bio::to_bio_operations;
+
+#[test]
+fn test_bio_operations() {
+    use AlignmentOperation::*;
+    assert_eq!(
+        to_bio_operations(b"MM=XID"),
+        vec![Match, Match, Match, Subst, Ins, Del]
+    );
+}
+
+#[test]
+fn test_bio_alignment() {
+    let (query, target) = (b"ACGTTACGT", b"ACGAACGT");
+    let aligner = AffineWavefronts::default();
+    let alignment = aligner.align_checked(query, target).unwrap();
+    let bio = alignment.to_bio_alignment(query.len(), target.len());
+
+    assert_eq!(bio.mode, AlignmentMode::Global);
+    assert_eq!(bio.score, alignment.score);
+    assert_eq!((bio.xend, bio.yend), (query.len(), target.len()));
+    assert_eq!(bio.operations.len(), alignment.cigar.len());
+    let substitutions = bio
+        .operations
+        .iter()
+        .filter(|&&op| op == AlignmentOperation::Subst)
+        .count();
+    assert_eq!(substitutions, aligner.stats().mismatches);
+}
+
+#[test]
+fn test_bio_alignment_with_unaligned_ends() {
+    let alignment = AffineWavefronts::default()
+        .align_checked(b"ACGT", b"ACGT")
+        .unwrap();
+    // Alignment of prefixes of longer sequences
+    let bio = alignment.to_bio_alignment(6, 5);
+    assert_eq!(bio.mode, AlignmentMode::Custom);
+    assert_eq!(
+        &bio.operations[4..],
+        &[AlignmentOperation::Xclip(2), AlignmentOperation::Yclip(1)]
+    );
+    assert_eq!(bio.cigar(false), "4=2S");
+}
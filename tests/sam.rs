@@ -0,0 +1,42 @@
+use lib_wfa2::affine_wavefront::{Alignment, AlignmentStatus};
+use lib_wfa2::cigar::expand_cigar;
+
+fn alignment(cigar: &str, status: AlignmentStatus) -> Alignment {
+    let cigar = expand_cigar(cigar).unwrap();
+    Alignment {
+        status,
+        score: -10,
+        pattern_end: cigar.iter().filter(|&&op| op != b'D').count(),
+        text_end: cigar.iter().filter(|&&op| op != b'I').count(),
+        elapsed: None,
+        cigar,
+    }
+}
+
+#[test]
+fn test_sam_record() {
+    let query = b"ACGTACGTAC";
+    let record =
+        alignment("4M1X2I3M", AlignmentStatus::Completed).to_sam_record("read1", query, "chr1", 99);
+    assert_eq!(
+        record,
+        "read1\t0\tchr1\t100\t255\t5M2I3M\t*\t0\t0\tACGTACGTAC\t*\tAS:i:-10\tNM:i:3"
+    );
+}
+
+#[test]
+fn test_sam_record_clips_free_ends() {
+    // Ends-free alignment: free query bases at the start, free target bases
+    // before the alignment and query bases past the end of the CIGAR
+    let query = b"TTACGTACGG";
+    let record =
+        alignment("2I3D6M", AlignmentStatus::Completed).to_sam_record("read1", query, "chr1", 0);
+    let fields: Vec<&str> = record.split('\t').collect();
+    assert_eq!(fields[3], "4");
+    assert_eq!(fields[5], "2S6M2S");
+    assert_eq!(fields[12], "NM:i:0");
+
+    let unmapped =
+        alignment("", AlignmentStatus::MaxStepsReached).to_sam_record("read1", query, "chr1", 0);
+    assert!(unmapped.starts_with("read1\t4\t*\t0\t0\t*"));
+}
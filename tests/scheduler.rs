@@ -0,0 +1,30 @@
+use lib_wfa2::affine_wavefront::MemoryMode;
+use lib_wfa2::scheduler::{estimate_alignment_memory, MemoryBudget};
+
+#[test]
+fn test_memory_estimate_ordering() {
+    let high = estimate_alignment_memory(10_000, 10_000, &MemoryMode::High, 0.05);
+    let low = estimate_alignment_memory(10_000, 10_000, &MemoryMode::Low, 0.05);
+    let ultralow = estimate_alignment_memory(10_000, 10_000, &MemoryMode::Ultralow, 0.05);
+    assert!(high > low);
+    assert!(low > ultralow);
+}
+
+#[test]
+fn test_memory_budget_defers_work() {
+    let budget = MemoryBudget::new(100);
+
+    let first = budget.acquire(60);
+    assert_eq!(budget.in_use(), 60);
+    assert!(budget.try_acquire(60).is_none());
+
+    drop(first);
+    assert_eq!(budget.in_use(), 0);
+
+    // Oversized work is admitted when nothing else is in flight
+    let big = budget
+        .try_acquire(500)
+        .expect("oversized reservation on idle budget");
+    assert!(budget.try_acquire(1).is_none());
+    drop(big);
+}
@@ -0,0 +1,93 @@
+use lib_wfa2::affine_wavefront::{
+    AffineWavefronts, AffineWavefrontsBuilder, AlignError, AlignmentScope, BuildError, PenaltyError,
+};
+use lib_wfa2::score_only::{scores_for_pairs, ScoreAligner};
+
+const QUERY: &[u8] = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+#[test]
+fn test_align_score_matches_full_alignment() {
+    let full = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    full.align(QUERY, REFERENCE).ok().expect("alignment failed");
+
+    let scorer = ScoreAligner::new(AffineWavefrontsBuilder::new().penalties(-1, 4, 6, 2)).unwrap();
+    assert_eq!(
+        scorer.aligner().get_alignment_scope(),
+        AlignmentScope::ComputeScore
+    );
+    assert_eq!(scorer.align_score(QUERY, REFERENCE), Ok(full.score()));
+}
+
+#[test]
+fn test_align_score_with_match_bonus_reuses_aligner() {
+    // Pairs of different lengths, so a restored score depends on the right one
+    let long_query = QUERY.repeat(3);
+    let long_reference = REFERENCE.repeat(3);
+    let pairs: [(&[u8], &[u8]); 3] = [
+        (QUERY, REFERENCE),
+        (&long_query, &long_reference),
+        (b"ACGT", b"AGGT"),
+    ];
+
+    let scorer = ScoreAligner::new(AffineWavefrontsBuilder::new().penalties(-1, 4, 6, 2)).unwrap();
+    for (a, b) in pairs {
+        let full = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+        full.align(a, b).ok().expect("alignment failed");
+        assert_eq!(scorer.align_score(a, b), Ok(full.score()));
+    }
+}
+
+#[test]
+fn test_align_score_reports_failures() {
+    let mut scorer = ScoreAligner::default();
+    assert_eq!(scorer.align_score(QUERY, QUERY), Ok(0));
+
+    scorer.set_max_alignment_steps(1);
+    assert_eq!(
+        scorer.align_score(QUERY, REFERENCE),
+        Err(AlignError::MaxStepsReached)
+    );
+}
+
+#[test]
+fn test_scores_for_pairs() {
+    let sequences: Vec<&[u8]> = vec![
+        QUERY,
+        REFERENCE,
+        b"TCTTTACTCGCGCGTTGGAGAAATACAATAG",
+        b"ACGTACGTACGT",
+    ];
+    let pairs: Vec<(&[u8], &[u8])> = sequences
+        .iter()
+        .flat_map(|&a| sequences.iter().map(move |&b| (a, b)))
+        .collect();
+
+    let config = AffineWavefrontsBuilder::new().penalties(0, 4, 6, 2);
+    let scorer = ScoreAligner::new(config.clone()).unwrap();
+    let expected: Vec<_> = pairs
+        .iter()
+        .map(|&(a, b)| scorer.align_score(a, b))
+        .collect();
+    assert_eq!(scorer.scores_for_pairs(pairs.iter().copied()), expected);
+    assert_eq!(scores_for_pairs(&config, &pairs, 1).unwrap(), expected);
+    assert_eq!(scores_for_pairs(&config, &pairs, 3).unwrap(), expected);
+    assert!(scores_for_pairs(&config, &[], 4).unwrap().is_empty());
+
+    // Diagonal of the distance matrix
+    for i in 0..sequences.len() {
+        assert_eq!(expected[i * sequences.len() + i], Ok(0));
+    }
+}
+
+#[test]
+fn test_invalid_config_is_reported_before_aligning() {
+    let invalid = AffineWavefrontsBuilder::new().penalties(0, 0, 6, 2);
+    let expected = BuildError::Penalties(PenaltyError::NonPositiveMismatch(0));
+    assert_eq!(
+        ScoreAligner::new(invalid.clone()).err(),
+        Some(expected.clone())
+    );
+    let pairs: Vec<(&[u8], &[u8])> = vec![(QUERY, REFERENCE); 4];
+    assert_eq!(scores_for_pairs(&invalid, &pairs, 2), Err(expected));
+}
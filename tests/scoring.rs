@@ -0,0 +1,197 @@
+use lib_wfa2::affine_wavefront::{
+    AffineWavefronts, AlignmentScope, BuildError, Distance, MemoryMode, PenaltyError, ScoreError,
+    SimilarityScores,
+};
+
+const QUERY: &[u8] = b"TCTTTACTCGCGCGTTGGAGAAATACAATAGT";
+const REFERENCE: &[u8] = b"TCTATACTGCGCGTTTGGAGAAATAAAATAGT";
+
+#[test]
+fn test_score_i64_matches_wfa2_score() {
+    let aligner = AffineWavefronts::with_penalties_and_memory_mode(0, 4, 6, 2, MemoryMode::High);
+    let _ = aligner.align(QUERY, REFERENCE);
+
+    assert_eq!(aligner.cigar_score_i64(), Some(i64::from(aligner.score())));
+    assert_eq!(aligner.score_i64(), i64::from(aligner.score()));
+    assert_eq!(aligner.score_checked(), Ok(aligner.score()));
+}
+
+#[test]
+fn test_score_i64_affine2p() {
+    let aligner = AffineWavefronts::with_penalties_affine2p(0, 4, 6, 2, 12, 1);
+    let _ = aligner.align(QUERY, REFERENCE);
+
+    assert_eq!(aligner.score_i64(), i64::from(aligner.score()));
+}
+
+#[test]
+fn test_distance_gcd_reduction_and_scaling() {
+    let distance = Distance::GapAffine {
+        mismatch: 8,
+        gap_opening: 12,
+        gap_extension: 4,
+    };
+    assert_eq!(distance.penalty_gcd(), 4);
+
+    let reduced = distance.reduced();
+    assert_eq!(
+        reduced,
+        Distance::GapAffine {
+            mismatch: 2,
+            gap_opening: 3,
+            gap_extension: 1,
+        }
+    );
+    assert_eq!(reduced.scaled(4), Ok(distance));
+    assert!(reduced.scaled(0).is_err());
+    assert!(reduced.scaled(i32::MAX).is_err());
+
+    // The reduced scheme finds the same alignment with a proportionally smaller score
+    let original = distance.create_aligner(None, None);
+    let cheaper = reduced.create_aligner(None, None);
+    let _ = original.align(QUERY, REFERENCE);
+    let _ = cheaper.align(QUERY, REFERENCE);
+    assert_eq!(original.score(), cheaper.score() * distance.penalty_gcd());
+    assert_eq!(original.cigar(), cheaper.cigar());
+}
+
+#[test]
+fn test_non_zero_match_is_normalized() {
+    // match = -1 means a bonus of 1 per matching base
+    let aligner = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    let normalization = aligner
+        .match_normalization()
+        .expect("non-zero match should be normalized");
+    assert_eq!(normalization.match_bonus(), 1);
+    assert_eq!(
+        aligner.get_distance(),
+        Distance::GapAffine {
+            mismatch: 10,
+            gap_opening: 12,
+            gap_extension: 5,
+        }
+    );
+
+    let _ = aligner.align(b"ACGTACGTACGT", b"ACGTACGTACGT");
+    assert_eq!(aligner.score(), 12);
+
+    // Three matches and one mismatch: 3 * 1 - 4
+    let _ = aligner.align(b"ACGT", b"AGGT");
+    assert_eq!(aligner.score(), -1);
+    assert_eq!(aligner.score_i64(), -1);
+
+    let zero_match = AffineWavefronts::with_penalties(0, 4, 6, 2);
+    assert_eq!(zero_match.match_normalization(), None);
+}
+
+#[test]
+fn test_similarity_scores() {
+    let scores = SimilarityScores {
+        match_score: 1,
+        mismatch: -4,
+        gap_opening: -6,
+        gap_extension: -2,
+        long_gap: None,
+    };
+    let aligner = AffineWavefronts::from_similarity(&scores, MemoryMode::High).unwrap();
+    let reference = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    assert_eq!(aligner.get_distance(), reference.get_distance());
+
+    // Three matches and one mismatch: 3 * 1 - 4
+    let _ = aligner.align(b"ACGT", b"AGGT");
+    assert_eq!(aligner.score(), -1);
+    let _ = aligner.align(b"ACGTACGTACGT", b"ACGTACGTACGT");
+    assert_eq!(aligner.score(), 12);
+
+    let dual = SimilarityScores {
+        long_gap: Some((-24, -1)),
+        ..scores
+    };
+    let aligner = AffineWavefronts::from_similarity(&dual, MemoryMode::Medium).unwrap();
+    assert_eq!(
+        aligner.get_distance(),
+        AffineWavefronts::with_penalties_affine2p(-1, 4, 6, 2, 24, 1).get_distance()
+    );
+
+    // WFA2-style (positive) penalties are rejected
+    let wrong_sign = SimilarityScores {
+        mismatch: 4,
+        ..scores
+    };
+    assert_eq!(
+        AffineWavefronts::from_similarity(&wrong_sign, MemoryMode::High).err(),
+        Some(BuildError::Penalties(PenaltyError::NonPositiveMismatch(-4)))
+    );
+}
+
+#[test]
+fn test_score_checked_consistent_across_memory_modes() {
+    let expected = {
+        let aligner = AffineWavefronts::with_penalties_affine2p_and_memory_mode(
+            0,
+            4,
+            6,
+            2,
+            12,
+            1,
+            MemoryMode::High,
+        );
+        let _ = aligner.align(QUERY, REFERENCE);
+        aligner.score()
+    };
+    for mode in [MemoryMode::Medium, MemoryMode::Low, MemoryMode::Ultralow] {
+        let aligner =
+            AffineWavefronts::with_penalties_affine2p_and_memory_mode(0, 4, 6, 2, 12, 1, mode);
+        let _ = aligner.align(QUERY, REFERENCE);
+        assert_eq!(aligner.score_checked(), Ok(expected));
+    }
+}
+
+#[test]
+fn test_score_checked_without_cigar_and_with_match_bonus() {
+    // Score-only scope: nothing to recompute from, the reported score is used
+    let mut aligner =
+        AffineWavefronts::with_penalties_and_memory_mode(0, 4, 6, 2, MemoryMode::High);
+    aligner.set_alignment_scope(AlignmentScope::ComputeScore);
+    let _ = aligner.align(QUERY, REFERENCE);
+    assert_eq!(aligner.score_checked(), Ok(aligner.score()));
+
+    // Scores restored from the zero-match rewrite agree with the CIGAR
+    let aligner = AffineWavefronts::with_penalties_and_memory_mode(-1, 4, 6, 2, MemoryMode::High);
+    let _ = aligner.align(QUERY, REFERENCE);
+    assert_eq!(aligner.score_checked(), Ok(aligner.score()));
+    assert_eq!(aligner.cigar_score_i64(), Some(i64::from(aligner.score())));
+}
+
+#[test]
+fn test_score_error_messages() {
+    assert_eq!(
+        ScoreError::Overflow(1 << 40).to_string(),
+        "alignment score 1099511627776 overflows i32"
+    );
+    let wrapped = ScoreError::WrappedAround {
+        reported: 5,
+        recomputed: -5,
+    };
+    assert!(wrapped.to_string().contains("wrapped around"));
+}
+
+#[test]
+fn test_match_bonus_score_only_after_full_alignment() {
+    let (long_query, long_reference) = (QUERY.repeat(3), REFERENCE.repeat(3));
+    let expected = {
+        let aligner = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+        let _ = aligner.align(&long_query, &long_reference);
+        aligner.score()
+    };
+
+    // The CIGAR of the first pair is still held during the score-only run
+    let mut aligner = AffineWavefronts::with_penalties(-1, 4, 6, 2);
+    let _ = aligner.align(QUERY, REFERENCE);
+    assert!(!aligner.cigar().is_empty());
+    aligner.set_alignment_scope(AlignmentScope::ComputeScore);
+    let _ = aligner.align(&long_query, &long_reference);
+    assert_eq!(aligner.score(), expected);
+    assert_eq!(aligner.score_checked(), Ok(expected));
+    assert_eq!(aligner.cigar_score_i64(), None);
+}
@@ -0,0 +1,33 @@
+use lib_wfa2::sequence::{FastxReader, FastxRecord};
+
+#[test]
+fn test_fastx_reader() {
+    let data = b">a description\nACG\nTT\n\n>b\nGG\n";
+    let records: Vec<FastxRecord> = FastxReader::new(&data[..])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].name, "a");
+    assert_eq!(records[0].sequence, b"ACGTT".to_vec());
+    assert_eq!(records[0].quality, None);
+    assert_eq!(records[1].sequence, b"GG".to_vec());
+
+    let data = b"@r1 extra\nACGT\n+\nIIII\n@r2\nAC\n+r2\n#I\n";
+    let records: Vec<FastxRecord> = FastxReader::new(&data[..])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].name, "r1");
+    assert_eq!(records[0].quality, Some(b"IIII".to_vec()));
+    assert_eq!(records[1].sequence, b"AC".to_vec());
+
+    assert!(FastxReader::new(&b""[..]).next().is_none());
+}
+
+#[test]
+fn test_fastx_reader_errors() {
+    for data in [&b"ACGT\n"[..], b"@r1\nACGT\n+\nIII\n", b"@r1\nACGT\n"] {
+        let first = FastxReader::new(data).next().unwrap();
+        assert!(first.is_err(), "{:?}", String::from_utf8_lossy(data));
+    }
+}
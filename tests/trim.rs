@@ -0,0 +1,75 @@
+use lib_wfa2::affine_wavefront::Distance;
+use lib_wfa2::trim::{soft_trim, trim_low_identity_ends, SoftTrimOptions};
+
+#[test]
+fn test_trim_ragged_ends() {
+    // Noisy 4-op head and tail around a clean core
+    let cigar = b"XIXDMMMMMMMMMMXXIX";
+    let trimmed = trim_low_identity_ends(cigar, 0.8, 5);
+
+    assert_eq!(trimmed.cigar, b"MMMMMMMMMM");
+    assert_eq!(trimmed.query_start, 3);
+    assert_eq!(trimmed.target_start, 3);
+    assert_eq!(trimmed.query_end, 13);
+    assert_eq!(trimmed.target_end, 13);
+    assert_eq!(trimmed.query_len, 17);
+
+    let clipped = trimmed.with_soft_clips();
+    assert_eq!(clipped, b"SSSMMMMMMMMMMSSSS");
+}
+
+#[test]
+fn test_trim_keeps_clean_alignment() {
+    let cigar = b"MMMMMXMMMMM";
+    let trimmed = trim_low_identity_ends(cigar, 0.5, 4);
+    assert_eq!(trimmed.cigar, cigar);
+    assert_eq!(trimmed.query_start, 0);
+    assert_eq!(trimmed.query_end, 11);
+}
+
+#[test]
+fn test_trim_everything_when_no_window_qualifies() {
+    let trimmed = trim_low_identity_ends(b"XXXXIDXX", 0.9, 3);
+    assert!(trimmed.is_empty());
+    assert_eq!(trimmed.with_soft_clips(), b"SSSSSSS");
+}
+
+#[test]
+fn test_soft_trim_by_base_quality() {
+    // Identical bases, but the last four were called with low quality
+    let cigar = b"MMMMMMMMMMMM";
+    let qualities = [30, 30, 30, 30, 30, 30, 30, 30, 5, 5, 5, 5];
+    let options = SoftTrimOptions {
+        window: 4,
+        min_identity: None,
+        min_base_quality: Some(20.0),
+        ..Default::default()
+    };
+    let trimmed = soft_trim(cigar, Some(&qualities), &options).unwrap();
+    assert_eq!(trimmed.clipped_start(), 0);
+    assert_eq!(trimmed.clipped_end(), 3);
+    assert_eq!(trimmed.with_soft_clips(), b"MMMMMMMMMSSS");
+
+    assert!(soft_trim(cigar, None, &options).is_err());
+    assert!(soft_trim(cigar, Some(&qualities[1..]), &options).is_err());
+}
+
+#[test]
+fn test_soft_trim_by_score_density() {
+    let cigar = b"DDDDMMMMMMMMMMMM";
+    let penalties = Distance::GapAffine {
+        mismatch: 4,
+        gap_opening: 6,
+        gap_extension: 2,
+    };
+    let options = SoftTrimOptions {
+        window: 4,
+        min_identity: None,
+        min_score_density: Some((penalties, -1.0)),
+        ..Default::default()
+    };
+    let trimmed = soft_trim(cigar, None, &options).unwrap();
+    assert_eq!(trimmed.cigar, b"MMMMMMMMMMMM");
+    assert_eq!(trimmed.target_start, 4);
+    assert_eq!(trimmed.clipped_start(), 0);
+}
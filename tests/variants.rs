@@ -0,0 +1,48 @@
+use lib_wfa2::cigar::expand_cigar;
+use lib_wfa2::variants::{extract_variants, vcf_record, write_vcf, Variant};
+
+fn variant(pos: usize, reference: &[u8], alternate: &[u8]) -> Variant {
+    Variant {
+        pos,
+        reference: reference.to_vec(),
+        alternate: alternate.to_vec(),
+    }
+}
+
+#[test]
+fn test_extract_variants() {
+    // SNV at 2, insertion of GG after 4, deletion of TT at 7-8
+    let reference = b"ACGTACGTTAC";
+    let query = b"ACTTAGGCGAC";
+    let cigar = expand_cigar("5M2I2M2D2M").unwrap();
+    let variants = extract_variants(query, reference, &cigar).unwrap();
+    assert_eq!(
+        variants,
+        vec![
+            variant(2, b"G", b"T"),
+            variant(4, b"A", b"AGG"),
+            variant(6, b"GTT", b"G"),
+        ]
+    );
+
+    // Deletion at the very start is anchored on the following base
+    let variants = extract_variants(b"GT", b"ACGT", &expand_cigar("2D2M").unwrap()).unwrap();
+    assert_eq!(variants, vec![variant(0, b"ACG", b"G")]);
+}
+
+#[test]
+fn test_vcf_output() {
+    let snv = variant(2, b"G", b"T");
+    assert_eq!(
+        vcf_record("chr1", 100, &snv, Some(-4)),
+        "chr1\t103\t.\tG\tT\t.\t.\tAS=-4"
+    );
+
+    let mut out = Vec::new();
+    write_vcf(&mut out, "chr1", 0, &[snv], None).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "##fileformat=VCFv4.2");
+    assert!(lines[2].starts_with("#CHROM\tPOS"));
+    assert_eq!(lines[3], "chr1\t3\t.\tG\tT\t.\t.\t.");
+}